@@ -1,8 +1,10 @@
 use crate::error::AppResult;
 use crate::state::AppState;
+use serde::Serialize;
 use serde_json::Value;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
 
 #[tauri::command]
 pub async fn save_token_secure(
@@ -101,6 +103,7 @@ pub async fn init_token_from_storage(
     if let Some(value) = store.get("slack_token") {
         if let Some(token) = value.as_str() {
             state.set_token(token.to_string()).await?;
+            spawn_warm_caches(app.clone());
             return Ok(true);
         }
     }
@@ -111,20 +114,49 @@ pub async fn init_token_from_storage(
 // Get the current user ID
 #[tauri::command]
 pub async fn get_current_user_id(state: State<'_, AppState>) -> AppResult<Option<String>> {
-    // First check if we have it cached
-    if let Some(user_id) = state.get_user_id().await {
-        return Ok(Some(user_id));
-    }
-    
-    // If not cached, try to get it from the Slack API
+    // Cached after the first auth.test, so this is normally free
+    state.current_user_id().await
+}
+
+/// Delete the user cache saved between runs; names are re-fetched as needed
+#[tauri::command]
+pub async fn clear_user_cache_disk(state: State<'_, AppState>) -> AppResult<()> {
+    state.clear_user_cache_disk().await
+}
+
+/// Payload of the `caches-ready` event
+#[derive(Debug, Clone, Serialize)]
+pub struct CachesReady {
+    pub users: usize,
+    pub channels: usize,
+}
+
+/// Prefetch users.list and conversations.list in parallel so the first
+/// search resolves names from cache, then emit `caches-ready`
+#[tauri::command]
+pub async fn warm_caches(app: AppHandle, state: State<'_, AppState>) -> AppResult<CachesReady> {
     let client = state.get_client().await?;
-    match client.test_auth().await {
-        Ok((true, Some(user_id))) => {
-            state.set_user_id(user_id.clone()).await;
-            Ok(Some(user_id))
-        }
-        Ok((true, None)) => Ok(None),
-        Ok((false, _)) => Err(crate::error::AppError::AuthError("Authentication failed".to_string())),
-        Err(e) => Err(crate::error::AppError::NetworkError(e.to_string())),
+    let (users, channels) = state
+        .warm_caches(
+            || async { Ok(client.get_users().await?) },
+            || async { Ok(client.get_channels().await?) },
+        )
+        .await?;
+
+    info!("Warmed caches: {} users, {} channels", users, channels);
+    let ready = CachesReady { users, channels };
+    if let Err(e) = app.emit("caches-ready", &ready) {
+        warn!("Failed to emit caches-ready: {}", e);
     }
+    Ok(ready)
+}
+
+/// Warm the caches in the background once a token is loaded
+pub(crate) fn spawn_warm_caches(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if let Err(e) = warm_caches(app.clone(), state).await {
+            warn!("Background cache warm-up failed: {}", e);
+        }
+    });
 }
@@ -0,0 +1,109 @@
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{Read, Write};
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
+
+const CACHE_SETTINGS_STORE: &str = "cache.dat";
+const COMPRESS_CACHES_KEY: &str = "compress_caches";
+/// Key of the wrapper object holding a base64-encoded gzip payload
+const GZIP_KEY: &str = "gzip";
+
+/// Whether persisted caches are written gzip-compressed (off by default)
+pub fn compression_enabled(app: &AppHandle) -> bool {
+    app.store(CACHE_SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(COMPRESS_CACHES_KEY))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+pub async fn get_cache_compression(app: AppHandle) -> AppResult<bool> {
+    Ok(compression_enabled(&app))
+}
+
+/// Toggle compression for caches written from now on; existing entries load either way
+#[tauri::command]
+pub async fn set_cache_compression(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> AppResult<()> {
+    let store = app.store(CACHE_SETTINGS_STORE)?;
+    store.set(COMPRESS_CACHES_KEY, Value::from(enabled));
+    store.save()?;
+    state.set_user_cache_compression(enabled);
+    Ok(())
+}
+
+/// Serialize a cache for the store, optionally as `{"gzip": "<base64>"}`
+pub fn encode_cache<T: Serialize>(value: &T, compress: bool) -> AppResult<Value> {
+    if !compress {
+        return Ok(serde_json::to_value(value)?);
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(value)?)?;
+    let compressed = encoder.finish()?;
+
+    let mut wrapper = serde_json::Map::new();
+    wrapper.insert(GZIP_KEY.to_string(), Value::String(BASE64.encode(compressed)));
+    Ok(Value::Object(wrapper))
+}
+
+/// Read a cache written by `encode_cache`, compressed or not
+pub fn decode_cache<T: DeserializeOwned>(value: Value) -> AppResult<T> {
+    let payload = match value.as_object().map(|map| (map.len(), map.get(GZIP_KEY))) {
+        Some((1, Some(Value::String(encoded)))) => encoded.clone(),
+        _ => return Ok(serde_json::from_value(value)?),
+    };
+
+    let compressed = BASE64
+        .decode(payload)
+        .map_err(|e| AppError::ParseError(format!("Invalid compressed cache: {}", e)))?;
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice()).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CachedEmoji;
+    use std::collections::HashMap;
+
+    fn sample_emoji() -> CachedEmoji {
+        let emoji = (0..2000)
+            .map(|i| (format!("emoji_{}", i), format!("https://emoji.slack-edge.com/T1/emoji_{}/abc.png", i)))
+            .chain(std::iter::once(("ship".to_string(), "alias:shipit".to_string())))
+            .collect::<HashMap<_, _>>();
+        CachedEmoji {
+            emoji,
+            cached_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_compressed_cache_round_trip() {
+        let cached = sample_emoji();
+
+        let compressed = encode_cache(&cached, true).unwrap();
+        let plain = encode_cache(&cached, false).unwrap();
+        assert!(compressed.get(GZIP_KEY).is_some());
+        assert!(compressed.to_string().len() < plain.to_string().len());
+
+        let from_compressed: CachedEmoji = decode_cache(compressed).unwrap();
+        let from_plain: CachedEmoji = decode_cache(plain).unwrap();
+        assert_eq!(from_compressed.emoji, cached.emoji);
+        assert_eq!(from_compressed.cached_at, cached.cached_at);
+        assert_eq!(from_plain.emoji, from_compressed.emoji);
+    }
+}
@@ -1,8 +1,15 @@
-use crate::error::AppResult;
-use crate::slack::models::{SlackConversation, SlackMessage};
-use crate::state::AppState;
+use crate::error::{AppError, AppResult, SlackApiError};
+use crate::slack::models::{
+    capped_reactions, Message, SearchResult, SlackBookmark, SlackConversation, SlackMessage,
+};
+use crate::slack::parser::{build_slack_url, replace_user_mentions, resolve_emoji_aliases, MatchMode};
+use crate::slack::{HistoryOptions, ThreadReplies};
+use crate::state::{AppState, CachedUser, DmSearchCacheKey};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{AppHandle, State};
+use chrono::FixedOffset;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_store::StoreExt;
 use tracing::{error, info, warn};
 
@@ -37,6 +44,232 @@ pub async fn get_favorite_channels(app: AppHandle) -> AppResult<Vec<String>> {
     Ok(Vec::new())
 }
 
+#[tauri::command]
+pub async fn save_low_reaction_channels(app: AppHandle, channels: Vec<String>) -> AppResult<()> {
+    info!("Saving {} low-reaction channels", channels.len());
+
+    let store = app.store("channels.dat")?;
+    store.set("low_reaction_channels", Value::from(channels));
+    store.save()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_low_reaction_channels(app: AppHandle) -> AppResult<Vec<String>> {
+    let mut channels: Vec<String> = low_reaction_channel_ids(&app)?.into_iter().collect();
+    channels.sort();
+    Ok(channels)
+}
+
+/// Channels the user flagged as rarely getting reactions, e.g. CI or alert integrations
+pub(crate) fn low_reaction_channel_ids(app: &AppHandle) -> AppResult<HashSet<String>> {
+    let store = app.store("channels.dat")?;
+
+    let channels = store
+        .get("low_reaction_channels")
+        .and_then(|value| {
+            value.as_array().map(|ids| {
+                ids.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect::<HashSet<_>>()
+            })
+        })
+        .unwrap_or_default();
+
+    Ok(channels)
+}
+
+/// Channels muted in this client, whether or not Slack accepted the mute too
+pub(crate) fn local_muted_channel_ids(app: &AppHandle) -> AppResult<HashSet<String>> {
+    let store = app.store("channels.dat")?;
+
+    let channels = store
+        .get("muted_channels")
+        .and_then(|value| {
+            value.as_array().map(|ids| {
+                ids.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect::<HashSet<_>>()
+            })
+        })
+        .unwrap_or_default();
+
+    Ok(channels)
+}
+
+/// Outcome of `set_channel_muted`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MuteResult {
+    pub channel: String,
+    pub muted: bool,
+    /// Slack's own mute list was updated; `false` means this client only
+    pub synced: bool,
+}
+
+/// Record the mute locally and try to mirror it into Slack's preferences
+///
+/// The local set is always updated so catch-up and live mode honour it right
+/// away. `sync` gets the channel's new muted state; if it fails the mute
+/// stays local-only.
+async fn apply_channel_mute<F, Fut>(
+    local: &mut HashSet<String>,
+    channel: &str,
+    muted: bool,
+    sync: F,
+) -> MuteResult
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<()>>,
+{
+    if muted {
+        local.insert(channel.to_string());
+    } else {
+        local.remove(channel);
+    }
+
+    let synced = match sync().await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Slack mute sync unavailable for {}, keeping it local: {}", channel, e);
+            false
+        }
+    };
+
+    MuteResult {
+        channel: channel.to_string(),
+        muted,
+        synced,
+    }
+}
+
+/// Mute or unmute a channel, in Slack where the token allows it and locally always
+#[tauri::command]
+pub async fn set_channel_muted(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    channel: String,
+    muted: bool,
+) -> AppResult<MuteResult> {
+    info!("{} channel {}", if muted { "Muting" } else { "Unmuting" }, channel);
+
+    let mut local = local_muted_channel_ids(&app)?;
+
+    let result = apply_channel_mute(&mut local, &channel, muted, || async {
+        // Without a usable client the mute simply stays local
+        let client = state.get_client().await?;
+        let mut remote = client.get_muted_channels().await?;
+        remote.retain(|id| id != &channel);
+        if muted {
+            remote.push(channel.clone());
+        }
+        client.set_muted_channels(&remote).await
+    })
+    .await;
+
+    let mut ids: Vec<String> = local.into_iter().collect();
+    ids.sort();
+    let store = app.store("channels.dat")?;
+    store.set("muted_channels", Value::from(ids));
+    store.save()?;
+    state.set_channel_muted_flag(&channel, muted).await;
+
+    Ok(result)
+}
+
+/// Join favorite channel IDs into the comma-separated form used by multi-channel search
+///
+/// Blank and repeated IDs are dropped, keeping the saved favorites order.
+fn favorites_channel_param(favorites: &[String]) -> Option<String> {
+    let mut seen = HashSet::new();
+    let ids: Vec<&str> = favorites
+        .iter()
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty() && seen.insert(*id))
+        .collect();
+
+    if ids.is_empty() {
+        None
+    } else {
+        Some(ids.join(","))
+    }
+}
+
+/// Drop messages that appear more than once (same channel and ts)
+fn dedupe_messages(messages: Vec<Message>) -> Vec<Message> {
+    let mut seen = HashSet::new();
+    messages
+        .into_iter()
+        .filter(|msg| seen.insert((msg.channel.clone(), msg.ts.clone())))
+        .collect()
+}
+
+/// Search all favorite channels at once, as if they were a single channel
+#[tauri::command]
+pub async fn search_favorites(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    query: String,
+    user: Option<String>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    limit: Option<usize>,
+    force_refresh: Option<bool>,
+    has_files: Option<bool>,
+    file_extensions: Option<Vec<String>>,
+    sort: Option<String>,
+) -> AppResult<SearchResult> {
+    let favorites = get_favorite_channels(app.clone()).await?;
+
+    let Some(channel_param) = favorites_channel_param(&favorites) else {
+        info!("No favorite channels to search");
+        return Ok(SearchResult {
+            messages: Vec::new(),
+            total: 0,
+            query,
+            execution_time_ms: 0,
+            result_reason: None,
+            truncated_at_api_limit: false,
+            truncated_by_slack: false,
+            partial_enrichment: false,
+            failed_channels: Vec::new(),
+        });
+    };
+
+    info!("Searching {} favorite channels", favorites.len());
+
+    let mut result = crate::commands::search::search_messages(
+        app,
+        query,
+        Some(channel_param),
+        user,
+        from_date,
+        to_date,
+        limit,
+        force_refresh,
+        None,
+        has_files,
+        file_extensions,
+        None,
+        sort,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        state,
+    )
+    .await?;
+
+    result.messages = dedupe_messages(result.messages);
+    result.total = result.messages.len();
+
+    Ok(result)
+}
+
 #[tauri::command]
 pub async fn save_recent_channels(app: AppHandle, recent: Vec<String>) -> AppResult<()> {
     info!("Saving {} recent channels", recent.len());
@@ -68,6 +301,550 @@ pub async fn get_recent_channels(app: AppHandle) -> AppResult<Vec<String>> {
     Ok(Vec::new())
 }
 
+/// Local-only unread state for a channel (Slack's read cursor is untouched)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocalUnread {
+    pub channel_id: String,
+    pub last_read_ts: Option<String>,
+    pub unread_count: usize,
+    pub first_unread_ts: Option<String>,
+}
+
+fn ts_value(ts: &str) -> f64 {
+    ts.parse::<f64>().unwrap_or(0.0)
+}
+
+/// Count messages newer than the local read cursor
+///
+/// Without a cursor every message counts as unread.
+fn compute_local_unread(
+    channel_id: &str,
+    messages: &[SlackMessage],
+    last_read_ts: Option<&str>,
+) -> LocalUnread {
+    let cursor = last_read_ts.map(ts_value);
+    let mut unread: Vec<&SlackMessage> = messages
+        .iter()
+        .filter(|msg| cursor.map_or(true, |c| ts_value(&msg.ts) > c))
+        .collect();
+    unread.sort_by(|a, b| {
+        ts_value(&a.ts)
+            .partial_cmp(&ts_value(&b.ts))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    LocalUnread {
+        channel_id: channel_id.to_string(),
+        last_read_ts: last_read_ts.map(String::from),
+        unread_count: unread.len(),
+        first_unread_ts: unread.first().map(|msg| msg.ts.clone()),
+    }
+}
+
+fn local_read_from_value(value: &Value) -> std::collections::HashMap<String, String> {
+    value
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .filter_map(|(channel, ts)| ts.as_str().map(|ts| (channel.clone(), ts.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn local_read_to_value(cursors: &std::collections::HashMap<String, String>) -> Value {
+    Value::Object(
+        cursors
+            .iter()
+            .map(|(channel, ts)| (channel.clone(), Value::String(ts.clone())))
+            .collect(),
+    )
+}
+
+/// Get the local read cursors, loading them from the store on first use
+async fn local_read_cursors(
+    app: &AppHandle,
+    state: &AppState,
+) -> AppResult<std::collections::HashMap<String, String>> {
+    if let Some(cursors) = state.get_local_read_cursors().await {
+        return Ok(cursors);
+    }
+
+    let store = app.store("channels.dat")?;
+    let cursors = store
+        .get("local_read_cursors")
+        .map(|value| local_read_from_value(&value))
+        .unwrap_or_default();
+
+    info!("Loaded {} local read cursors", cursors.len());
+    state.load_local_read_cursors(cursors.clone()).await;
+    Ok(cursors)
+}
+
+/// Mark a channel as read up to `ts` in this app only
+#[tauri::command]
+pub async fn set_local_read(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    channel_id: String,
+    ts: String,
+) -> AppResult<()> {
+    info!("Setting local read cursor for {} to {}", channel_id, ts);
+
+    // Make sure persisted cursors are loaded so we don't overwrite them
+    local_read_cursors(&app, &state).await?;
+    let cursors = state.set_local_read(channel_id, ts).await;
+
+    let store = app.store("channels.dat")?;
+    store.set("local_read_cursors", local_read_to_value(&cursors));
+    store.save()?;
+
+    Ok(())
+}
+
+/// Get local unread state for a channel, computed against its recent history
+#[tauri::command]
+pub async fn get_local_unread(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    channel_id: String,
+) -> AppResult<LocalUnread> {
+    let cursors = local_read_cursors(&app, &state).await?;
+    let last_read_ts = cursors.get(&channel_id).cloned();
+
+    let client = state.get_client().await?;
+    let messages = client
+        .get_channel_messages_with_options(
+            &channel_id,
+            last_read_ts.clone(),
+            None,
+            200,
+            HistoryOptions {
+                thread_replies: ThreadReplies::Lazy,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    Ok(compute_local_unread(&channel_id, &messages, last_read_ts.as_deref()))
+}
+
+/// A message ts fit for conversations.mark, trimmed of stray whitespace
+fn mark_read_ts(ts: &str) -> AppResult<&str> {
+    let ts = ts.trim();
+    if ts_value(ts) <= 0.0 {
+        return Err(AppError::ParseError(format!("Invalid timestamp '{}'", ts)));
+    }
+    Ok(ts)
+}
+
+/// Mark a channel read on Slack up to `ts`, clearing its unread badge
+///
+/// `ts` is typically the newest message of a search result. Errors are the
+/// client's friendly ones (auth, access, unknown message).
+#[tauri::command]
+pub async fn mark_channel_read(
+    state: State<'_, AppState>,
+    channel_id: String,
+    ts: String,
+) -> AppResult<()> {
+    let ts = mark_read_ts(&ts)?;
+    let client = state.get_client().await?;
+
+    client.mark_conversation_as_read(&channel_id, ts).await?;
+    info!("Marked channel {} read up to {}", channel_id, ts);
+    Ok(())
+}
+
+/// Maximum number of channels fetched at once by catch_up
+const CATCH_UP_CONCURRENCY: usize = 5;
+/// Newest messages fetched per channel by catch_up
+const CATCH_UP_FETCH_LIMIT: usize = 200;
+/// Newest messages scanned per channel for threads started before the digest cutoff
+const DIGEST_THREAD_PARENT_LIMIT: usize = 100;
+
+/// Keep messages strictly newer than the cursor, oldest first
+///
+/// conversations.history's `oldest` is inclusive, so the cursor message
+/// itself comes back and is dropped here.
+fn messages_since(messages: Vec<SlackMessage>, cursor: Option<&str>) -> Vec<SlackMessage> {
+    let cursor = cursor.map(ts_value);
+    let mut newer: Vec<SlackMessage> = messages
+        .into_iter()
+        .filter(|msg| cursor.is_none_or(|c| ts_value(&msg.ts) > c))
+        .collect();
+    sort_by_ts(&mut newer);
+    newer
+}
+
+/// Convert a conversations.history message, keeping its file metadata and reactions
+pub(crate) fn history_message(
+    msg: SlackMessage,
+    channel_id: &str,
+    channel_name: &str,
+    user_cache: &HashMap<String, CachedUser>,
+    reactor_cap: usize,
+    team_domain: Option<&str>,
+) -> Message {
+    let user_id = msg
+        .user
+        .clone()
+        .or_else(|| msg.bot_id.clone())
+        .unwrap_or_default();
+    let user_name = user_cache
+        .get(&user_id)
+        .map(|u| u.name.clone())
+        .or_else(|| msg.username.clone())
+        .unwrap_or_else(|| user_id.clone());
+    let is_pinned = msg.is_pinned();
+    let is_saved = msg.is_saved();
+    let is_shared_channel = msg.in_shared_channel();
+    let block_urls = msg.block_urls();
+
+    Message {
+        permalink: build_slack_url(team_domain, channel_id, &msg.ts, msg.thread_ts.as_deref()),
+        is_thread_parent: msg.reply_count.unwrap_or(0) > 0,
+        is_thread_reply: msg.is_thread_reply(),
+        text: replace_user_mentions(&msg.text, user_cache),
+        ts: msg.ts,
+        thread_ts: msg.thread_ts,
+        user: user_id,
+        user_name,
+        channel: channel_id.to_string(),
+        channel_name: channel_name.to_string(),
+        reply_count: msg.reply_count,
+        reactions: capped_reactions(msg.reactions, reactor_cap),
+        files: msg.files,
+        highlights: None,
+        metadata: msg.metadata,
+        is_pinned,
+        is_saved,
+        is_shared_channel,
+        block_urls,
+    }
+}
+
+/// New messages in one channel since it was last read
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelCatchUp {
+    /// Oldest first
+    pub messages: Vec<Message>,
+    /// More unread messages than `CATCH_UP_FETCH_LIMIT`; only the newest are included
+    pub truncated: bool,
+}
+
+/// Fetch what's new in each channel since it was last read
+///
+/// Uses the local read cursor when there is one and Slack's `last_read`
+/// otherwise. Channels without any cursor return their recent history.
+/// Channels that fail to load are logged and left out of the result.
+#[tauri::command]
+pub async fn catch_up(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    channel_ids: Vec<String>,
+) -> AppResult<HashMap<String, ChannelCatchUp>> {
+    use futures::stream::{self, StreamExt};
+
+    let cursors = local_read_cursors(&app, &state).await?;
+    let client = state.get_client().await?;
+
+    let muted = local_muted_channel_ids(&app)?;
+    let channel_ids: Vec<String> = channel_ids.into_iter().filter(|id| !muted.contains(id)).collect();
+    info!("Catching up on {} channels", channel_ids.len());
+
+    let fetched: Vec<(String, anyhow::Result<(Vec<SlackMessage>, bool)>)> = stream::iter(channel_ids)
+        .map(|channel_id| {
+            let client = &client;
+            let local_cursor = cursors.get(&channel_id).cloned();
+            async move {
+                let cursor = match local_cursor {
+                    Some(cursor) => Some(cursor),
+                    None => client
+                        .get_channel_info(&channel_id)
+                        .await
+                        .ok()
+                        .and_then(|info| info.last_read)
+                        .filter(|ts| ts_value(ts) > 0.0),
+                };
+                let result = client
+                    .get_channel_messages_with_options(
+                        &channel_id,
+                        cursor.clone(),
+                        None,
+                        CATCH_UP_FETCH_LIMIT,
+                        HistoryOptions {
+                            thread_replies: ThreadReplies::Lazy,
+                            ..Default::default()
+                        },
+                    )
+                    .await
+                    .map(|messages| {
+                        let truncated = messages.len() >= CATCH_UP_FETCH_LIMIT;
+                        (messages_since(messages, cursor.as_deref()), truncated)
+                    });
+                (channel_id, result)
+            }
+        })
+        .buffer_unordered(CATCH_UP_CONCURRENCY)
+        .collect()
+        .await;
+
+    let user_cache = state.get_user_cache_full().await;
+    let reactor_cap = state.reactor_cap().await;
+    let team_domain = state.team_domain().await;
+    let channel_cache = state.get_channel_cache().await;
+    let mut caught_up = HashMap::new();
+    for (channel_id, result) in fetched {
+        match result {
+            Ok((messages, truncated)) => {
+                if truncated {
+                    warn!("Catch-up for {} stopped at {} messages", channel_id, CATCH_UP_FETCH_LIMIT);
+                }
+                let channel_name = channel_cache
+                    .get(&channel_id)
+                    .cloned()
+                    .unwrap_or_else(|| channel_id.clone());
+                let mut converted = Vec::with_capacity(messages.len());
+                for msg in messages {
+                    let mut message = history_message(
+                        msg,
+                        &channel_id,
+                        &channel_name,
+                        &user_cache,
+                        reactor_cap,
+                        team_domain.as_deref(),
+                    );
+                    message.text = state.resolve_subteam_mentions(message.text).await;
+                    converted.push(message);
+                }
+                caught_up.insert(channel_id, ChannelCatchUp { messages: converted, truncated });
+            }
+            Err(e) => warn!("Catch-up failed for channel {}: {}", channel_id, e),
+        }
+    }
+
+    Ok(caught_up)
+}
+
+/// A thread whose newest reply arrived after the digest cutoff
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DigestThread {
+    pub thread_ts: String,
+    pub reply_count: usize,
+    pub latest_reply: String,
+}
+
+/// New activity in one channel since the digest cutoff
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelDigest {
+    pub channel_id: String,
+    pub channel_name: String,
+    pub message_count: usize,
+    /// Messages that mention the current user directly
+    pub mention_count: usize,
+    pub active_threads: Vec<DigestThread>,
+    pub messages: Vec<Message>,
+    /// The fetch hit `CATCH_UP_FETCH_LIMIT`, so older messages since the cutoff are missing
+    pub truncated: bool,
+}
+
+/// "Since I was last online" summary across several channels
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub since_ts: String,
+    pub total_messages: usize,
+    pub total_mentions: usize,
+    /// Channels with new activity, most mentions first
+    pub channels: Vec<ChannelDigest>,
+}
+
+fn mentions_user(text: &str, user_id: &str) -> bool {
+    text.contains(&format!("<@{}>", user_id)) || text.contains(&format!("<@{}|", user_id))
+}
+
+/// Count mentions and collect active threads from a channel's history since `since_ts`
+///
+/// `messages` is conversations.history output fetched with `oldest = since_ts`;
+/// only messages strictly newer than the cutoff count. `recent_parents` is the
+/// channel's newest history without a cutoff, so threads started before
+/// `since_ts` that got replies after it are found too.
+fn summarize_channel(
+    messages: &[SlackMessage],
+    recent_parents: &[SlackMessage],
+    since_ts: &str,
+    user_id: Option<&str>,
+) -> (usize, Vec<DigestThread>) {
+    let since = ts_value(since_ts);
+    let mention_count = user_id.map_or(0, |uid| {
+        messages
+            .iter()
+            .filter(|msg| ts_value(&msg.ts) > since && mentions_user(&msg.text, uid))
+            .count()
+    });
+
+    let mut seen = HashSet::new();
+    let mut active_threads: Vec<DigestThread> = messages
+        .iter()
+        .chain(recent_parents)
+        .filter(|msg| msg.reply_count.unwrap_or(0) > 0 && seen.insert(msg.ts.as_str()))
+        .filter_map(|msg| {
+            let latest_reply = msg.latest_reply.clone()?;
+            (ts_value(&latest_reply) > since).then(|| DigestThread {
+                thread_ts: msg.thread_ts.clone().unwrap_or_else(|| msg.ts.clone()),
+                reply_count: msg.reply_count.unwrap_or(0),
+                latest_reply,
+            })
+        })
+        .collect();
+    active_threads.sort_by(|a, b| {
+        ts_value(&b.latest_reply)
+            .partial_cmp(&ts_value(&a.latest_reply))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    (mention_count, active_threads)
+}
+
+/// Order channels by mentions, then by volume, dropping channels with nothing new
+fn assemble_digest(since_ts: &str, mut channels: Vec<ChannelDigest>) -> Digest {
+    channels.retain(|c| c.message_count > 0 || !c.active_threads.is_empty());
+    channels.sort_by(|a, b| {
+        b.mention_count
+            .cmp(&a.mention_count)
+            .then(b.message_count.cmp(&a.message_count))
+            .then_with(|| a.channel_name.cmp(&b.channel_name))
+    });
+
+    Digest {
+        since_ts: since_ts.to_string(),
+        total_messages: channels.iter().map(|c| c.message_count).sum(),
+        total_mentions: channels.iter().map(|c| c.mention_count).sum(),
+        channels,
+    }
+}
+
+/// Build a morning summary of new messages across channels since `since_ts`
+///
+/// Messages are grouped per channel with the current user's mention count
+/// and the threads that picked up replies after the cutoff, including threads
+/// started before it. Muted channels are skipped and channels that fail to
+/// load are logged and left out.
+#[tauri::command]
+pub async fn build_digest(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    channel_ids: Vec<String>,
+    since_ts: String,
+) -> AppResult<Digest> {
+    use futures::stream::{self, StreamExt};
+
+    if ts_value(&since_ts) <= 0.0 {
+        return Err(AppError::ParseError(format!("Invalid timestamp '{}'", since_ts)));
+    }
+
+    let client = state.get_client().await?;
+    let user_id = state.current_user_id().await?;
+
+    let muted = local_muted_channel_ids(&app)?;
+    let channel_ids: Vec<String> = channel_ids.into_iter().filter(|id| !muted.contains(id)).collect();
+    info!("Building digest for {} channels since {}", channel_ids.len(), since_ts);
+
+    type DigestFetch = (String, anyhow::Result<Vec<SlackMessage>>, Vec<SlackMessage>);
+    let fetched: Vec<DigestFetch> = stream::iter(channel_ids)
+        .map(|channel_id| {
+            let client = &client;
+            let since_ts = since_ts.clone();
+            async move {
+                let history_options = || HistoryOptions {
+                    thread_replies: ThreadReplies::Lazy,
+                    ..Default::default()
+                };
+                let result = client
+                    .get_channel_messages_with_options(
+                        &channel_id,
+                        Some(since_ts),
+                        None,
+                        CATCH_UP_FETCH_LIMIT,
+                        history_options(),
+                    )
+                    .await;
+                // Older parents only matter for their thread metadata, so a failure just loses those
+                let recent_parents = match result {
+                    Ok(_) => client
+                        .get_channel_messages_with_options(
+                            &channel_id,
+                            None,
+                            None,
+                            DIGEST_THREAD_PARENT_LIMIT,
+                            history_options(),
+                        )
+                        .await
+                        .unwrap_or_else(|e| {
+                            warn!("Digest couldn't scan older threads in {}: {}", channel_id, e);
+                            Vec::new()
+                        }),
+                    Err(_) => Vec::new(),
+                };
+                (channel_id, result, recent_parents)
+            }
+        })
+        .buffer_unordered(CATCH_UP_CONCURRENCY)
+        .collect()
+        .await;
+
+    let user_cache = state.get_user_cache_full().await;
+    let reactor_cap = state.reactor_cap().await;
+    let team_domain = state.team_domain().await;
+    let channel_cache = state.get_channel_cache().await;
+    let mut channels = Vec::new();
+    for (channel_id, result, recent_parents) in fetched {
+        let history = match result {
+            Ok(history) => history,
+            Err(e) => {
+                warn!("Digest failed for channel {}: {}", channel_id, e);
+                continue;
+            }
+        };
+        let channel_name = channel_cache
+            .get(&channel_id)
+            .cloned()
+            .unwrap_or_else(|| channel_id.clone());
+        let truncated = history.len() >= CATCH_UP_FETCH_LIMIT;
+        if truncated {
+            warn!("Digest for {} stopped at {} messages", channel_id, CATCH_UP_FETCH_LIMIT);
+        }
+        let (mention_count, active_threads) =
+            summarize_channel(&history, &recent_parents, &since_ts, user_id.as_deref());
+
+        let mut messages = Vec::new();
+        for msg in messages_since(history, Some(&since_ts)) {
+            let mut message = history_message(
+                msg,
+                &channel_id,
+                &channel_name,
+                &user_cache,
+                reactor_cap,
+                team_domain.as_deref(),
+            );
+            message.text = state.resolve_subteam_mentions(message.text).await;
+            messages.push(message);
+        }
+
+        channels.push(ChannelDigest {
+            channel_id,
+            channel_name,
+            message_count: messages.len(),
+            mention_count,
+            active_threads,
+            messages,
+            truncated,
+        });
+    }
+
+    Ok(assemble_digest(&since_ts, channels))
+}
+
 /// Get DM channels (Phase 1: Read-only)
 /// IMPORTANT: This is an experimental feature that requires:
 /// 1. Feature flag to be enabled (dmChannelsEnabled)
@@ -87,7 +864,7 @@ pub async fn get_dm_channels(state: State<'_, AppState>) -> AppResult<Vec<SlackC
         }
         Err(e) => {
             // Log the error with appropriate severity based on the error type
-            if e.to_string().contains("missing_scope") || e.to_string().contains("im:read") {
+            if matches!(SlackApiError::of(&e), Some(SlackApiError::MissingScope { .. })) {
                 warn!("Cannot fetch DM channels: Missing im:read permission. {}", e);
             } else {
                 error!("Failed to fetch DM channels: {}", e);
@@ -97,6 +874,30 @@ pub async fn get_dm_channels(state: State<'_, AppState>) -> AppResult<Vec<SlackC
     }
 }
 
+/// Get the links on a channel's bookmark bar
+///
+/// Without the bookmarks:read scope the bar is just empty, like DMs without im:read.
+#[tauri::command]
+pub async fn get_channel_bookmarks(
+    state: State<'_, AppState>,
+    channel_id: String,
+) -> AppResult<Vec<SlackBookmark>> {
+    info!("Getting bookmarks for channel: {}", channel_id);
+
+    let client = state.get_client().await?;
+    match client.get_bookmarks(&channel_id).await {
+        Ok(bookmarks) => Ok(bookmarks),
+        Err(e) if matches!(SlackApiError::of(&e), Some(SlackApiError::MissingScope { .. })) => {
+            warn!("Cannot read bookmarks: Missing bookmarks:read permission. {}", e);
+            Ok(Vec::new())
+        }
+        Err(e) => {
+            error!("Failed to get bookmarks for {}: {}", channel_id, e);
+            Err(e.into())
+        }
+    }
+}
+
 /// Check if the token has permission to access DM channels
 #[tauri::command]
 pub async fn check_dm_permissions(state: State<'_, AppState>) -> AppResult<bool> {
@@ -111,7 +912,7 @@ pub async fn check_dm_permissions(state: State<'_, AppState>) -> AppResult<bool>
             Ok(true)
         }
         Err(e) => {
-            if e.to_string().contains("missing_scope") || e.to_string().contains("im:read") {
+            if matches!(SlackApiError::of(&e), Some(SlackApiError::MissingScope { .. })) {
                 info!("DM channel permissions not available: {}", e);
                 Ok(false)
             } else {
@@ -134,11 +935,18 @@ pub async fn search_dm_messages(
     dm_id: String,
     query: Option<String>,
     limit: Option<usize>,
+    fuzzy: Option<bool>, // Tolerate small typos in query terms (slower)
+    force_refresh: Option<bool>, // Skip the search cache
 ) -> AppResult<Vec<SlackMessage>> {
     info!(
-        "Searching DM channel {} with query: {:?} (Phase 2)",
-        dm_id, query
+        "Searching DM channel {} with query: {:?}, fuzzy: {:?} (Phase 2)",
+        dm_id, query, fuzzy
     );
+    let match_mode = if fuzzy.unwrap_or(false) {
+        MatchMode::Fuzzy
+    } else {
+        MatchMode::Exact
+    };
 
     // Get the Slack client
     let client = state.get_client().await?;
@@ -146,11 +954,23 @@ pub async fn search_dm_messages(
     // Set default limit if not provided (conservative for DMs)
     let max_results = limit.unwrap_or(50).min(100);
 
-    // Try to search DM messages
-    match client
-        .search_dm_messages(&dm_id, query.as_deref(), max_results)
-        .await
-    {
+    let cache_key = DmSearchCacheKey {
+        dm_id: dm_id.clone(),
+        query: query.clone().unwrap_or_default(),
+        limit: max_results,
+        fuzzy: match_mode == MatchMode::Fuzzy,
+    };
+
+    // Try to search DM messages, repeating searches are served from the search cache
+    let searched = state
+        .get_or_search_dm(&cache_key, force_refresh.unwrap_or(false), || async {
+            let home_team = state.team_id().await;
+            Ok(client
+                .search_dm_messages_with_mode(&dm_id, query.as_deref(), max_results, match_mode, home_team.as_deref())
+                .await?)
+        })
+        .await;
+    match searched {
         Ok(messages) => {
             info!(
                 "Successfully searched DM {}: found {} messages",
@@ -161,12 +981,12 @@ pub async fn search_dm_messages(
         }
         Err(e) => {
             // Log the error with appropriate severity based on the error type
-            if e.to_string().contains("missing_scope") || e.to_string().contains("im:history") {
+            if matches!(e, AppError::MissingScope { .. }) {
                 warn!("Cannot search DM messages: Missing im:history permission. {}", e);
             } else {
                 error!("Failed to search DM messages: {}", e);
             }
-            Err(e.into())
+            Err(e)
         }
     }
 }
@@ -175,16 +995,19 @@ pub async fn search_dm_messages(
 ///
 /// Returns a list of (channel_id, channel_name) tuples for channels that:
 /// - User is a member of (is_member = true)
-/// - Are not muted (is_muted = false)
+/// - Are not muted, in Slack (is_muted) or locally via set_channel_muted
 /// - Are not archived (is_archived = false)
 #[tauri::command]
 pub async fn get_unmuted_member_channels(
+    app: AppHandle,
     state: State<'_, AppState>
 ) -> AppResult<Vec<(String, String)>> {
     info!("[get_unmuted_member_channels] Fetching unmuted channels");
 
     let client = state.get_client().await?;
     let all_channels = client.get_channels().await?;
+    let locally_muted = local_muted_channel_ids(&app)?;
+    let mute_flags = state.channel_mute_flags().await;
 
     info!("[get_unmuted_member_channels] Total channels fetched: {}", all_channels.len());
 
@@ -193,7 +1016,10 @@ pub async fn get_unmuted_member_channels(
         .into_iter()
         .filter(|ch| {
             let is_member = ch.is_member.unwrap_or(false);
-            let is_muted = ch.is_muted.unwrap_or(false);
+            let is_muted = mute_flags
+                .get(&ch.id)
+                .copied()
+                .unwrap_or(ch.is_muted.unwrap_or(false) || locally_muted.contains(&ch.id));
             let is_archived = ch.is_archived.unwrap_or(false);
 
             is_member && !is_muted && !is_archived
@@ -205,3 +1031,1175 @@ pub async fn get_unmuted_member_channels(
 
     Ok(unmuted)
 }
+
+/// Maximum number of conversations.info requests in flight for unread counts
+const UNREAD_COUNT_CONCURRENCY: usize = 8;
+
+/// A channel with unread messages, for the "needs attention" list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActiveChannel {
+    pub channel_id: String,
+    pub channel_name: String,
+    pub unread_count: u64,
+}
+
+/// Channels with unread messages, most unread first (then by name)
+///
+/// A channel whose unread count couldn't be read is treated as read.
+fn active_channels(channels: Vec<SlackConversation>) -> Vec<ActiveChannel> {
+    let mut active: Vec<ActiveChannel> = channels
+        .into_iter()
+        .filter_map(|channel| {
+            let unread_count = channel.unread_count_display.filter(|count| *count > 0)?;
+            Some(ActiveChannel {
+                channel_name: channel.name.unwrap_or_else(|| channel.id.clone()),
+                channel_id: channel.id,
+                unread_count,
+            })
+        })
+        .collect();
+    active.sort_by(|a, b| {
+        b.unread_count
+            .cmp(&a.unread_count)
+            .then_with(|| a.channel_name.cmp(&b.channel_name))
+    });
+    active
+}
+
+/// Channels I'm a member of that have unread messages, most unread first
+///
+/// Membership comes from users.conversations; unread counts need a
+/// conversations.info per channel, fetched with bounded parallelism.
+#[tauri::command]
+pub async fn get_active_channels(state: State<'_, AppState>) -> AppResult<Vec<ActiveChannel>> {
+    use futures::stream::{self, StreamExt};
+
+    let client = state.get_client().await?;
+    let user_id = state
+        .current_user_id()
+        .await?
+        .ok_or_else(|| AppError::AuthError("Could not determine the current user".to_string()))?;
+
+    let channels = client.get_user_conversations(&user_id).await?;
+    info!("Checking unread counts for {} channels", channels.len());
+
+    let detailed: Vec<SlackConversation> = stream::iter(channels)
+        .map(|channel| {
+            let client = &client;
+            async move {
+                match client.get_channel_info(&channel.id).await {
+                    // users.conversations has the name even when conversations.info doesn't
+                    Ok(info) => SlackConversation {
+                        name: channel.name.or(info.name),
+                        ..info
+                    },
+                    Err(e) => {
+                        warn!("Couldn't read unread count for {}: {}", channel.id, e);
+                        channel
+                    }
+                }
+            }
+        })
+        .buffer_unordered(UNREAD_COUNT_CONCURRENCY)
+        .collect()
+        .await;
+
+    let active = active_channels(detailed);
+    info!("{} channels have unread messages", active.len());
+    Ok(active)
+}
+
+/// A topic, purpose or rename event from a channel's history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelChangeEvent {
+    pub ts: String,
+    pub user: Option<String>,
+    /// "topic", "purpose" or "name"
+    pub kind: String,
+    pub value: String,
+    /// Readable summary, e.g. "alice changed topic to Release week"
+    pub text: String,
+}
+
+/// Pick the topic/purpose/name change events out of channel history
+fn channel_change_events(
+    messages: &[SlackMessage],
+    user_names: &std::collections::HashMap<String, String>,
+) -> Vec<ChannelChangeEvent> {
+    messages
+        .iter()
+        .filter_map(|msg| {
+            let (kind, value) = match msg.subtype.as_deref()? {
+                "channel_topic" => ("topic", msg.topic.clone()),
+                "channel_purpose" => ("purpose", msg.purpose.clone()),
+                "channel_name" => ("name", msg.name.clone()),
+                _ => return None,
+            };
+            // Older events may lack the dedicated field; the text is the best we have
+            let value = value.unwrap_or_else(|| msg.text.clone());
+
+            let actor = msg
+                .user
+                .as_ref()
+                .map(|id| user_names.get(id).cloned().unwrap_or_else(|| id.clone()))
+                .unwrap_or_else(|| "Someone".to_string());
+
+            let text = match (kind, msg.old_name.as_ref()) {
+                ("name", Some(old_name)) => {
+                    format!("{} renamed the channel from {} to {}", actor, old_name, value)
+                }
+                ("name", None) => format!("{} renamed the channel to {}", actor, value),
+                _ if value.is_empty() => format!("{} cleared the {}", actor, kind),
+                _ => format!("{} changed {} to {}", actor, kind, value),
+            };
+
+            Some(ChannelChangeEvent {
+                ts: msg.ts.clone(),
+                user: msg.user.clone(),
+                kind: kind.to_string(),
+                value,
+                text,
+            })
+        })
+        .collect()
+}
+
+/// Get the history of topic, purpose and name changes for a channel
+#[tauri::command]
+pub async fn get_channel_change_history(
+    state: State<'_, AppState>,
+    channel_id: String,
+    limit: Option<usize>,
+) -> AppResult<Vec<ChannelChangeEvent>> {
+    info!("Getting topic/purpose/name change history for channel: {}", channel_id);
+
+    let client = state.get_client().await?;
+    let messages = client
+        .get_channel_messages_with_options(
+            &channel_id,
+            None,
+            None,
+            limit.unwrap_or(1000),
+            HistoryOptions {
+                thread_replies: ThreadReplies::Lazy,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    let user_names = state.get_user_cache().await;
+    let events = channel_change_events(&messages, &user_names);
+
+    info!(
+        "Found {} change events in {} messages for channel {}",
+        events.len(),
+        messages.len(),
+        channel_id
+    );
+
+    Ok(events)
+}
+
+/// Someone joining or leaving a channel, from its history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MembershipEvent {
+    pub ts: String,
+    pub user: Option<String>,
+    pub user_name: String,
+    /// "join" or "leave"
+    pub kind: String,
+}
+
+/// When and by whom a channel was created, and who came and went since
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelTimeline {
+    pub channel_id: String,
+    pub channel_name: Option<String>,
+    /// RFC 3339 creation time
+    pub created: Option<String>,
+    pub creator: Option<String>,
+    pub creator_name: Option<String>,
+    /// Oldest first; empty when the history could not be read
+    pub membership_events: Vec<MembershipEvent>,
+    pub history_accessible: bool,
+}
+
+/// Pick the join/leave events out of channel history, oldest first
+fn membership_events(messages: &[SlackMessage], user_names: &HashMap<String, String>) -> Vec<MembershipEvent> {
+    let mut events: Vec<MembershipEvent> = messages
+        .iter()
+        .filter_map(|msg| {
+            // Private channels report group_* subtypes
+            let kind = match msg.subtype.as_deref()? {
+                "channel_join" | "group_join" => "join",
+                "channel_leave" | "group_leave" => "leave",
+                _ => return None,
+            };
+            let user_name = msg
+                .user
+                .as_ref()
+                .map(|id| user_names.get(id).cloned().unwrap_or_else(|| id.clone()))
+                .unwrap_or_else(|| "Someone".to_string());
+
+            Some(MembershipEvent {
+                ts: msg.ts.clone(),
+                user: msg.user.clone(),
+                user_name,
+                kind: kind.to_string(),
+            })
+        })
+        .collect();
+    events.sort_by(|a, b| ts_value(&a.ts).total_cmp(&ts_value(&b.ts)));
+    events
+}
+
+/// Combine conversations.info with whatever history could be read
+fn channel_timeline(
+    info: &SlackConversation,
+    history: Option<&[SlackMessage]>,
+    user_names: &HashMap<String, String>,
+) -> ChannelTimeline {
+    ChannelTimeline {
+        channel_id: info.id.clone(),
+        channel_name: info.name.clone(),
+        created: info
+            .created
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|created| created.to_rfc3339()),
+        creator: info.creator.clone(),
+        creator_name: info.creator.as_ref().and_then(|id| user_names.get(id).cloned()),
+        membership_events: history
+            .map(|messages| membership_events(messages, user_names))
+            .unwrap_or_default(),
+        history_accessible: history.is_some(),
+    }
+}
+
+/// Get when a channel was created, by whom, and its recent joins and leaves
+#[tauri::command]
+pub async fn get_channel_timeline(
+    state: State<'_, AppState>,
+    channel: String,
+) -> AppResult<ChannelTimeline> {
+    info!("Getting timeline for channel: {}", channel);
+
+    let client = state.get_client().await?;
+    let info = client.get_channel_info(&channel).await?;
+
+    let history = match client
+        .get_channel_messages_with_options(
+            &channel,
+            None,
+            None,
+            1000,
+            HistoryOptions {
+                thread_replies: ThreadReplies::Lazy,
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        Ok(messages) => Some(messages),
+        Err(e) => {
+            warn!("Channel {} history unavailable for its timeline: {}", channel, e);
+            None
+        }
+    };
+
+    let mut user_names = state.get_user_cache().await;
+    if let Some(creator) = info.creator.as_ref().filter(|id| !user_names.contains_key(*id)) {
+        match client.get_user_info(creator).await {
+            Ok(user) => {
+                let name = user
+                    .profile
+                    .as_ref()
+                    .and_then(|p| p.display_name.clone().filter(|s| !s.is_empty()))
+                    .or_else(|| user.real_name.clone().filter(|s| !s.is_empty()))
+                    .unwrap_or_else(|| user.name.clone());
+                state.cache_user(creator.clone(), name.clone(), user.real_name.clone()).await;
+                user_names.insert(creator.clone(), name);
+            }
+            Err(e) => warn!("Failed to look up channel creator {}: {}", creator, e),
+        }
+    }
+
+    let timeline = channel_timeline(&info, history.as_deref(), &user_names);
+    info!(
+        "Channel {} timeline has {} membership events",
+        channel,
+        timeline.membership_events.len()
+    );
+    Ok(timeline)
+}
+
+/// Result of checking whether a channel ID can be searched
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelValidity {
+    pub exists: bool,
+    pub accessible: bool,
+    pub is_member: bool,
+    #[serde(rename = "type")]
+    pub channel_type: String,
+}
+
+/// Build a ChannelValidity from a conversations.info lookup
+///
+/// `channel_not_found` means the ID is stale or deleted. Auth errors (e.g.
+/// `invalid_auth`) say nothing about the channel and are returned as
+/// `AuthError`. Any other Slack error (e.g. `missing_scope`, `not_in_channel`)
+/// means the channel exists but this token cannot read it.
+pub(crate) fn channel_validity(
+    channel: Option<&SlackConversation>,
+    error: Option<&str>,
+) -> AppResult<ChannelValidity> {
+    if let Some(error_msg) = error {
+        if let auth_error @ AppError::AuthError(_) = AppError::slack_api(error_msg) {
+            return Err(auth_error);
+        }
+        return Ok(ChannelValidity {
+            exists: error_msg != "channel_not_found",
+            accessible: false,
+            is_member: false,
+            channel_type: "unknown".to_string(),
+        });
+    }
+
+    let Some(channel) = channel else {
+        return Ok(ChannelValidity {
+            exists: false,
+            accessible: false,
+            is_member: false,
+            channel_type: "unknown".to_string(),
+        });
+    };
+
+    let is_im = channel.is_im.unwrap_or(false);
+    let is_mpim = channel.is_mpim.unwrap_or(false);
+    let channel_type = if is_im {
+        "im"
+    } else if is_mpim {
+        "mpim"
+    } else if channel.is_private.unwrap_or(false) || channel.is_group.unwrap_or(false) {
+        "private_channel"
+    } else {
+        "public_channel"
+    };
+
+    Ok(ChannelValidity {
+        exists: true,
+        accessible: true,
+        // DMs don't report is_member, but the user is always part of their own DMs
+        is_member: channel.is_member.unwrap_or(is_im || is_mpim),
+        channel_type: channel_type.to_string(),
+    })
+}
+
+/// Check that a channel ID exists and is readable before searching it
+///
+/// Lets the UI warn about stale channel IDs (e.g. from old favorites)
+/// instead of running a search that is guaranteed to fail.
+#[tauri::command]
+pub async fn validate_channel(
+    state: State<'_, AppState>,
+    channel_id: String,
+) -> AppResult<ChannelValidity> {
+    info!("Validating channel: {}", channel_id);
+
+    let client = state.get_client().await?;
+    let (channel, error) = client.probe_channel(&channel_id).await?;
+    let validity = channel_validity(channel.as_ref(), error.as_deref())?;
+
+    if !validity.accessible {
+        warn!(
+            "Channel {} is not accessible (exists: {}, error: {:?})",
+            channel_id, validity.exists, error
+        );
+    }
+
+    Ok(validity)
+}
+
+/// Get the ts of a channel's first message so the date picker can
+/// disable dates before it
+#[tauri::command]
+pub async fn get_channel_oldest_ts(
+    state: State<'_, AppState>,
+    channel_id: String,
+) -> AppResult<Option<String>> {
+    let client = state.get_client().await?;
+    Ok(client.get_oldest_message_ts(&channel_id).await?)
+}
+
+/// Progress of a running `export_channel`, emitted as `export-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub channel: String,
+    /// "history", "threads", "rendering" or "done"
+    pub stage: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    fn from_param(format: Option<&str>) -> AppResult<Self> {
+        match format.map(|f| f.to_ascii_lowercase()).as_deref() {
+            None | Some("markdown") | Some("md") => Ok(ExportFormat::Markdown),
+            Some("json") => Ok(ExportFormat::Json),
+            Some(other) => Err(AppError::ParseError(format!(
+                "Unknown export format '{}' (expected markdown or json)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ExportedReaction {
+    pub name: String,
+    pub count: u32,
+}
+
+/// A message in a channel export, with its thread replies nested under it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ExportedMessage {
+    pub ts: String,
+    pub user: Option<String>,
+    pub user_name: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reactions: Vec<ExportedReaction>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<ExportedMessage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ChannelExport<'a> {
+    channel: &'a str,
+    channel_name: &'a str,
+    from: Option<&'a str>,
+    to: Option<&'a str>,
+    message_count: usize,
+    messages: &'a [ExportedMessage],
+}
+
+fn sort_by_ts(messages: &mut [SlackMessage]) {
+    messages.sort_by(|a, b| {
+        ts_value(&a.ts)
+            .partial_cmp(&ts_value(&b.ts))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+fn exported_message(msg: SlackMessage, user_names: &HashMap<String, String>) -> ExportedMessage {
+    let user_name = msg
+        .user
+        .as_ref()
+        .and_then(|id| user_names.get(id).cloned())
+        .or_else(|| msg.username.clone())
+        .or_else(|| msg.user.clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    ExportedMessage {
+        ts: msg.ts,
+        user: msg.user,
+        user_name,
+        text: msg.text,
+        reactions: msg
+            .reactions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| ExportedReaction {
+                name: r.name,
+                count: r.count,
+            })
+            .collect(),
+        files: msg
+            .files
+            .unwrap_or_default()
+            .into_iter()
+            .map(|f| f.name)
+            .collect(),
+        replies: Vec::new(),
+    }
+}
+
+/// Nest thread replies under their parents, oldest first at both levels
+///
+/// `threads` maps a parent ts to its conversations.replies result (which
+/// repeats the parent). Replies that also show up in history (broadcasts)
+/// only appear inside their thread.
+fn assemble_export(
+    history: Vec<SlackMessage>,
+    mut threads: HashMap<String, Vec<SlackMessage>>,
+    user_names: &HashMap<String, String>,
+) -> Vec<ExportedMessage> {
+    let mut top_level: Vec<SlackMessage> = history
+        .into_iter()
+        .filter(|msg| msg.thread_ts.as_ref().is_none_or(|t| t == &msg.ts))
+        .collect();
+    sort_by_ts(&mut top_level);
+    top_level.dedup_by(|a, b| a.ts == b.ts);
+
+    top_level
+        .into_iter()
+        .map(|msg| {
+            let mut replies: Vec<SlackMessage> = threads
+                .remove(&msg.ts)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|reply| reply.ts != msg.ts)
+                .collect();
+            sort_by_ts(&mut replies);
+            replies.dedup_by(|a, b| a.ts == b.ts);
+
+            let mut exported = exported_message(msg, user_names);
+            exported.replies = replies
+                .into_iter()
+                .map(|reply| exported_message(reply, user_names))
+                .collect();
+            exported
+        })
+        .collect()
+}
+
+pub(crate) fn format_ts(ts: &str, offset: FixedOffset, format: &str) -> String {
+    chrono::DateTime::from_timestamp(ts_value(ts) as i64, 0)
+        .map(|dt| dt.with_timezone(&offset).format(format).to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn push_markdown_message(out: &mut String, msg: &ExportedMessage, offset: FixedOffset, prefix: &str) {
+    out.push_str(&format!(
+        "{}**{}** {}\n",
+        prefix,
+        msg.user_name,
+        format_ts(&msg.ts, offset, "%H:%M")
+    ));
+    for line in msg.text.lines() {
+        out.push_str(prefix);
+        out.push_str(line);
+        out.push('\n');
+    }
+    for file in &msg.files {
+        out.push_str(&format!("{}Attachment: {}\n", prefix, file));
+    }
+    if !msg.reactions.is_empty() {
+        let reactions: Vec<String> = msg
+            .reactions
+            .iter()
+            .map(|r| format!(":{}: {}", r.name, r.count))
+            .collect();
+        out.push_str(&format!("{}{}\n", prefix, reactions.join(" · ")));
+    }
+}
+
+/// Render an export as Markdown, one heading per day with replies quoted under their parent
+fn render_export_markdown(
+    channel_name: &str,
+    messages: &[ExportedMessage],
+    offset: FixedOffset,
+) -> String {
+    let reply_total: usize = messages.iter().map(|m| m.replies.len()).sum();
+    let mut out = format!(
+        "# #{}\n\n_{} messages, {} thread replies_\n",
+        channel_name,
+        messages.len(),
+        reply_total
+    );
+
+    let mut current_day = String::new();
+    for msg in messages {
+        let day = format_ts(&msg.ts, offset, "%Y-%m-%d");
+        if day != current_day {
+            out.push_str(&format!("\n## {}\n", day));
+            current_day = day;
+        }
+
+        out.push('\n');
+        push_markdown_message(&mut out, msg, offset, "");
+        for reply in &msg.replies {
+            out.push_str(">\n");
+            push_markdown_message(&mut out, reply, offset, "> ");
+        }
+    }
+
+    out
+}
+
+/// Unix timestamp for a date range bound (`YYYY-MM-DD` or RFC 3339, UTC days)
+pub(crate) fn date_bound_ts(date: &str, end_of_day: bool) -> AppResult<String> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(date) {
+        return Ok(dt.timestamp().to_string());
+    }
+
+    let day = chrono::NaiveDate::parse_from_str(date.split('T').next().unwrap_or(date), "%Y-%m-%d")
+        .map_err(|e| AppError::ParseError(format!("Invalid date '{}': {}", date, e)))?;
+    let time = if end_of_day {
+        day.and_hms_opt(23, 59, 59)
+    } else {
+        day.and_hms_opt(0, 0, 0)
+    };
+    Ok(time
+        .map(|t| t.and_utc().timestamp().to_string())
+        .unwrap_or_default())
+}
+
+async fn resolve_exported_text(
+    state: &AppState,
+    msg: &mut ExportedMessage,
+    user_cache: &HashMap<String, CachedUser>,
+    emoji: &HashMap<String, String>,
+) {
+    let text = resolve_emoji_aliases(&replace_user_mentions(&msg.text, user_cache), emoji);
+    msg.text = state.resolve_subteam_mentions(text).await;
+    for reaction in msg.reactions.iter_mut() {
+        reaction.name = resolve_emoji_aliases(&format!(":{}:", reaction.name), emoji)
+            .trim_matches(':')
+            .to_string();
+    }
+}
+
+fn emit_export_progress(app: &AppHandle, channel: &str, stage: &str, done: usize, total: usize) {
+    let progress = ExportProgress {
+        channel: channel.to_string(),
+        stage: stage.to_string(),
+        done,
+        total,
+    };
+    if let Err(e) = app.emit("export-progress", &progress) {
+        warn!("Failed to emit export progress: {}", e);
+    }
+}
+
+/// Export a channel's complete history with threads over a date range
+///
+/// Pages through the whole range (no call cap), fetches every thread,
+/// resolves user/group mentions and emoji aliases, and renders Markdown
+/// (default) or JSON. Progress is emitted as `export-progress` events.
+#[tauri::command]
+pub async fn export_channel(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    channel: String,
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+) -> AppResult<String> {
+    let format = ExportFormat::from_param(format.as_deref())?;
+    let oldest = from.as_deref().map(|d| date_bound_ts(d, false)).transpose()?;
+    let latest = to.as_deref().map(|d| date_bound_ts(d, true)).transpose()?;
+    info!("Exporting channel {} ({:?} to {:?}) as {:?}", channel, from, to, format);
+
+    let client = state.get_client().await?;
+
+    emit_export_progress(&app, &channel, "history", 0, 0);
+    let history = client
+        .get_channel_messages_with_options(
+            &channel,
+            oldest,
+            latest,
+            usize::MAX,
+            HistoryOptions {
+                thread_replies: ThreadReplies::Lazy,
+                full_history: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+    emit_export_progress(&app, &channel, "history", history.len(), history.len());
+
+    let parents: Vec<String> = history
+        .iter()
+        .filter(|msg| msg.reply_count.unwrap_or(0) > 0)
+        .map(|msg| msg.ts.clone())
+        .collect();
+    let mut threads = HashMap::new();
+    for (i, parent_ts) in parents.iter().enumerate() {
+        match client.get_thread_replies(&channel, parent_ts).await {
+            Ok(replies) => {
+                threads.insert(parent_ts.clone(), replies);
+            }
+            Err(e) => warn!("Failed to export thread {} in {}: {}", parent_ts, channel, e),
+        }
+        emit_export_progress(&app, &channel, "threads", i + 1, parents.len());
+    }
+
+    emit_export_progress(&app, &channel, "rendering", 0, 0);
+    let user_cache = state.get_user_cache_full().await;
+    let user_names = state.get_user_cache().await;
+    let emoji = crate::commands::emoji::get_emoji_list(app.clone(), state.clone(), None)
+        .await
+        .ok()
+        .and_then(|response| response.emoji)
+        .unwrap_or_default();
+
+    let mut messages = assemble_export(history, threads, &user_names);
+    for msg in messages.iter_mut() {
+        resolve_exported_text(&state, msg, &user_cache, &emoji).await;
+        for reply in msg.replies.iter_mut() {
+            resolve_exported_text(&state, reply, &user_cache, &emoji).await;
+        }
+    }
+
+    let channel_name = state
+        .get_channel_cache()
+        .await
+        .get(&channel)
+        .cloned()
+        .unwrap_or_else(|| channel.clone());
+
+    let output = match format {
+        ExportFormat::Markdown => {
+            render_export_markdown(&channel_name, &messages, *chrono::Local::now().offset())
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(&ChannelExport {
+            channel: &channel,
+            channel_name: &channel_name,
+            from: from.as_deref(),
+            to: to.as_deref(),
+            message_count: messages.len(),
+            messages: &messages,
+        })?,
+    };
+
+    emit_export_progress(&app, &channel, "done", messages.len(), messages.len());
+    info!("Exported {} messages from {}", messages.len(), channel);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(json: serde_json::Value) -> SlackConversation {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_active_channels_keep_only_unread() {
+        let channels = vec![
+            conversation(serde_json::json!({ "id": "C1", "name": "general", "unread_count_display": 0 })),
+            conversation(serde_json::json!({ "id": "C2", "name": "ops", "unread_count_display": 3 })),
+            conversation(serde_json::json!({ "id": "C3", "name": "random" })),
+            conversation(serde_json::json!({ "id": "C4", "name": "dev", "unread_count_display": 12 })),
+            conversation(serde_json::json!({ "id": "C5", "name": "alerts", "unread_count_display": 3 })),
+        ];
+
+        let active: Vec<(String, u64)> = active_channels(channels)
+            .into_iter()
+            .map(|c| (c.channel_name, c.unread_count))
+            .collect();
+        assert_eq!(
+            active,
+            vec![
+                ("dev".to_string(), 12),
+                ("alerts".to_string(), 3),
+                ("ops".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_channel_validity_accessible_not_member() {
+        let channel = conversation(serde_json::json!({
+            "id": "C1234567890",
+            "name": "general",
+            "is_channel": true,
+            "is_private": false,
+            "is_member": false
+        }));
+
+        let validity = channel_validity(Some(&channel), None).unwrap();
+        assert!(validity.exists);
+        assert!(validity.accessible);
+        assert!(!validity.is_member);
+        assert_eq!(validity.channel_type, "public_channel");
+    }
+
+    fn message(channel: &str, ts: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "ts": ts,
+            "threadTs": null,
+            "user": "U1",
+            "userName": "user",
+            "text": "hello",
+            "channel": channel,
+            "channelName": channel,
+            "permalink": "",
+            "isThreadParent": false,
+            "replyCount": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_favorites_channel_param_contains_all_favorites() {
+        let favorites: Vec<String> = ["C1", " C2 ", "", "C3", "C1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(favorites_channel_param(&favorites), Some("C1,C2,C3".to_string()));
+        assert_eq!(favorites_channel_param(&[]), None);
+    }
+
+    #[test]
+    fn test_dedupe_messages_merges_results() {
+        let messages = vec![
+            message("C1", "1.000"),
+            message("C2", "1.000"),
+            message("C1", "1.000"),
+            message("C1", "2.000"),
+        ];
+
+        let deduped = dedupe_messages(messages);
+        let keys: Vec<(String, String)> = deduped
+            .iter()
+            .map(|m| (m.channel.clone(), m.ts.clone()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("C1".to_string(), "1.000".to_string()),
+                ("C2".to_string(), "1.000".to_string()),
+                ("C1".to_string(), "2.000".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_channel_change_events_from_history() {
+        let messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "5.0", "user": "U1", "text": "hello everyone" },
+            {
+                "ts": "4.0", "user": "U1", "subtype": "channel_topic",
+                "text": "<@U1> set the channel topic: Release week", "topic": "Release week"
+            },
+            {
+                "ts": "3.0", "user": "U2", "subtype": "channel_purpose",
+                "text": "<@U2> set the channel purpose: Ship it", "purpose": "Ship it"
+            },
+            {
+                "ts": "2.0", "user": "U1", "subtype": "channel_join",
+                "text": "<@U1> has joined the channel"
+            },
+            {
+                "ts": "1.0", "user": "U3", "subtype": "channel_name",
+                "text": "<@U3> renamed the channel", "name": "releases", "old_name": "release"
+            }
+        ]))
+        .unwrap();
+
+        let mut user_names = std::collections::HashMap::new();
+        user_names.insert("U1".to_string(), "alice".to_string());
+        user_names.insert("U2".to_string(), "bob".to_string());
+
+        let events = channel_change_events(&messages, &user_names);
+        let texts: Vec<&str> = events.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "alice changed topic to Release week",
+                "bob changed purpose to Ship it",
+                "U3 renamed the channel from release to releases",
+            ]
+        );
+        assert_eq!(events[0].kind, "topic");
+        assert_eq!(events[2].value, "releases");
+    }
+
+    #[test]
+    fn test_channel_timeline_from_info_and_history() {
+        let info: SlackConversation = serde_json::from_value(serde_json::json!({
+            "id": "C1", "name": "releases", "created": 1700000000, "creator": "U1"
+        }))
+        .unwrap();
+        let messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1700000300.000100", "user": "U3", "subtype": "channel_leave", "text": "<@U3> has left the channel" },
+            { "ts": "1700000250.000100", "user": "U1", "text": "welcome!" },
+            { "ts": "1700000200.000100", "user": "U3", "subtype": "channel_join", "text": "<@U3> has joined the channel" },
+            { "ts": "1700000100.000100", "user": "U2", "subtype": "channel_join", "text": "<@U2> has joined the channel" }
+        ]))
+        .unwrap();
+        let user_names = HashMap::from([
+            ("U1".to_string(), "alice".to_string()),
+            ("U2".to_string(), "bob".to_string()),
+        ]);
+
+        let timeline = channel_timeline(&info, Some(&messages), &user_names);
+        assert_eq!(timeline.created.as_deref(), Some("2023-11-14T22:13:20+00:00"));
+        assert_eq!(timeline.creator_name.as_deref(), Some("alice"));
+        assert!(timeline.history_accessible);
+        let events: Vec<(&str, &str)> = timeline
+            .membership_events
+            .iter()
+            .map(|e| (e.user_name.as_str(), e.kind.as_str()))
+            .collect();
+        assert_eq!(events, vec![("bob", "join"), ("U3", "join"), ("U3", "leave")]);
+
+        let without_history = channel_timeline(&info, None, &user_names);
+        assert!(!without_history.history_accessible);
+        assert!(without_history.membership_events.is_empty());
+    }
+
+    fn history(ts_list: &[&str]) -> Vec<SlackMessage> {
+        let messages: Vec<serde_json::Value> = ts_list
+            .iter()
+            .map(|ts| serde_json::json!({ "ts": ts, "user": "U1", "text": "msg" }))
+            .collect();
+        serde_json::from_value(serde_json::Value::Array(messages)).unwrap()
+    }
+
+    #[test]
+    fn test_messages_since_cursor() {
+        let messages = history(&["1700000300.000100", "1700000200.000100", "1700000100.000100"]);
+
+        let newer = messages_since(messages.clone(), Some("1700000200.000100"));
+        let ts: Vec<&str> = newer.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(ts, vec!["1700000300.000100"]);
+
+        let other = messages_since(messages, Some("1700000050.000000"));
+        assert_eq!(other.len(), 3);
+        assert_eq!(other[0].ts, "1700000100.000100");
+    }
+
+    #[test]
+    fn test_mark_read_ts_rejects_malformed() {
+        assert_eq!(mark_read_ts(" 1700000000.000100\n").unwrap(), "1700000000.000100");
+        assert!(matches!(mark_read_ts(""), Err(AppError::ParseError(_))));
+        assert!(matches!(mark_read_ts("p1700000000000100"), Err(AppError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_compute_local_unread() {
+        // conversations.history order: newest first; inclusive fetch repeats the cursor message
+        let messages = history(&["1700000300.000100", "1700000200.000100", "1700000100.000100"]);
+
+        let unread = compute_local_unread("C1", &messages, Some("1700000100.000100"));
+        assert_eq!(unread.unread_count, 2);
+        assert_eq!(unread.first_unread_ts.as_deref(), Some("1700000200.000100"));
+
+        let all = compute_local_unread("C1", &messages, None);
+        assert_eq!(all.unread_count, 3);
+
+        let none = compute_local_unread("C1", &messages, Some("1700000300.000100"));
+        assert_eq!(none.unread_count, 0);
+        assert_eq!(none.first_unread_ts, None);
+    }
+
+    #[test]
+    fn test_local_read_persistence_round_trip() {
+        let mut cursors = std::collections::HashMap::new();
+        cursors.insert("C1".to_string(), "1700000100.000100".to_string());
+        cursors.insert("D2".to_string(), "1700000200.000200".to_string());
+
+        let value = local_read_to_value(&cursors);
+        assert_eq!(local_read_from_value(&value), cursors);
+        assert!(local_read_from_value(&Value::Null).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_local_read_updates_state() {
+        let state = AppState::new();
+        assert!(state.get_local_read_cursors().await.is_none());
+
+        let cursors = state.set_local_read("C1".to_string(), "1.0".to_string()).await;
+        assert_eq!(cursors.get("C1").map(String::as_str), Some("1.0"));
+        assert_eq!(state.get_local_read_cursors().await, Some(cursors));
+    }
+
+    #[test]
+    fn test_channel_validity_not_found() {
+        let validity = channel_validity(None, Some("channel_not_found")).unwrap();
+        assert!(!validity.exists);
+        assert!(!validity.accessible);
+    }
+
+    #[test]
+    fn test_channel_validity_no_access() {
+        let validity = channel_validity(None, Some("missing_scope")).unwrap();
+        assert!(validity.exists);
+        assert!(!validity.accessible);
+    }
+
+    #[test]
+    fn test_export_format_from_param() {
+        assert_eq!(ExportFormat::from_param(None).unwrap(), ExportFormat::Markdown);
+        assert_eq!(ExportFormat::from_param(Some("MD")).unwrap(), ExportFormat::Markdown);
+        assert_eq!(ExportFormat::from_param(Some("Json")).unwrap(), ExportFormat::Json);
+        assert!(matches!(ExportFormat::from_param(Some("pdf")), Err(AppError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_channel_validity_auth_error() {
+        let body = r#"{"ok":false,"error":"invalid_auth"}"#;
+        let error = serde_json::from_str::<Value>(body).unwrap()["error"].as_str().map(str::to_string);
+
+        let result = channel_validity(None, error.as_deref());
+        assert!(matches!(result, Err(AppError::AuthError(_))));
+        assert!(matches!(channel_validity(None, Some("token_revoked")), Err(AppError::AuthError(_))));
+    }
+
+    #[test]
+    fn test_export_nests_threads_in_order() {
+        // conversations.history: newest first, spanning three days, with one broadcast reply
+        let history: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1700179200.000100", "user": "U2", "text": "day three" },
+            { "ts": "1700092900.000300", "thread_ts": "1700092800.000100", "user": "U1", "text": "broadcast", "subtype": "thread_broadcast" },
+            { "ts": "1700092800.000100", "user": "U1", "text": "day two parent", "reply_count": 2,
+              "reactions": [{ "name": "eyes", "count": 1, "users": ["U2"] }] },
+            { "ts": "1700006400.000100", "user": "U1", "text": "day one" }
+        ]))
+        .unwrap();
+        let replies: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1700092800.000100", "thread_ts": "1700092800.000100", "user": "U1", "text": "day two parent", "reply_count": 2 },
+            { "ts": "1700092900.000300", "thread_ts": "1700092800.000100", "user": "U1", "text": "broadcast" },
+            { "ts": "1700092850.000200", "thread_ts": "1700092800.000100", "user": "U2", "text": "first reply" }
+        ]))
+        .unwrap();
+        let threads = HashMap::from([("1700092800.000100".to_string(), replies)]);
+        let users = HashMap::from([
+            ("U1".to_string(), "alice".to_string()),
+            ("U2".to_string(), "bob".to_string()),
+        ]);
+
+        let exported = assemble_export(history, threads, &users);
+        let top: Vec<&str> = exported.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(top, vec!["day one", "day two parent", "day three"]);
+        let thread: Vec<&str> = exported[1].replies.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(thread, vec!["first reply", "broadcast"]);
+        assert_eq!(exported[1].replies[0].user_name, "bob");
+
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let markdown = render_export_markdown("general", &exported, utc);
+        assert!(markdown.contains("_3 messages, 2 thread replies_"));
+        let days: Vec<&str> = markdown.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(days, vec!["## 2023-11-15", "## 2023-11-16", "## 2023-11-17"]);
+        assert!(markdown.contains("> **bob** 00:00\n> first reply\n"));
+        assert!(markdown.contains(":eyes: 1"));
+        assert!(markdown.find("day two parent") < markdown.find("first reply"));
+    }
+
+    #[test]
+    fn test_export_bound_ts() {
+        assert_eq!(date_bound_ts("2023-11-15", false).unwrap(), "1700006400");
+        assert_eq!(date_bound_ts("2023-11-15", true).unwrap(), "1700092799");
+        assert!(date_bound_ts("15/11/2023", false).is_err());
+    }
+
+    #[test]
+    fn test_inlined_replies_are_flagged() {
+        let slack_message = |json| serde_json::from_value::<SlackMessage>(json).unwrap();
+        let convert = |msg| history_message(msg, "C1", "general", &HashMap::new(), 50, Some("acme"));
+
+        let parent = convert(slack_message(serde_json::json!({
+            "ts": "1700000000.000100", "thread_ts": "1700000000.000100", "text": "parent", "reply_count": 1
+        })));
+        let reply = convert(slack_message(serde_json::json!({
+            "ts": "1700000100.000200", "thread_ts": "1700000000.000100", "text": "reply"
+        })));
+        let standalone = convert(slack_message(serde_json::json!({
+            "ts": "1700000200.000300", "text": "no thread"
+        })));
+
+        assert!(parent.is_thread_parent && !parent.is_thread_reply);
+        assert!(reply.is_thread_reply);
+        assert_eq!(reply.thread_ts.as_deref(), Some("1700000000.000100"));
+        assert!(!standalone.is_thread_reply);
+        assert_eq!(serde_json::to_value(&reply).unwrap()["isThreadReply"], true);
+        assert_eq!(
+            reply.permalink,
+            "https://acme.slack.com/archives/C1/p1700000100000200?thread_ts=1700000000.000100&cid=C1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mute_is_kept_locally_when_api_unavailable() {
+        let mut local = HashSet::new();
+
+        let result = apply_channel_mute(&mut local, "C1", true, || async {
+            Err(anyhow::anyhow!("API error: not_allowed_token_type"))
+        })
+        .await;
+        assert!(result.muted);
+        assert!(!result.synced);
+        assert!(local.contains("C1"));
+
+        let result = apply_channel_mute(&mut local, "C1", false, || async { Ok(()) }).await;
+        assert!(result.synced);
+        assert!(local.is_empty());
+    }
+
+    #[test]
+    fn test_digest_groups_channels_and_counts_mentions() {
+        let since = "1700000000.000000";
+        let general: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1700000300.000100", "user": "U2", "text": "<@ME> can you review?" },
+            { "ts": "1700000200.000100", "user": "U3", "text": "ping <@ME|me> and <@U9>" },
+            { "ts": "1700000100.000100", "user": "U2", "text": "standup notes", "reply_count": 3,
+              "latest_reply": "1700000400.000100" },
+            { "ts": "1700000000.000000", "user": "U2", "text": "<@ME> already read" }
+        ]))
+        .unwrap();
+        let random: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1700000500.000100", "user": "U3", "text": "lunch?" }
+        ]))
+        .unwrap();
+        // Newest history without a cutoff: one thread revived after `since`, one gone quiet
+        let random_parents: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1700000500.000100", "user": "U3", "text": "lunch?" },
+            { "ts": "1699990000.000100", "user": "U3", "text": "old thread", "reply_count": 2,
+              "latest_reply": "1700000600.000100" },
+            { "ts": "1699980000.000100", "user": "U3", "text": "stale thread", "reply_count": 1,
+              "latest_reply": "1699980100.000100" }
+        ]))
+        .unwrap();
+
+        let channel = |id: &str, history: Vec<SlackMessage>, parents: Vec<SlackMessage>| {
+            let (mention_count, active_threads) = summarize_channel(&history, &parents, since, Some("ME"));
+            let messages: Vec<Message> = messages_since(history, Some(since))
+                .into_iter()
+                .map(|m| history_message(m, id, id, &HashMap::new(), 50, None))
+                .collect();
+            ChannelDigest {
+                channel_id: id.to_string(),
+                channel_name: id.to_string(),
+                message_count: messages.len(),
+                mention_count,
+                active_threads,
+                messages,
+                truncated: false,
+            }
+        };
+        let quiet = channel("quiet", Vec::new(), Vec::new());
+        let digest = assemble_digest(
+            since,
+            vec![
+                channel("random", random, random_parents),
+                quiet,
+                channel("general", general.clone(), general),
+            ],
+        );
+
+        let order: Vec<&str> = digest.channels.iter().map(|c| c.channel_id.as_str()).collect();
+        assert_eq!(order, vec!["general", "random"]);
+        assert_eq!(digest.channels[0].mention_count, 2);
+        assert_eq!(digest.channels[0].message_count, 3);
+        assert_eq!(digest.channels[0].active_threads.len(), 1);
+        assert_eq!(digest.channels[0].active_threads[0].thread_ts, "1700000100.000100");
+        assert_eq!(digest.channels[1].mention_count, 0);
+        assert_eq!(digest.channels[1].message_count, 1);
+        let revived: Vec<&str> = digest.channels[1].active_threads.iter().map(|t| t.thread_ts.as_str()).collect();
+        assert_eq!(revived, vec!["1699990000.000100"]);
+        assert_eq!(digest.total_messages, 4);
+        assert_eq!(digest.total_mentions, 2);
+    }
+}
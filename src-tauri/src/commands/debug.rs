@@ -1,9 +1,12 @@
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::slack::rate_limit::RateLimitStatus;
+use crate::slack::{build_search_query, SearchRequest, SlackClientConfig};
 use crate::state::AppState;
 use tauri::State;
 use tracing::info;
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 // Extended SlackUserInfo with bot and deleted fields
 #[derive(Debug, Deserialize)]
@@ -339,4 +342,279 @@ pub async fn debug_problematic_users(state: State<'_, AppState>) -> AppResult<St
 
     info!("[DEBUG] {}", result);
     Ok(result)
-}
\ No newline at end of file
+}
+
+/// Methods call_slack_method may invoke: read-only lookups, plus
+/// `conversations.kick`, the one write power users asked for
+const ALLOWED_SLACK_METHODS: &[&str] = &[
+    "api.test",
+    "auth.test",
+    "bookmarks.list",
+    "bots.info",
+    "conversations.history",
+    "conversations.info",
+    "conversations.kick",
+    "conversations.list",
+    "conversations.members",
+    "conversations.replies",
+    "dnd.info",
+    "dnd.teamInfo",
+    "emoji.list",
+    "files.info",
+    "files.list",
+    "pins.list",
+    "reactions.get",
+    "reactions.list",
+    "reminders.info",
+    "reminders.list",
+    "search.all",
+    "search.files",
+    "search.messages",
+    "stars.list",
+    "team.info",
+    "team.profile.get",
+    "usergroups.list",
+    "usergroups.users.list",
+    "users.conversations",
+    "users.getPresence",
+    "users.info",
+    "users.list",
+    "users.lookupByEmail",
+    "users.profile.get",
+];
+
+/// Check that `method` is on the allowlist
+///
+/// Anything else, including names that could escape the API base URL
+/// (slashes, query strings, full URLs), is rejected.
+fn validate_method_name(method: &str) -> AppResult<()> {
+    if !ALLOWED_SLACK_METHODS.contains(&method) {
+        return Err(AppError::ConfigError(format!(
+            "Slack method '{}' is not allowed",
+            method
+        )));
+    }
+
+    Ok(())
+}
+
+/// Flatten a JSON object of params into form fields
+///
+/// Strings are sent as-is; other values (numbers, bools, arrays, objects) are
+/// sent as their JSON text, which is how Slack expects e.g. `blocks`.
+fn method_params(params: &Value) -> AppResult<Vec<(String, String)>> {
+    match params {
+        Value::Null => Ok(Vec::new()),
+        Value::Object(map) => Ok(map
+            .iter()
+            .filter(|(_, v)| !v.is_null())
+            .map(|(k, v)| {
+                let value = match v {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (k.clone(), value)
+            })
+            .collect()),
+        _ => Err(AppError::ParseError(
+            "Slack method params must be a JSON object".to_string(),
+        )),
+    }
+}
+
+#[tauri::command]
+pub async fn set_advanced_mode(enabled: bool, state: State<'_, AppState>) -> AppResult<()> {
+    state.set_advanced_mode(enabled).await;
+    Ok(())
+}
+
+/// Call any Slack Web API method directly (advanced mode only)
+#[tauri::command]
+pub async fn call_slack_method(
+    method: String,
+    params: Value,
+    state: State<'_, AppState>,
+) -> AppResult<Value> {
+    if !state.is_advanced_mode().await {
+        return Err(AppError::ConfigError(
+            "Raw Slack method calls require advanced mode to be enabled".to_string(),
+        ));
+    }
+
+    validate_method_name(&method)?;
+    let form = method_params(&params)?;
+
+    info!("[DEBUG] Calling raw Slack method: {}", method);
+
+    let client = state.get_client().await?;
+    Ok(client.call_method(&method, &form).await?)
+}
+
+/// Current request pool availability and rate-limit backoff, so the UI can
+/// explain slowness ("slowing down due to Slack limits")
+#[tauri::command]
+pub async fn get_rate_limit_status(state: State<'_, AppState>) -> AppResult<RateLimitStatus> {
+    Ok(state.rate_limit_status().await)
+}
+
+/// How many times a rate-limited request is retried before it fails (default 3)
+#[tauri::command]
+pub async fn set_max_rate_limit_retries(state: State<'_, AppState>, max_retries: u32) -> AppResult<()> {
+    state.set_max_rate_limit_retries(max_retries).await;
+    Ok(())
+}
+
+/// Requests in flight, pagination delay and timeout for every Slack client
+#[tauri::command]
+pub async fn set_client_config(state: State<'_, AppState>, config: SlackClientConfig) -> AppResult<()> {
+    state.set_client_config(config).await;
+    Ok(())
+}
+
+/// Show the exact Slack query a search with these inputs would run
+///
+/// Sentinels such as `USE_CONVERSATIONS_HISTORY` are returned as-is, since
+/// they decide which API the search falls back to.
+#[tauri::command]
+pub async fn preview_search_query(
+    query: String,
+    channel: Option<String>,
+    user: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    has_files: Option<bool>,
+    is_realtime: Option<bool>,
+    file_extensions: Option<Vec<String>>,
+    dates_are_exact: Option<bool>,
+) -> AppResult<String> {
+    Ok(build_search_query(&SearchRequest {
+        query,
+        channel,
+        user,
+        from_date: from,
+        to_date: to,
+        limit: None,
+        is_realtime,
+        has_files,
+        file_extensions,
+        dates_are_exact,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_method_name_rejects_non_methods() {
+        assert!(validate_method_name("conversations.kick").is_ok());
+        assert!(validate_method_name("users.info").is_ok());
+
+        assert!(validate_method_name("").is_err());
+        assert!(validate_method_name("conversations").is_err());
+        assert!(validate_method_name("../oauth.access").is_err());
+        assert!(validate_method_name("https://evil.example.com/x.y").is_err());
+        assert!(validate_method_name("chat.postMessage?channel=C1").is_err());
+        assert!(validate_method_name("auth.revoke").is_err());
+        // Unlisted writes are rejected even though the name is well-formed
+        assert!(validate_method_name("chat.delete").is_err());
+        assert!(validate_method_name("admin.users.remove").is_err());
+    }
+
+    #[test]
+    fn test_method_params_passes_values() {
+        let params = serde_json::json!({
+            "channel": "C123",
+            "user": "U456",
+            "limit": 10,
+            "inclusive": true,
+            "blocks": [{ "type": "divider" }],
+            "cursor": null
+        });
+
+        let mut form = method_params(&params).unwrap();
+        form.sort();
+        assert_eq!(
+            form,
+            vec![
+                ("blocks".to_string(), r#"[{"type":"divider"}]"#.to_string()),
+                ("channel".to_string(), "C123".to_string()),
+                ("inclusive".to_string(), "true".to_string()),
+                ("limit".to_string(), "10".to_string()),
+                ("user".to_string(), "U456".to_string()),
+            ]
+        );
+
+        assert!(method_params(&Value::Null).unwrap().is_empty());
+        assert!(method_params(&serde_json::json!(["C123"])).is_err());
+    }
+
+    async fn preview(query: &str, channel: Option<&str>, user: Option<&str>, exts: Option<Vec<&str>>) -> String {
+        preview_search_query(
+            query.to_string(),
+            channel.map(String::from),
+            user.map(String::from),
+            Some("2024-03-10".to_string()),
+            Some("2024-03-12".to_string()),
+            None,
+            None,
+            exts.map(|e| e.into_iter().map(String::from).collect()),
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_preview_search_query() {
+        assert_eq!(
+            preview("deploy", Some("#general"), None, None).await,
+            "deploy in:general after:2024-03-09 before:2024-03-13"
+        );
+        assert_eq!(
+            preview("deploy", None, Some("<@U01ABCDEF23>"), None).await,
+            "deploy from:U01ABCDEF23 after:2024-03-09 before:2024-03-13"
+        );
+        // Single channel plus user, or plus file extensions, falls back to history
+        assert_eq!(
+            preview("", Some("general"), Some("U01ABCDEF23"), None).await,
+            "USE_CONVERSATIONS_HISTORY"
+        );
+        assert_eq!(
+            preview("report", Some("general"), None, Some(vec!["pdf"])).await,
+            "USE_CONVERSATIONS_HISTORY"
+        );
+        assert_eq!(
+            preview("hi", Some("👥 alice-bob"), None, None).await,
+            "INVALID_GROUP_DM_CHANNEL"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preview_exact_dates_skip_the_extra_day() {
+        let preview = |exact| {
+            preview_search_query(
+                "deploy".to_string(),
+                None,
+                None,
+                Some("2024-01-05T14:00:00Z".to_string()),
+                Some("2024-01-06T00:00:00Z".to_string()),
+                None,
+                None,
+                None,
+                exact,
+            )
+        };
+
+        // The heuristic widens to_date by a whole day, pulling in all of the 6th
+        assert_eq!(
+            preview(None).await.unwrap(),
+            "deploy after:2024-01-04 before:2024-01-07"
+        );
+        // An exact midnight end already excludes the 6th
+        assert_eq!(
+            preview(Some(true)).await.unwrap(),
+            "deploy after:2024-01-04 before:2024-01-06"
+        );
+    }
+}
@@ -1,9 +1,18 @@
-use crate::error::AppError;
-use crate::state::AppState;
+use crate::commands::cache::{compression_enabled, decode_cache, encode_cache};
+use crate::error::{AppError, AppResult};
+use crate::slack::parser::map_outside_code;
+use crate::slack::{HistoryOptions, SlackMessage, ThreadReplies};
+use crate::state::{AppState, CachedEmoji};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::State;
-use tracing::{error, info};
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
+use tracing::{error, info, warn};
+
+const EMOJI_STORE: &str = "emoji.dat";
+const EMOJI_STORE_KEY: &str = "emoji_cache";
+/// Upper bound on history scanned for emoji usage
+const USAGE_HISTORY_LIMIT: usize = 2000;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EmojiListResponse {
@@ -12,16 +21,72 @@ pub struct EmojiListResponse {
     pub error: Option<String>,
 }
 
+/// Store key for one workspace's emoji, so switching tokens never loads another team's set
+fn emoji_store_key(team_id: &str) -> String {
+    format!("{}:{}", EMOJI_STORE_KEY, team_id)
+}
+
+/// Seed the in-memory emoji cache from the store so cold starts skip emoji.list
+async fn load_persisted_emoji(app: &AppHandle, state: &AppState) {
+    if state.has_emoji_cache().await {
+        return;
+    }
+
+    let Some(team_id) = state.team_id().await else {
+        return;
+    };
+    let Ok(store) = app.store(EMOJI_STORE) else {
+        return;
+    };
+    if let Some(cached) = store
+        .get(emoji_store_key(&team_id))
+        .and_then(|value| decode_cache::<CachedEmoji>(value).ok())
+    {
+        info!("Loaded {} emojis from the store", cached.emoji.len());
+        state.load_emoji_cache(cached).await;
+    }
+}
+
+fn persist_emoji(app: &AppHandle, team_id: &str, cached: &CachedEmoji) -> AppResult<()> {
+    let store = app.store(EMOJI_STORE)?;
+    store.set(emoji_store_key(team_id), encode_cache(cached, compression_enabled(app))?);
+    // Drop the entry written before the store was keyed by workspace
+    store.delete(EMOJI_STORE_KEY);
+    store.save()?;
+    Ok(())
+}
+
 #[tauri::command]
-pub async fn get_emoji_list(state: State<'_, AppState>) -> Result<EmojiListResponse, AppError> {
-    info!("Getting emoji list from Slack");
-    
-    // Get the Slack client from app state (handles token retrieval and client creation)
-    let client = state.get_client().await?;
+pub async fn get_emoji_list(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+) -> Result<EmojiListResponse, AppError> {
+    info!("Getting emoji list");
+
+    load_persisted_emoji(&app, &state).await;
 
-    match client.get_emoji_list().await {
-        Ok(emoji_map) => {
-            info!("Successfully fetched {} emojis", emoji_map.len());
+    let fetched = state
+        .get_or_fetch_emoji(force_refresh.unwrap_or(false), || async {
+            // Get the Slack client from app state (handles token retrieval and client creation)
+            let client = state.get_client().await?;
+            Ok(client.get_emoji_list().await?)
+        })
+        .await;
+
+    match fetched {
+        Ok((emoji_map, refreshed)) => {
+            if let Some(cached) = refreshed {
+                info!("Successfully fetched {} emojis", emoji_map.len());
+                match state.team_id().await {
+                    Some(team_id) => {
+                        if let Err(e) = persist_emoji(&app, &team_id, &cached) {
+                            warn!("Failed to persist emoji cache: {}", e);
+                        }
+                    }
+                    None => warn!("Not persisting emoji cache without a team ID"),
+                }
+            }
             Ok(EmojiListResponse {
                 ok: true,
                 emoji: Some(emoji_map),
@@ -37,4 +102,239 @@ pub async fn get_emoji_list(state: State<'_, AppState>) -> Result<EmojiListRespo
             })
         }
     }
-}
\ No newline at end of file
+}
+
+/// How often one emoji shows up in a channel
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmojiUsage {
+    pub name: String,
+    pub count: usize,
+    /// Appearances as `:name:` in message text
+    pub in_text: usize,
+    /// Reactions, counting every person who reacted
+    pub in_reactions: usize,
+    /// Image URL for custom emoji; `None` for standard emoji
+    pub url: Option<String>,
+}
+
+/// Follow `alias:<target>` entries in the emoji.list map to the emoji they name
+fn canonical_emoji<'a>(name: &'a str, emoji: &'a HashMap<String, String>) -> &'a str {
+    emoji
+        .get(name)
+        .and_then(|value| value.strip_prefix("alias:"))
+        .unwrap_or(name)
+}
+
+/// Tally emoji from message text and reactions, most used first
+///
+/// Skin tones are folded into their base emoji and aliases into their target.
+fn tally_emoji(messages: &[SlackMessage], emoji: &HashMap<String, String>) -> Vec<EmojiUsage> {
+    let re = regex::Regex::new(r":([a-z0-9_+'\-]+):").unwrap();
+    let mut tally: HashMap<String, (usize, usize)> = HashMap::new();
+
+    for msg in messages {
+        map_outside_code(&msg.text, |segment| {
+            for cap in re.captures_iter(segment) {
+                if !cap[1].starts_with("skin-tone-") {
+                    tally.entry(canonical_emoji(&cap[1], emoji).to_string()).or_default().0 += 1;
+                }
+            }
+            segment.to_string()
+        });
+
+        for reaction in msg.reactions.iter().flatten() {
+            let base = reaction.name.split("::").next().unwrap_or(&reaction.name);
+            tally.entry(canonical_emoji(base, emoji).to_string()).or_default().1 += reaction.count as usize;
+        }
+    }
+
+    let mut usage: Vec<EmojiUsage> = tally
+        .into_iter()
+        .map(|(name, (in_text, in_reactions))| EmojiUsage {
+            url: emoji.get(&name).filter(|value| value.starts_with("http")).cloned(),
+            name,
+            count: in_text + in_reactions,
+            in_text,
+            in_reactions,
+        })
+        .collect();
+    usage.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    usage
+}
+
+/// Emoji used in a channel over the last `days` days, most used first
+#[tauri::command]
+pub async fn channel_emoji_usage(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    channel: String,
+    days: Option<u32>,
+) -> AppResult<Vec<EmojiUsage>> {
+    let days = days.unwrap_or(30).max(1);
+    let oldest = chrono::Utc::now().timestamp() - i64::from(days) * 86_400;
+
+    let client = state.get_client().await?;
+    let channel_id = client.resolve_channel_id(channel.trim()).await?;
+    let messages = client
+        .get_channel_messages_with_options(
+            &channel_id,
+            Some(oldest.to_string()),
+            None,
+            USAGE_HISTORY_LIMIT,
+            HistoryOptions {
+                thread_replies: ThreadReplies::Lazy,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    if messages.len() >= USAGE_HISTORY_LIMIT {
+        warn!("Channel {} hit the history limit; emoji usage covers the newest {} messages", channel_id, messages.len());
+    }
+
+    let emoji = get_emoji_list(app.clone(), state.clone(), None)
+        .await
+        .ok()
+        .and_then(|response| response.emoji)
+        .unwrap_or_default();
+
+    let usage = tally_emoji(&messages, &emoji);
+    info!("Found {} distinct emoji in {} messages from {}", usage.len(), messages.len(), channel_id);
+    Ok(usage)
+}
+
+/// Image URL for a custom emoji, following `alias:` chains to the emoji they name
+///
+/// Standard (unicode) emoji have no entry in emoji.list, or are the target
+/// of an alias, and have no image to fetch.
+fn resolve_emoji_url(name: &str, emoji: &HashMap<String, String>) -> AppResult<String> {
+    let mut current = name.trim().trim_matches(':');
+    let mut seen = std::collections::HashSet::new();
+
+    while seen.insert(current) {
+        match emoji.get(current) {
+            Some(value) => match value.strip_prefix("alias:") {
+                Some(target) => current = target,
+                None if value.starts_with("http") => return Ok(value.clone()),
+                None => break,
+            },
+            None => {
+                return Err(AppError::ApiError(format!(
+                    ":{}: is a standard emoji and has no image to download",
+                    current
+                )))
+            }
+        }
+    }
+
+    Err(AppError::ApiError(format!(":{}: does not resolve to a custom emoji image", name)))
+}
+
+/// Fetch the image bytes of a custom emoji for offline rendering
+#[tauri::command]
+pub async fn download_emoji(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> AppResult<Vec<u8>> {
+    let response = get_emoji_list(app, state.clone(), None).await?;
+    let emoji = match response.emoji {
+        Some(emoji) => emoji,
+        None => return Err(AppError::ApiError(response.error.unwrap_or_else(|| "Emoji list unavailable".to_string()))),
+    };
+
+    let url = resolve_emoji_url(&name, &emoji)?;
+    info!("Downloading emoji :{}: from {}", name.trim_matches(':'), url);
+
+    // The configured client carries the token and the request timeout
+    let client = state.get_client().await?;
+    let response = client.client.get(&url).send().await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        error!("Failed to download emoji {}: {}", name, status);
+        return Err(anyhow::anyhow!("Failed to download emoji: {}", status).into());
+    }
+
+    Ok(response.bytes().await?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tally_emoji_counts_text_and_reactions() {
+        let messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            {
+                "ts": "1.1",
+                "text": "shipped :tada: :partyparrot: :thumbsup::skin-tone-3:",
+                "reactions": [
+                    { "name": "tada", "count": 3, "users": ["U1", "U2", "U3"] },
+                    { "name": "thumbsup::skin-tone-2", "count": 1, "users": ["U4"] }
+                ]
+            },
+            {
+                "ts": "1.2",
+                "text": "`:tada:` in code doesn't count, :parrot: does",
+                "reactions": [{ "name": "partyparrot", "count": 2, "users": ["U1", "U2"] }]
+            }
+        ]))
+        .unwrap();
+        let emoji: HashMap<String, String> = [
+            ("partyparrot", "https://emoji.slack-edge.com/T1/partyparrot/abc.gif"),
+            ("parrot", "alias:partyparrot"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let usage = tally_emoji(&messages, &emoji);
+
+        assert_eq!(
+            usage[0],
+            EmojiUsage {
+                name: "partyparrot".to_string(),
+                count: 4,
+                in_text: 2,
+                in_reactions: 2,
+                url: Some("https://emoji.slack-edge.com/T1/partyparrot/abc.gif".to_string()),
+            }
+        );
+        assert_eq!(
+            usage[1],
+            EmojiUsage { name: "tada".to_string(), count: 4, in_text: 1, in_reactions: 3, url: None }
+        );
+        assert_eq!(
+            usage[2],
+            EmojiUsage { name: "thumbsup".to_string(), count: 2, in_text: 1, in_reactions: 1, url: None }
+        );
+        assert_eq!(usage.len(), 3);
+    }
+
+    #[test]
+    fn test_resolve_emoji_url_follows_alias_chain() {
+        let emoji: HashMap<String, String> = [
+            ("partyparrot", "https://emoji.slack-edge.com/T1/partyparrot/abc.gif"),
+            ("parrot", "alias:partyparrot"),
+            ("birb", "alias:parrot"),
+            ("yes", "alias:thumbsup"),
+            ("loop_a", "alias:loop_b"),
+            ("loop_b", "alias:loop_a"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        assert_eq!(
+            resolve_emoji_url(":birb:", &emoji).unwrap(),
+            "https://emoji.slack-edge.com/T1/partyparrot/abc.gif"
+        );
+        assert!(resolve_emoji_url("thumbsup", &emoji).is_err());
+        assert!(resolve_emoji_url("yes", &emoji)
+            .unwrap_err()
+            .to_string()
+            .contains("standard emoji"));
+        assert!(resolve_emoji_url("loop_a", &emoji).is_err());
+    }
+}
@@ -1,7 +1,9 @@
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 use crate::state::AppState;
 use tauri::{State, AppHandle};
+use tauri_plugin_store::StoreExt;
 use tracing::{info, error, debug};
+use std::collections::HashMap;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use std::path::{Path, PathBuf};
@@ -13,12 +15,81 @@ pub struct FileDownloadOptions {
     pub show_dialog: bool,
 }
 
+const FILE_UNAVAILABLE: &str =
+    "This file is no longer available. It may have been deleted from Slack.";
+
+/// Tombstoned (deleted) files carry no URL, so there is nothing to fetch
+fn ensure_file_url(url: &str) -> AppResult<()> {
+    if url.trim().is_empty() {
+        return Err(AppError::ApiError(FILE_UNAVAILABLE.to_string()));
+    }
+    Ok(())
+}
+
+/// Error for a failed file fetch; 404/410 mean the file has been deleted
+fn file_fetch_error(context: &str, status: reqwest::StatusCode) -> AppError {
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::GONE {
+        AppError::ApiError(FILE_UNAVAILABLE.to_string())
+    } else {
+        anyhow::anyhow!("{}: {}", context, status).into()
+    }
+}
+
+/// Slack file ID from a `files-pri/T123-F456/name` URL
+fn slack_file_id(url: &str) -> Option<String> {
+    url.split('/')
+        .filter_map(|segment| segment.split_once('-'))
+        .map(|(_, id)| id)
+        .find(|id| id.starts_with('F') && id.len() > 1 && id.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(String::from)
+}
+
+/// Previously downloaded copy of a file, if it's still on disk and not being forced
+fn existing_download(registry: &HashMap<String, String>, file_id: &str, force: bool) -> Option<PathBuf> {
+    if force {
+        return None;
+    }
+    let path = PathBuf::from(registry.get(file_id)?);
+    if path.is_file() {
+        Some(path)
+    } else {
+        debug!("Downloaded copy of {} is gone ({:?}), fetching again", file_id, path);
+        None
+    }
+}
+
+/// The download registry, loading it from the store on first use
+async fn downloaded_files(app: &AppHandle, state: &AppState) -> AppResult<HashMap<String, String>> {
+    if let Some(files) = state.get_downloaded_files().await {
+        return Ok(files);
+    }
+
+    let store = app.store("files.dat")?;
+    let files: HashMap<String, String> = store
+        .get("downloaded_files")
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    state.load_downloaded_files(files.clone()).await;
+    Ok(files)
+}
+
+async fn record_download(app: &AppHandle, state: &AppState, file_id: String, path: &Path) -> AppResult<()> {
+    let files = state
+        .record_download(file_id, path.to_string_lossy().to_string())
+        .await;
+    let store = app.store("files.dat")?;
+    store.set("downloaded_files", serde_json::to_value(&files)?);
+    store.save()?;
+    Ok(())
+}
+
 /// Get a file's content with authentication
 #[tauri::command]
 pub async fn get_slack_file(
     url: String,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<u8>> {
+    ensure_file_url(&url)?;
     let token = state.get_token().await?;
     
     info!("Fetching Slack file from URL: {}", url);
@@ -36,7 +107,7 @@ pub async fn get_slack_file(
     if !response.status().is_success() {
         let status = response.status();
         error!("Failed to fetch file: {}", status);
-        return Err(anyhow::anyhow!("Failed to fetch file: {}", status).into());
+        return Err(file_fetch_error("Failed to fetch file", status));
     }
     
     let bytes = response.bytes().await?;
@@ -50,6 +121,7 @@ pub async fn get_authenticated_file_url(
     url: String,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
+    ensure_file_url(&url)?;
     let token = state.get_token().await?;
     
     // For Slack files, we need to append the token as a query parameter
@@ -61,12 +133,29 @@ pub async fn get_authenticated_file_url(
 }
 
 /// Download a file from Slack to local filesystem
+///
+/// Files already downloaded (by Slack file ID) are not fetched again while the
+/// local copy exists; `force` downloads anyway.
 #[tauri::command]
 pub async fn download_slack_file(
+    app: AppHandle,
     url: String,
     file_name: String,
+    file_id: Option<String>,
+    force: Option<bool>,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
+    ensure_file_url(&url)?;
+
+    let file_id = file_id.or_else(|| slack_file_id(&url));
+    if let Some(ref id) = file_id {
+        let registry = downloaded_files(&app, &state).await?;
+        if let Some(path) = existing_download(&registry, id, force.unwrap_or(false)) {
+            info!("File {} already downloaded to {:?}", id, path);
+            return Ok(path.to_string_lossy().to_string());
+        }
+    }
+
     let token = state.get_token().await?;
     
     info!("Downloading Slack file: {} -> {}", url, file_name);
@@ -84,7 +173,7 @@ pub async fn download_slack_file(
     if !response.status().is_success() {
         let status = response.status();
         error!("Failed to download file: {}", status);
-        return Err(anyhow::anyhow!("Failed to download file: {}", status).into());
+        return Err(file_fetch_error("Failed to download file", status));
     }
     
     let bytes = response.bytes().await?;
@@ -102,6 +191,10 @@ pub async fn download_slack_file(
     file.flush().await?;
     
     info!("File downloaded successfully to: {:?}", file_path);
+
+    if let Some(id) = file_id {
+        record_download(&app, &state, id, &file_path).await?;
+    }
     
     Ok(file_path.to_string_lossy().to_string())
 }
@@ -116,6 +209,7 @@ pub async fn download_slack_file_with_options(
     app_handle: AppHandle,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
+    ensure_file_url(&url)?;
     let token = state.get_token().await?;
     
     info!("Downloading Slack file with options: {} -> {}", url, file_name);
@@ -173,7 +267,7 @@ pub async fn download_slack_file_with_options(
     if !response.status().is_success() {
         let status = response.status();
         error!("Failed to download file: {}", status);
-        return Err(anyhow::anyhow!("Failed to download file: {}", status).into());
+        return Err(file_fetch_error("Failed to download file", status));
     }
     
     let bytes = response.bytes().await?;
@@ -261,6 +355,10 @@ pub async fn download_slack_files_batch(
     
     for (url, file_name) in files {
         debug!("Downloading file: {}", file_name);
+        if ensure_file_url(&url).is_err() {
+            error!("Skipping {}: file is no longer available", file_name);
+            continue;
+        }
         
         // Fetch the file content
         let response = client
@@ -336,6 +434,7 @@ pub async fn get_file_content(
     encoding: Option<String>,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
+    ensure_file_url(&url)?;
     let token = state.get_token().await?;
 
     info!("Fetching file content from URL: {} (max_size: {}, encoding: {:?})", url, max_size, encoding);
@@ -353,7 +452,7 @@ pub async fn get_file_content(
     if !response.status().is_success() {
         let status = response.status();
         error!("Failed to fetch file content: {}", status);
-        return Err(anyhow::anyhow!("Failed to fetch file content: {}", status).into());
+        return Err(file_fetch_error("Failed to fetch file content", status));
     }
 
     // Get content length to check size
@@ -450,6 +549,7 @@ pub async fn create_file_data_url(
     mime_type: String,
     state: State<'_, AppState>,
 ) -> AppResult<String> {
+    ensure_file_url(&url)?;
     let token = state.get_token().await?;
 
     info!("Creating data URL for file: {}", url);
@@ -467,7 +567,7 @@ pub async fn create_file_data_url(
     if !response.status().is_success() {
         let status = response.status();
         error!("Failed to fetch file for data URL: {}", status);
-        return Err(anyhow::anyhow!("Failed to fetch file: {}", status).into());
+        return Err(file_fetch_error("Failed to fetch file", status));
     }
 
     let bytes = response.bytes().await?;
@@ -489,6 +589,7 @@ pub async fn download_file_binary(
     url: String,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<u8>> {
+    ensure_file_url(&url)?;
     let token = state.get_token().await?;
 
     info!("Downloading binary file from workspace {}: {}", workspace_id, url);
@@ -506,7 +607,7 @@ pub async fn download_file_binary(
     if !response.status().is_success() {
         let status = response.status();
         error!("Failed to fetch binary file: {}", status);
-        return Err(anyhow::anyhow!("Failed to fetch binary file: {}", status).into());
+        return Err(file_fetch_error("Failed to fetch binary file", status));
     }
 
     let bytes = response.bytes().await?;
@@ -514,4 +615,60 @@ pub async fn download_file_binary(
     info!("Downloaded {} bytes successfully", bytes.len());
 
     Ok(bytes.to_vec())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::SlackMessage;
+
+    #[test]
+    fn test_tombstoned_file_yields_friendly_error() {
+        let message: SlackMessage = serde_json::from_value(serde_json::json!({
+            "ts": "1700000000.000100",
+            "text": "see attached",
+            "files": [
+                { "id": "F1", "mode": "tombstone" },
+                { "id": "F2", "name": "report.pdf", "title": "report", "mimetype": "application/pdf",
+                  "size": 1024, "url_private": "https://files.slack.com/files-pri/T1-F2/report.pdf" }
+            ]
+        }))
+        .unwrap();
+
+        let files = message.files.unwrap();
+        assert!(files[0].unavailable);
+        assert!(!files[1].unavailable);
+
+        let url = files[0].url_private.clone().unwrap_or_default();
+        match ensure_file_url(&url) {
+            Err(AppError::ApiError(msg)) => assert_eq!(msg, FILE_UNAVAILABLE),
+            other => panic!("expected unavailable error, got {:?}", other.map(|_| ())),
+        }
+        assert!(ensure_file_url(files[1].url_private.as_deref().unwrap()).is_ok());
+
+        match file_fetch_error("Failed to fetch file", reqwest::StatusCode::NOT_FOUND) {
+            AppError::ApiError(msg) => assert_eq!(msg, FILE_UNAVAILABLE),
+            other => panic!("expected unavailable error, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_existing_download_short_circuits_until_deleted() {
+        assert_eq!(
+            slack_file_id("https://files.slack.com/files-pri/T01ABC-F07XYZ/report.pdf").as_deref(),
+            Some("F07XYZ")
+        );
+
+        let path = std::env::temp_dir().join(format!("psc-download-{}.txt", std::process::id()));
+        std::fs::write(&path, b"cached").unwrap();
+        let registry = HashMap::from([("F07XYZ".to_string(), path.to_string_lossy().to_string())]);
+
+        assert_eq!(existing_download(&registry, "F07XYZ", false), Some(path.clone()));
+        assert_eq!(existing_download(&registry, "F07XYZ", true), None);
+        assert_eq!(existing_download(&registry, "F000", false), None);
+
+        // Deleted locally: download again
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(existing_download(&registry, "F07XYZ", false), None);
+    }
+}
@@ -1,10 +1,12 @@
 pub mod auth;
+pub mod cache;
 pub mod channels;
 pub mod debug;
 pub mod emoji;
 pub mod export;
 pub mod files;
 pub mod mark;
+pub mod pins;
 pub mod post;
 pub mod reactions;
 pub mod search;
@@ -0,0 +1,123 @@
+//! Commands for listing, pinning and unpinning channel messages
+
+use crate::commands::search::search_result_message;
+use crate::error::AppResult;
+use crate::slack::models::{Message, SlackMessage};
+use crate::slack::parser::{build_slack_url, replace_user_mentions};
+use crate::state::{AppState, CachedUser};
+use std::collections::HashMap;
+use tauri::State;
+use tracing::info;
+
+/// Convert a pinned message to the search result shape, so pins render like hits
+fn pinned_message(
+    msg: SlackMessage,
+    channel_id: &str,
+    user_cache: &HashMap<String, CachedUser>,
+    channel_names: &HashMap<String, String>,
+    team_domain: Option<&str>,
+    reactor_cap: usize,
+) -> Message {
+    let user_id = msg.user.clone().or_else(|| msg.bot_id.clone()).unwrap_or_default();
+    let user_name = user_cache
+        .get(&user_id)
+        .map(|u| u.name.clone())
+        .or_else(|| msg.username.clone())
+        .unwrap_or_else(|| user_id.clone());
+    let channel_name = channel_names
+        .get(channel_id)
+        .cloned()
+        .unwrap_or_else(|| channel_id.to_string());
+    let permalink = msg
+        .permalink
+        .clone()
+        .unwrap_or_else(|| build_slack_url(team_domain, channel_id, &msg.ts, msg.thread_ts.as_deref()));
+    let text = replace_user_mentions(&msg.text, user_cache);
+    let reply_count = msg.reply_count;
+
+    let message = search_result_message(
+        SlackMessage {
+            permalink: Some(permalink),
+            ..msg
+        },
+        user_name,
+        (channel_id.to_string(), channel_name),
+        (text, None),
+        true,
+        reactor_cap,
+    );
+    // Unlike search.messages, pins.list reports reply counts
+    Message {
+        is_thread_parent: reply_count.unwrap_or(0) > 0 && !message.is_thread_reply,
+        reply_count,
+        ..message
+    }
+}
+
+/// Messages pinned in a channel, in the same format as search results
+#[tauri::command]
+pub async fn get_pinned_messages(state: State<'_, AppState>, channel: String) -> AppResult<Vec<Message>> {
+    info!("Getting pinned messages for channel: {}", channel);
+
+    let client = state.get_client().await?;
+    let pinned = client.get_pinned_messages(&channel).await?;
+
+    let user_cache = state.get_user_cache_full().await;
+    let channel_names = state.get_channel_cache().await;
+    let team_domain = state.team_domain().await;
+    let reactor_cap = state.reactor_cap().await;
+    let mut messages = Vec::with_capacity(pinned.len());
+    for msg in pinned {
+        let mut message =
+            pinned_message(msg, &channel, &user_cache, &channel_names, team_domain.as_deref(), reactor_cap);
+        message.text = state.resolve_subteam_mentions(message.text).await;
+        messages.push(message);
+    }
+
+    info!("Channel {} has {} pinned messages", channel, messages.len());
+    Ok(messages)
+}
+
+/// Pin a message; pinning it again does nothing
+#[tauri::command]
+pub async fn pin_message(state: State<'_, AppState>, channel: String, ts: String) -> AppResult<()> {
+    let client = state.get_client().await?;
+    client.pin_message(&channel, &ts).await?;
+    Ok(())
+}
+
+/// Unpin a message; unpinning one that isn't pinned does nothing
+#[tauri::command]
+pub async fn unpin_message(state: State<'_, AppState>, channel: String, ts: String) -> AppResult<()> {
+    let client = state.get_client().await?;
+    client.unpin_message(&channel, &ts).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_message_matches_search_shape() {
+        let msg: SlackMessage = serde_json::from_value(serde_json::json!({
+            "ts": "1700000100.000100", "user": "U1", "text": "ask <@U2> before deploying",
+            "reply_count": 4, "pinned_to": ["C1"]
+        }))
+        .unwrap();
+        let user_cache = HashMap::from([
+            ("U1".to_string(), CachedUser { name: "alice".to_string(), real_name: None, cached_at: 0 }),
+            ("U2".to_string(), CachedUser { name: "bob".to_string(), real_name: None, cached_at: 0 }),
+        ]);
+        let channel_names = HashMap::from([("C1".to_string(), "ops".to_string())]);
+
+        let message = pinned_message(msg, "C1", &user_cache, &channel_names, Some("acme"), 50);
+        assert_eq!(message.user_name, "alice");
+        assert_eq!(message.channel_name, "ops");
+        assert_eq!(message.text, "ask @bob before deploying");
+        assert_eq!(message.permalink, "https://acme.slack.com/archives/C1/p1700000100000100");
+        assert!(message.is_pinned);
+        assert!(message.is_thread_parent);
+        assert_eq!(message.reply_count, Some(4));
+    }
+}
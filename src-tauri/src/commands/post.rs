@@ -1,20 +1,205 @@
-use crate::slack::models::PostMessageResponse;
-use crate::state::AppState;
+use crate::slack::models::{PostMessageRequest, PostMessageResponse};
+use crate::slack::parser::{find_user_by_name, map_outside_code, replace_user_mentions, resolve_emoji_aliases};
+use crate::state::{AppState, CachedUser};
+use std::collections::HashMap;
+
+/// Slack errors returned when a username/icon override isn't allowed for the token
+const IDENTITY_REJECTED_ERRORS: &[&str] = &[
+    "not_allowed_token_type",
+    "invalid_arguments",
+    "missing_scope",
+];
+
+/// chat.update errors worth explaining, with what to tell the user
+const UPDATE_ERROR_HINTS: &[(&str, &str)] = &[
+    ("cant_update_message", "only messages you posted can be edited"),
+    ("message_not_found", "the message no longer exists; it may have been deleted"),
+    ("edit_window_closed", "the workspace's edit window for this message has closed"),
+];
+
+/// chat.postMessage rejects text longer than this many characters
+const SLACK_POST_LIMIT: usize = 40_000;
+
+const CODE_FENCE: &str = "```";
+
+/// Split `text` into posts of at most `limit` characters
+///
+/// Splits fall between lines where possible, and otherwise at whitespace so a
+/// `<@U123>` mention never straddles two posts. A code block cut by a split is
+/// closed at the end of one post and reopened at the start of the next.
+fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let fence_len = CODE_FENCE.len() + 1; // fence plus its newline
+    if text.chars().count() <= limit || limit <= fence_len * 2 {
+        return vec![text.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    let mut in_fence = false;
+
+    // Break lines that can't fit in any post into pieces first
+    let piece_limit = limit - fence_len * 2;
+    let pieces = text
+        .split('\n')
+        .flat_map(|line| split_long_line(line, piece_limit));
+
+    for piece in pieces {
+        let piece_len = piece.chars().count();
+        let separator = usize::from(!current.is_empty());
+        let toggles_fence = piece.matches(CODE_FENCE).count() % 2 == 1;
+        // A fence still open after this piece needs room to be closed before the post ends
+        let reserve = if in_fence != toggles_fence { fence_len } else { 0 };
+
+        if current_len + separator + piece_len + reserve > limit && !current.is_empty() {
+            if in_fence {
+                current.push('\n');
+                current.push_str(CODE_FENCE);
+            }
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+            if in_fence {
+                current.push_str(CODE_FENCE);
+                current_len = CODE_FENCE.len();
+            }
+        }
+
+        if !current.is_empty() {
+            current.push('\n');
+            current_len += 1;
+        }
+        current.push_str(&piece);
+        current_len += piece_len;
+
+        if toggles_fence {
+            in_fence = !in_fence;
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Cut a line longer than `limit` characters at whitespace, or anywhere outside `<...>`
+fn split_long_line(line: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+
+    while rest.chars().count() > limit {
+        let hard_end = rest.char_indices().nth(limit).map_or(rest.len(), |(i, _)| i);
+        let window = &rest[..hard_end];
+        let cut = match window.rfind(char::is_whitespace) {
+            Some(i) if i > 0 => i,
+            // No whitespace: back off to before an unclosed `<` so mentions stay whole
+            _ => match window.rfind('<') {
+                Some(i) if i > 0 && !window[i..].contains('>') => i,
+                _ => hard_end,
+            },
+        };
+
+        pieces.push(rest[..cut].to_string());
+        rest = rest[cut..].trim_start_matches(' ');
+    }
+
+    pieces.push(rest.to_string());
+    pieces
+}
+
+/// Build the chat.postMessage request for a top-level channel post
+///
+/// Empty overrides are dropped so they never reach Slack.
+fn build_channel_post_request(
+    channel_id: &str,
+    text: &str,
+    username: Option<String>,
+    icon_emoji: Option<String>,
+    icon_url: Option<String>,
+) -> PostMessageRequest {
+    PostMessageRequest {
+        channel: channel_id.to_string(),
+        text: text.to_string(),
+        thread_ts: None,
+        username: username.filter(|s| !s.is_empty()),
+        icon_emoji: icon_emoji.filter(|s| !s.is_empty()),
+        icon_url: icon_url.filter(|s| !s.is_empty()),
+    }
+}
+
+/// `text` with typed references linked as the preview shows them, so what is
+/// posted matches what was previewed
+async fn link_outgoing(state: &AppState, text: &str) -> String {
+    let users = state.get_user_cache_full().await;
+    let channels = state.get_channel_cache().await;
+    link_draft_references(text, &users, &channels)
+}
 
 #[tauri::command]
 pub async fn post_to_channel(
     state: tauri::State<'_, AppState>,
     channel_id: String,
     text: String,
+    username: Option<String>,
+    icon_emoji: Option<String>,
+    icon_url: Option<String>,
+    auto_split: Option<bool>,
 ) -> Result<PostMessageResponse, String> {
     let client = state.get_client().await.map_err(|e| e.to_string())?;
+    let text = link_outgoing(&state, &text).await;
+
+    let length = text.chars().count();
+    let chunks = if length <= SLACK_POST_LIMIT {
+        vec![text]
+    } else if auto_split.unwrap_or(false) {
+        split_message(&text, SLACK_POST_LIMIT)
+    } else {
+        return Err(format!(
+            "Failed to post message: text is {length} characters, over Slack's {SLACK_POST_LIMIT} limit (enable auto-split to post it in parts)"
+        ));
+    };
+
+    // Post the parts in order, answering with the first one
+    let mut posted: Option<PostMessageResponse> = None;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let request = build_channel_post_request(
+            &channel_id,
+            chunk,
+            username.clone(),
+            icon_emoji.clone(),
+            icon_url.clone(),
+        );
+        let has_identity =
+            request.username.is_some() || request.icon_emoji.is_some() || request.icon_url.is_some();
 
-    match client.post_message(&channel_id, &text, None).await {
+        match client.post_message_request(&request).await {
+            Ok(response) => {
+                posted.get_or_insert(response);
+            }
+            Err(e) => {
+                eprintln!("Failed to post message: {e:?}");
+                let progress = if i > 0 {
+                    format!(" after posting {} of {} parts", i, chunks.len())
+                } else {
+                    String::new()
+                };
+                let message = e.to_string();
+                if has_identity && IDENTITY_REJECTED_ERRORS.iter().any(|code| message.contains(code)) {
+                    return Err(format!(
+                        "Failed to post message{progress}: custom username/icon requires a bot token with chat:write.customize ({e})"
+                    ));
+                }
+                return Err(format!("Failed to post message{progress}: {e}"));
+            }
+        }
+    }
+
+    match posted.ok_or_else(|| "Failed to post message: nothing to post".to_string()) {
         Ok(mut response) => {
             // Get current user ID and name for the posted message
             if let Some(ref mut message) = response.message {
                 // Get current user ID from state
-                if let Ok((_, Some(user_id))) = client.test_auth().await {
+                if let Ok(Some(user_id)) = state.current_user_id().await {
                     // Set the user ID
                     message.user = user_id.clone();
                     
@@ -43,10 +228,7 @@ pub async fn post_to_channel(
             }
             Ok(response)
         }
-        Err(e) => {
-            eprintln!("Failed to post message: {e:?}");
-            Err(format!("Failed to post message: {e}"))
-        }
+        Err(e) => Err(e),
     }
 }
 
@@ -59,6 +241,7 @@ pub async fn post_thread_reply(
     reply_broadcast: Option<bool>,
 ) -> Result<PostMessageResponse, String> {
     let client = state.get_client().await.map_err(|e| e.to_string())?;
+    let text = link_outgoing(&state, &text).await;
 
     match client
         .post_message_with_broadcast(&channel_id, &text, Some(&thread_ts), reply_broadcast.unwrap_or(false))
@@ -68,7 +251,7 @@ pub async fn post_thread_reply(
             // Get current user ID and name for the posted message
             if let Some(ref mut message) = response.message {
                 // Get current user ID from state
-                if let Ok((_, Some(user_id))) = client.test_auth().await {
+                if let Ok(Some(user_id)) = state.current_user_id().await {
                     // Set the user ID
                     message.user = user_id.clone();
                     
@@ -104,6 +287,130 @@ pub async fn post_thread_reply(
     }
 }
 
+/// Error text for a failed chat.update, explaining the common Slack refusals
+fn update_error_message(error: &str) -> String {
+    match UPDATE_ERROR_HINTS.iter().find(|(code, _)| error.contains(code)) {
+        Some((_, hint)) => format!("Failed to edit message: {hint}"),
+        None => format!("Failed to edit message: {error}"),
+    }
+}
+
+/// Edit a message the user posted, e.g. to fix a typo without reposting
+#[tauri::command]
+pub async fn update_posted_message(
+    state: tauri::State<'_, AppState>,
+    channel_id: String,
+    ts: String,
+    text: String,
+) -> Result<PostMessageResponse, String> {
+    let client = state.get_client().await.map_err(|e| e.to_string())?;
+    let text = link_outgoing(&state, &text).await;
+
+    let length = text.chars().count();
+    if length > SLACK_POST_LIMIT {
+        return Err(format!(
+            "Failed to edit message: text is {length} characters, over Slack's {SLACK_POST_LIMIT} limit"
+        ));
+    }
+
+    match client.update_message(&channel_id, &ts, &text).await {
+        Ok(mut response) => {
+            if let Some(ref mut message) = response.message {
+                if let Ok(Some(user_id)) = state.current_user_id().await {
+                    message.user_name = state.get_user_cache().await.get(&user_id).cloned();
+                    message.user = user_id;
+                }
+            }
+            Ok(response)
+        }
+        Err(e) => {
+            eprintln!("Failed to update message: {e:?}");
+            Err(update_error_message(&e.to_string()))
+        }
+    }
+}
+
+/// Turn typed `@name` / `#channel` references into the tokens Slack will link
+///
+/// Only names found in the caches are converted; anything else stays as typed,
+/// just as Slack would leave it unlinked.
+fn link_draft_references(
+    draft: &str,
+    users: &HashMap<String, CachedUser>,
+    channels: &HashMap<String, String>,
+) -> String {
+    let re = regex::Regex::new(r"(^|[\s(])([@#])([A-Za-z0-9._\-]+)").unwrap();
+
+    map_outside_code(draft, |segment| {
+        re.replace_all(segment, |cap: &regex::Captures| {
+            let name = &cap[3];
+            let linked = match &cap[2] {
+                "@" => find_user_by_name(name, users).map(|id| format!("<@{}>", id)),
+                _ => channels
+                    .iter()
+                    .find(|(_, channel)| channel.eq_ignore_ascii_case(name))
+                    .map(|(id, _)| format!("<#{}>", id)),
+            };
+            format!("{}{}", &cap[1], linked.unwrap_or_else(|| cap[0][cap[1].len()..].to_string()))
+        })
+        .into_owned()
+    })
+}
+
+/// Show channel links (`<#C123>`, `<#C123|general>`) and broadcasts (`<!here>`) as they read
+fn replace_channel_links(text: &str, channels: &HashMap<String, String>) -> String {
+    let channel_re = regex::Regex::new(r"<#([CGD][A-Z0-9]+)(?:\|([^>]*))?>").unwrap();
+    let broadcast_re = regex::Regex::new(r"<!(here|channel|everyone)(?:\|[^>]*)?>").unwrap();
+
+    map_outside_code(text, |segment| {
+        let segment = channel_re.replace_all(segment, |cap: &regex::Captures| {
+            match (channels.get(&cap[1]), cap.get(2).filter(|label| !label.as_str().is_empty())) {
+                (Some(name), _) => format!("#{}", name),
+                (None, Some(label)) => format!("#{}", label.as_str()),
+                (None, None) => cap[0].to_string(),
+            }
+        });
+        broadcast_re.replace_all(&segment, "@$1").into_owned()
+    })
+}
+
+/// How `draft` will read once posted: typed references linked the way Slack
+/// links them, then shown through the same mention and emoji resolution as
+/// received messages
+///
+/// `&`, `<` and `>` are escaped by Slack on send and unescaped for display, so
+/// they come out as typed.
+fn render_draft(
+    draft: &str,
+    users: &HashMap<String, CachedUser>,
+    channels: &HashMap<String, String>,
+    emoji: &HashMap<String, String>,
+) -> String {
+    let linked = link_draft_references(draft, users, channels);
+    let shown = replace_channel_links(&replace_user_mentions(&linked, users), channels);
+    resolve_emoji_aliases(&shown, emoji)
+}
+
+/// Preview how a draft will look once posted, for the composer
+#[tauri::command]
+pub async fn render_preview(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    text: String,
+) -> Result<String, String> {
+    let users = state.get_user_cache_full().await;
+    let channels = state.get_channel_cache().await;
+    // Without the emoji list only aliases stay unresolved, so the preview still works
+    let emoji = crate::commands::emoji::get_emoji_list(app, state.clone(), None)
+        .await
+        .ok()
+        .and_then(|response| response.emoji)
+        .unwrap_or_default();
+
+    let rendered = render_draft(&text, &users, &channels, &emoji);
+    Ok(state.resolve_subteam_mentions(rendered).await)
+}
+
 #[tauri::command]
 pub async fn check_posting_permissions(state: tauri::State<'_, AppState>) -> Result<bool, String> {
     let client = state.get_client().await.map_err(|e| e.to_string())?;
@@ -118,3 +425,119 @@ pub async fn check_posting_permissions(state: tauri::State<'_, AppState>) -> Res
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_draft_resolves_mentions_and_emoji() {
+        let users = HashMap::from([
+            ("U1".to_string(), CachedUser { name: "alice".to_string(), real_name: None, cached_at: 0 }),
+            ("U2".to_string(), CachedUser { name: "bob".to_string(), real_name: None, cached_at: 0 }),
+        ]);
+        let channels = HashMap::from([("C1".to_string(), "releases".to_string())]);
+        let emoji = HashMap::from([("party".to_string(), "alias:tada".to_string())]);
+
+        let rendered = render_draft(
+            "@Alice shipped :party: in #Releases, cc <@U2> <!here> `@alice` mail@alice @nobody",
+            &users,
+            &channels,
+            &emoji,
+        );
+        assert_eq!(
+            rendered,
+            "@alice shipped :tada: in #releases, cc @bob @here `@alice` mail@alice @nobody"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_outgoing_text_is_linked_like_the_preview() {
+        let state = AppState::new();
+        state.cache_user("U1".to_string(), "alice".to_string(), None).await;
+        state.cache_channel("C1".to_string(), "releases".to_string(), false, false).await;
+
+        let sent = link_outgoing(&state, "@alice see #releases, not `@alice` or @nobody").await;
+        assert_eq!(sent, "<@U1> see <#C1>, not `@alice` or @nobody");
+    }
+
+    #[test]
+    fn test_identity_fields_included_when_provided() {
+        let request = build_channel_post_request(
+            "C123",
+            "status update",
+            Some("Deploy Bot".to_string()),
+            Some(":rocket:".to_string()),
+            Some("https://example.com/icon.png".to_string()),
+        );
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["channel"], "C123");
+        assert_eq!(body["username"], "Deploy Bot");
+        assert_eq!(body["icon_emoji"], ":rocket:");
+        assert_eq!(body["icon_url"], "https://example.com/icon.png");
+    }
+
+    #[test]
+    fn test_split_message_keeps_code_fences_balanced() {
+        let log_line = "2024-01-05T14:00:00Z INFO worker processed batch 42 for <@U01ABCDEF23>";
+        let log: Vec<&str> = std::iter::repeat_n(log_line, 40).collect();
+        let text = format!("Build failed:\n```\n{}\n```\nPlease take a look.", log.join("\n"));
+
+        let chunks = split_message(&text, 500);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 500, "chunk too long: {}", chunk.len());
+            assert_eq!(chunk.matches(CODE_FENCE).count() % 2, 0, "unbalanced fence in {chunk}");
+            assert_eq!(chunk.matches("<@").count(), chunk.matches("U01ABCDEF23>").count());
+        }
+        assert!(chunks[0].starts_with("Build failed:\n```"));
+        assert!(chunks.last().unwrap().ends_with("```\nPlease take a look."));
+
+        // Dropping the added fences gives back every original line in order
+        let rejoined: Vec<&str> = chunks
+            .iter()
+            .flat_map(|c| c.lines())
+            .filter(|line| *line != CODE_FENCE)
+            .collect();
+        let original: Vec<&str> = text.lines().filter(|line| *line != CODE_FENCE).collect();
+        assert_eq!(rejoined, original);
+
+        assert_eq!(split_message("short", 500), vec!["short".to_string()]);
+    }
+
+    #[test]
+    fn test_split_long_line_never_cuts_a_mention() {
+        let line = format!("{}<@U01ABCDEF23>", "x".repeat(95));
+        let pieces = split_long_line(&line, 100);
+        assert_eq!(pieces, vec!["x".repeat(95), "<@U01ABCDEF23>".to_string()]);
+
+        let words = split_long_line("alpha beta gamma delta", 11);
+        assert_eq!(words, vec!["alpha beta", "gamma delta"]);
+    }
+
+    #[test]
+    fn test_identity_fields_omitted_when_absent() {
+        let request = build_channel_post_request("C123", "hello", None, Some(String::new()), None);
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert!(body.get("username").is_none());
+        assert!(body.get("icon_emoji").is_none());
+        assert!(body.get("icon_url").is_none());
+        assert!(body.get("thread_ts").is_none());
+    }
+
+    #[test]
+    fn test_update_errors_are_explained() {
+        assert_eq!(
+            update_error_message("API error: cant_update_message"),
+            "Failed to edit message: only messages you posted can be edited"
+        );
+        assert!(update_error_message("API error: message_not_found").contains("no longer exists"));
+        assert_eq!(
+            update_error_message("API error: channel_not_found"),
+            "Failed to edit message: API error: channel_not_found"
+        );
+    }
+}
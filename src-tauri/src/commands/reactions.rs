@@ -1,11 +1,98 @@
-use crate::error::AppResult;
+use crate::commands::emoji::get_emoji_list;
+use crate::error::{AppError, AppResult};
 use crate::slack::SlackReaction;
 use crate::state::AppState;
-use tauri::State;
-use tracing::{error, info};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use tauri::{AppHandle, State};
+use tracing::{error, info, warn};
+
+/// Slack's standard emoji names, the same data the frontend's STANDARD_EMOJIS is generated from
+const STANDARD_EMOJI_MAPPING: &str = include_str!("../../../scripts/slack_emoji_mapping.json");
+
+/// Standard emoji the mapping leaves out, mostly ones shown as text without U+FE0F
+const EXTRA_STANDARD_EMOJI: &[&str] = &[
+    "a", "airplane", "alembic", "arrow_backward", "arrow_down", "arrow_forward", "arrow_heading_down",
+    "arrow_heading_up", "arrow_left", "arrow_lower_left", "arrow_lower_right", "arrow_right",
+    "arrow_right_hook", "arrow_up", "arrow_up_down", "arrow_upper_left", "arrow_upper_right",
+    "atom_symbol", "b", "ballot_box_with_check", "bangbang", "biohazard_sign", "black_medium_square",
+    "black_nib", "black_small_square", "candle", "chains", "clubs", "coffin", "comet", "congratulations",
+    "copyright", "crossed_swords", "desktop_computer", "diamonds", "dove_of_peace", "eight_pointed_black_star",
+    "eight_spoked_asterisk", "eject", "email", "eye", "female_sign", "fleur_de_lis", "funeral_urn", "gear",
+    "hammer_and_pick", "heart", "hearts", "heavy_check_mark", "heavy_heart_exclamation_mark_ornament",
+    "heavy_multiplication_x", "hotsprings", "infinity", "information_source", "interrobang", "keyboard",
+    "latin_cross", "left_right_arrow", "leftwards_arrow_with_hook", "level_slider", "m", "male_sign",
+    "medical_symbol", "mountain", "o2", "orthodox_cross", "parking", "part_alternation_mark",
+    "peace_symbol", "pencil2", "phone", "point_up", "printer", "radioactive_sign", "recycle",
+    "registered", "relaxed", "sa", "scales", "scissors", "secret", "shamrock", "shield", "skull_and_crossbones",
+    "snowflake", "snowman", "spades", "sparkle", "spider", "spider_web", "star_and_crescent",
+    "star_of_david", "sunny", "telephone", "thermometer", "tm", "u6708", "umbrella", "v", "warning",
+    "wavy_dash", "wheel_of_dharma", "white_frowning_face", "white_medium_square", "white_small_square",
+    "writing_hand", "yin_yang",
+];
+
+fn standard_emoji() -> &'static HashSet<String> {
+    static NAMES: OnceLock<HashSet<String>> = OnceLock::new();
+    NAMES.get_or_init(|| {
+        let mapping: HashMap<String, String> = serde_json::from_str(STANDARD_EMOJI_MAPPING).unwrap_or_default();
+        mapping
+            .into_keys()
+            .chain(EXTRA_STANDARD_EMOJI.iter().map(|name| name.to_string()))
+            .collect()
+    })
+}
+
+fn unknown_emoji(raw: &str) -> AppError {
+    AppError::ApiError(format!("unknown emoji :{}:", raw.trim().trim_matches(':')))
+}
+
+/// Turn what the user typed into the name reactions.add expects
+///
+/// Strips surrounding colons and accepts a skin tone written as
+/// `:thumbsup::skin-tone-2:`, `thumbsup:skin-tone-2` or `thumbsup::skin-tone-2`.
+/// Anything that can't be an emoji name is rejected before reaching Slack.
+fn normalize_emoji_name(raw: &str) -> AppResult<String> {
+    let trimmed = raw.trim().trim_matches(':').to_lowercase();
+    let parts: Vec<&str> = trimmed.split(':').filter(|p| !p.is_empty()).collect();
+
+    let is_name = |name: &str| {
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '\''))
+    };
+    let (base, tone) = match parts.as_slice() {
+        [base] => (*base, None),
+        [base, tone] => (*base, Some(*tone)),
+        _ => return Err(unknown_emoji(raw)),
+    };
+    if !is_name(base) || base.starts_with("skin-tone-") {
+        return Err(unknown_emoji(raw));
+    }
+
+    match tone {
+        None => Ok(base.to_string()),
+        Some(tone @ ("skin-tone-2" | "skin-tone-3" | "skin-tone-4" | "skin-tone-5" | "skin-tone-6")) => {
+            Ok(format!("{}::{}", base, tone))
+        }
+        Some(_) => Err(unknown_emoji(raw)),
+    }
+}
+
+/// Check a normalized name against the standard set and this workspace's custom emoji
+///
+/// The skin tone suffix is left out of the lookup; normalize_emoji_name already
+/// checked it.
+fn ensure_known_emoji(name: &str, custom: &HashMap<String, String>) -> AppResult<()> {
+    let base = name.split("::").next().unwrap_or(name);
+    if standard_emoji().contains(base) || custom.contains_key(base) {
+        Ok(())
+    } else {
+        Err(unknown_emoji(name))
+    }
+}
 
 #[tauri::command]
 pub async fn add_reaction(
+    app: AppHandle,
     state: State<'_, AppState>,
     channel: String,
     timestamp: String,
@@ -16,20 +103,55 @@ pub async fn add_reaction(
         emoji, timestamp, channel
     );
 
+    let name = normalize_emoji_name(&emoji)?;
+    // Without the custom list a custom name can't be told from a typo, so leave that to Slack
+    match get_emoji_list(app, state.clone(), None).await?.emoji {
+        Some(custom) => ensure_known_emoji(&name, &custom)?,
+        None => warn!("Custom emoji unavailable, not checking :{}: before adding", name),
+    }
     let client = state.get_client().await?;
 
-    match client.add_reaction(&channel, &timestamp, &emoji).await {
+    match client.add_reaction(&channel, &timestamp, &name).await {
         Ok(_) => {
             info!("Successfully added reaction");
+            state.invalidate_reactions(&channel, &timestamp).await;
             Ok(())
         }
         Err(e) => {
             error!("Failed to add reaction: {}", e);
-            Err(e.into())
+            // Neither the standard set nor this workspace's custom emoji has the name
+            match AppError::from(e) {
+                AppError::ApiError(code) if code == "invalid_name" => Err(unknown_emoji(&emoji)),
+                other => Err(other),
+            }
         }
     }
 }
 
+/// Check that `user_id` is among the reactors for `emoji`
+///
+/// reactions.remove can only remove the caller's own reaction, so anything
+/// else would fail with an unhelpful `no_reaction`. Slack truncates the
+/// users list on busy reactions; when that happens we can't be sure and let
+/// the removal proceed.
+fn ensure_user_reacted(
+    reactions: &[SlackReaction],
+    emoji: &str,
+    user_id: &str,
+) -> Result<(), String> {
+    let name = emoji.trim_matches(':');
+
+    let reacted = reactions.iter().find(|r| r.name == name).map(|r| {
+        r.users.iter().any(|u| u == user_id) || (r.count as usize) > r.users.len()
+    });
+
+    if reacted.unwrap_or(false) {
+        Ok(())
+    } else {
+        Err(format!("You haven't reacted with :{}: on this message", name))
+    }
+}
+
 #[tauri::command]
 pub async fn remove_reaction(
     state: State<'_, AppState>,
@@ -42,11 +164,27 @@ pub async fn remove_reaction(
         emoji, timestamp, channel
     );
 
+    let name = normalize_emoji_name(&emoji)?;
     let client = state.get_client().await?;
 
-    match client.remove_reaction(&channel, &timestamp, &emoji).await {
+    // An empty list from a failed lookup would look like "not reacted", so a
+    // failed fetch skips the guard and leaves the verdict to reactions.remove
+    if let Some(user_id) = state.current_user_id().await? {
+        match client.get_reactions_checked(&channel, &timestamp).await {
+            Ok(reactions) => {
+                if let Err(msg) = ensure_user_reacted(&reactions, &name, &user_id) {
+                    info!("Not removing reaction {}: {}", emoji, msg);
+                    return Err(AppError::ApiError(msg));
+                }
+            }
+            Err(e) => warn!("Couldn't check existing reactions, removing anyway: {}", e),
+        }
+    }
+
+    match client.remove_reaction(&channel, &timestamp, &name).await {
         Ok(_) => {
             info!("Successfully removed reaction");
+            state.invalidate_reactions(&channel, &timestamp).await;
             Ok(())
         }
         Err(e) => {
@@ -56,6 +194,23 @@ pub async fn remove_reaction(
     }
 }
 
+/// How many reactor IDs to keep per reaction; reactions with more are flagged `users_truncated`
+#[tauri::command]
+pub async fn set_reactor_cap(state: State<'_, AppState>, cap: usize) -> AppResult<()> {
+    if cap == 0 {
+        return Err(AppError::ConfigError("Reactor cap must be at least 1".to_string()));
+    }
+    state.set_reactor_cap(cap).await;
+    Ok(())
+}
+
+/// How many seconds fetched reactions are reused before asking Slack again
+#[tauri::command]
+pub async fn set_reaction_cache_ttl(state: State<'_, AppState>, ttl_secs: u64) -> AppResult<()> {
+    state.set_reaction_cache_ttl(ttl_secs).await;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_reactions(
     state: State<'_, AppState>,
@@ -80,3 +235,91 @@ pub async fn get_reactions(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reaction(name: &str, users: &[&str]) -> SlackReaction {
+        SlackReaction {
+            name: name.to_string(),
+            count: users.len() as u32,
+            users: users.iter().map(|u| u.to_string()).collect(),
+            users_truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_guard_rejects_when_user_not_a_reactor() {
+        let reactions = vec![reaction("thumbsup", &["U2", "U3"])];
+
+        let err = ensure_user_reacted(&reactions, "thumbsup", "U1").unwrap_err();
+        assert!(err.contains(":thumbsup:"));
+        assert!(ensure_user_reacted(&reactions, "tada", "U1").is_err());
+    }
+
+    #[test]
+    fn test_guard_allows_own_reaction() {
+        let reactions = vec![reaction("thumbsup", &["U1", "U2"])];
+        assert!(ensure_user_reacted(&reactions, ":thumbsup:", "U1").is_ok());
+    }
+
+    #[test]
+    fn test_emoji_name_colons_are_stripped() {
+        assert_eq!(normalize_emoji_name(":tada:").unwrap(), "tada");
+        assert_eq!(normalize_emoji_name(" +1 ").unwrap(), "+1");
+        assert_eq!(normalize_emoji_name(":Party-Parrot:").unwrap(), "party-parrot");
+        assert_eq!(normalize_emoji_name(":thumbsup::skin-tone-3:").unwrap(), "thumbsup::skin-tone-3");
+        assert_eq!(normalize_emoji_name("wave:skin-tone-5").unwrap(), "wave::skin-tone-5");
+    }
+
+    #[test]
+    fn test_malformed_emoji_names_are_rejected() {
+        for raw in ["", "::", "two words", ":wave::skin-tone-9:", ":skin-tone-2:", "a:b:c"] {
+            let err = normalize_emoji_name(raw).unwrap_err();
+            assert!(err.to_string().contains("unknown emoji :"), "accepted {:?}", raw);
+        }
+        assert_eq!(
+            unknown_emoji(":foo:").to_string(),
+            "API error: unknown emoji :foo:"
+        );
+    }
+
+    #[test]
+    fn test_unknown_emoji_names_are_rejected_before_slack() {
+        let custom = HashMap::from([("partyparrot".to_string(), "https://emoji.example/pp.gif".to_string())]);
+
+        assert!(ensure_known_emoji("thumbsup", &custom).is_ok());
+        assert!(ensure_known_emoji("heart", &custom).is_ok());
+        assert!(ensure_known_emoji("wave::skin-tone-3", &custom).is_ok());
+        assert!(ensure_known_emoji("partyparrot", &custom).is_ok());
+
+        let name = normalize_emoji_name(":definitely_not_an_emoji:").unwrap();
+        let err = ensure_known_emoji(&name, &custom).unwrap_err();
+        assert_eq!(err.to_string(), "API error: unknown emoji :definitely_not_an_emoji:");
+    }
+
+    #[test]
+    fn test_reactors_beyond_cap_are_truncated() {
+        let users: Vec<String> = (0..200).map(|i| format!("U{}", i)).collect();
+        let refs: Vec<&str> = users.iter().map(String::as_str).collect();
+
+        let mut busy = reaction("fire", &refs);
+        busy.cap_users(50);
+        assert_eq!(busy.users.len(), 50);
+        assert_eq!(busy.count, 200);
+        assert!(busy.users_truncated);
+
+        let mut small = reaction("eyes", &["U1", "U2"]);
+        small.cap_users(50);
+        assert_eq!(small.users.len(), 2);
+        assert!(!small.users_truncated);
+    }
+
+    #[test]
+    fn test_guard_allows_truncated_user_list() {
+        let mut busy = reaction("eyes", &["U2"]);
+        busy.count = 80;
+        assert!(ensure_user_reacted(&[busy], "eyes", "U1").is_ok());
+    }
+}
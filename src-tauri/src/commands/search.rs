@@ -1,23 +1,522 @@
-use crate::error::{AppError, AppResult};
+use crate::error::{AppError, AppResult, SlackApiError};
+use crate::commands::channels::{
+    channel_validity, date_bound_ts, format_ts, low_reaction_channel_ids, ChannelValidity,
+};
+use crate::slack::models::{
+    capped_reactions, MessageSummary, RecentSearch, ResultReason, SearchOptions, SearchSort,
+    SlackConversation, SlackUserProfile,
+};
+use crate::slack::client::is_user_id;
 use crate::slack::{
     build_search_query, fetch_all_results, Message, SearchRequest, SearchResult, SlackClient,
-    SlackMessage, SlackReaction, SlackUser, SlackChannelInfo,
+    SlackMessage, SlackReaction, SlackUser, SlackChannelInfo, HistoryOptions, ThreadReplies,
 };
 use anyhow::anyhow;
-use crate::state::{AppState, CachedUser};
+use crate::slack::parser::{build_slack_url, find_user_by_name, parse_inline_operators, preview};
+use crate::state::{AppState, CachedUser, ReplyInfo, SearchCacheKey};
 use futures::future::join_all;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
-use tauri::State;
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
 use tracing::{debug, error, info, warn};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 
 fn replace_user_mentions(text: &str, user_cache: &HashMap<String, CachedUser>) -> String {
     crate::slack::parser::replace_user_mentions(text, user_cache)
 }
 
+/// Ways a search came back incomplete, shared by the parallel channel fetches
+#[derive(Default)]
+struct Truncation {
+    /// Slack's 100-page search limit cut results off
+    api_limit: AtomicBool,
+    /// Slack warned that a response was truncated
+    by_slack: AtomicBool,
+}
+
+/// Result count used when a search doesn't ask for one
+pub const DEFAULT_RESULT_LIMIT: usize = 100;
+/// Largest result count a search may request
+pub const MAX_RESULT_LIMIT: usize = 5000;
+
+/// Apply the default limit and clamp requests above the maximum
+fn resolve_result_limit(limit: Option<usize>) -> usize {
+    match limit {
+        None | Some(0) => DEFAULT_RESULT_LIMIT,
+        Some(limit) if limit > MAX_RESULT_LIMIT => {
+            warn!(
+                "Requested limit {} exceeds the maximum; clamping to {}",
+                limit, MAX_RESULT_LIMIT
+            );
+            MAX_RESULT_LIMIT
+        }
+        Some(limit) => limit,
+    }
+}
+
+/// Unwrap fetched search pages, remembering if they came back incomplete
+fn take_fetched(fetched: crate::slack::client::FetchedResults, truncation: &Truncation) -> Vec<SlackMessage> {
+    if fetched.truncated_at_api_limit {
+        truncation.api_limit.store(true, Ordering::Relaxed);
+    }
+    if fetched.truncated_by_slack {
+        truncation.by_slack.store(true, Ordering::Relaxed);
+    }
+    fetched.messages
+}
+
+/// Search every channel in parallel, then retry the channels that errored once
+///
+/// Typed errors that a retry can't fix (bad token, unknown channel, bad JSON) are
+/// not retried. Returns the combined messages and the channels that still failed.
+async fn search_channels_with_retry<F, Fut>(
+    channels: &[String],
+    search: F,
+) -> (Vec<SlackMessage>, Vec<String>)
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<Vec<SlackMessage>>>,
+{
+    let mut messages = Vec::new();
+    let mut failed = Vec::new();
+    let mut still_failing = Vec::new();
+
+    let results = join_all(channels.iter().map(|ch| search(ch.clone()))).await;
+    for (channel, result) in channels.iter().zip(results) {
+        match result {
+            Ok(found) => messages.extend(found),
+            Err(e)
+                if e.downcast_ref::<AppError>().is_some_and(|e| !e.is_transient())
+                    || SlackApiError::of(&e).is_some_and(|e| !e.is_transient()) =>
+            {
+                error!("Search failed for channel '{}', not retrying: {}", channel, e);
+                still_failing.push(channel.clone());
+            }
+            Err(e) => {
+                warn!("Search failed for channel '{}', will retry: {}", channel, e);
+                failed.push(channel.clone());
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        return (messages, still_failing);
+    }
+
+    let retries = join_all(failed.iter().map(|ch| search(ch.clone()))).await;
+    for (channel, result) in failed.into_iter().zip(retries) {
+        match result {
+            Ok(found) => messages.extend(found),
+            Err(e) => {
+                error!("Search failed for channel '{}' after retry: {}", channel, e);
+                still_failing.push(channel);
+            }
+        }
+    }
+
+    (messages, still_failing)
+}
+
+/// Author name for Slack Connect messages, taken from the inline `user_profile`
+///
+/// External users aren't visible to users.info, so shared channels skip that lookup
+/// whenever Slack sent the profile along with the message.
+fn inline_author(msg: &SlackMessage) -> Option<String> {
+    if msg.in_shared_channel() {
+        msg.inline_user_name()
+    } else {
+        None
+    }
+}
+
+/// Whether a channel is Slack Connect, per conversations.info (false if it can't be read)
+pub(crate) async fn is_shared_channel(client: &SlackClient, channel_id: &str) -> bool {
+    match client.get_channel_info(channel_id).await {
+        Ok(info) => info.is_slack_connect(),
+        Err(e) => {
+            debug!("Couldn't read channel info for {}: {}", channel_id, e);
+            false
+        }
+    }
+}
+
+/// Set `is_saved` on results found in the user's saved items
+///
+/// Messages Slack already reported as `is_starred` stay flagged.
+fn mark_saved(messages: &mut [Message], saved: &HashSet<(String, String)>) {
+    for message in messages.iter_mut() {
+        message.is_saved |= saved.contains(&(message.channel.clone(), message.ts.clone()));
+    }
+}
+
+/// Fetch stars.list once for the whole result set, reusing a recent fetch;
+/// without `stars:read` nothing is flagged
+async fn flag_saved(client: &SlackClient, state: &AppState, messages: &mut [Message]) {
+    if messages.is_empty() {
+        return;
+    }
+    if let Some(saved) = state.get_cached_saved_items().await {
+        mark_saved(messages, &saved);
+        return;
+    }
+    match client.get_saved_message_keys().await {
+        Ok(saved) => {
+            mark_saved(messages, &saved);
+            state.cache_saved_items(saved).await;
+        }
+        Err(e) => warn!("Could not load saved items, skipping saved flags: {}", e),
+    }
+}
+
+/// Maximum number of single-message lookups in flight while filling reply counts
+const REPLY_COUNT_CONCURRENCY: usize = 10;
+
+/// Copy a looked-up thread shape onto a search result
+fn apply_reply_info(message: &mut Message, info: &ReplyInfo) {
+    message.reply_count = Some(info.reply_count);
+    message.is_thread_parent = info.reply_count > 0;
+    if message.thread_ts.is_none() {
+        message.thread_ts = info.thread_ts.clone();
+    }
+}
+
+/// Look up `(index, channel, ts)` targets with bounded parallelism
+///
+/// Targets that fail or no longer exist are left out.
+async fn lookup_reply_infos<F, Fut>(
+    targets: Vec<(usize, String, String)>,
+    fetch: F,
+) -> Vec<(usize, ReplyInfo)>
+where
+    F: Fn(String, String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<Option<ReplyInfo>>>,
+{
+    use futures::stream::{self, StreamExt};
+
+    stream::iter(targets)
+        .map(|(index, channel, ts)| {
+            let request = fetch(channel.clone(), ts.clone());
+            async move {
+                match request.await {
+                    Ok(info) => info.map(|info| (index, info)),
+                    Err(e) => {
+                        debug!("Couldn't read reply count for {}:{}: {}", channel, ts, e);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(REPLY_COUNT_CONCURRENCY)
+        .filter_map(|found| async move { found })
+        .collect()
+        .await
+}
+
+/// Fill reply_count / is_thread_parent, which search.messages leaves out
+///
+/// Thread replies can't be parents and are skipped; recent lookups come from the cache.
+async fn fill_reply_counts(client: &SlackClient, state: &AppState, messages: &mut [Message]) {
+    let mut targets = Vec::new();
+    for (index, message) in messages.iter_mut().enumerate() {
+        if message.is_thread_reply {
+            continue;
+        }
+        match state.get_cached_reply_info(&message.channel, &message.ts).await {
+            Some(info) => apply_reply_info(message, &info),
+            None => targets.push((index, message.channel.clone(), message.ts.clone())),
+        }
+    }
+    if targets.is_empty() {
+        return;
+    }
+
+    info!("Looking up reply counts for {} search results", targets.len());
+    let found = lookup_reply_infos(targets, |channel, ts| async move {
+        let message = client.get_single_message(&channel, &ts).await?;
+        Ok(message.map(|msg| ReplyInfo {
+            thread_ts: msg.thread_ts,
+            reply_count: msg.reply_count.unwrap_or(0),
+        }))
+    })
+    .await;
+
+    for (index, info) in found {
+        let message = &mut messages[index];
+        state.cache_reply_info(&message.channel, &message.ts, info.clone()).await;
+        apply_reply_info(message, &info);
+    }
+}
+
+/// Maximum number of conversations.info requests in flight when naming result channels
+const CHANNEL_NAME_CONCURRENCY: usize = 10;
+
+/// Whether a result's channel name is only a stand-in (missing, or the bare ID)
+fn is_placeholder_channel_name(message: &Message) -> bool {
+    message.channel_name.is_empty()
+        || message.channel_name == message.channel
+        || message.channel_name == "Unknown Channel"
+}
+
+/// Unique channel IDs of results that still need a readable channel name
+fn unnamed_channel_ids(messages: &[Message]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    messages
+        .iter()
+        .filter(|m| m.channel != "unknown" && is_placeholder_channel_name(m))
+        .filter(|m| seen.insert(m.channel.clone()))
+        .map(|m| m.channel.clone())
+        .collect()
+}
+
+/// Look up names for `channel_ids` in parallel; channels that fail or come back unnamed are left out
+async fn lookup_channel_names<F, Fut>(channel_ids: Vec<String>, fetch: F) -> HashMap<String, String>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<Option<String>>>,
+{
+    use futures::stream::{self, StreamExt};
+
+    stream::iter(channel_ids)
+        .map(|channel_id| {
+            let request = fetch(channel_id.clone());
+            async move {
+                match request.await {
+                    Ok(name) => name.filter(|n| !n.is_empty() && *n != channel_id).map(|n| (channel_id, n)),
+                    Err(e) => {
+                        debug!("Couldn't name channel {}: {}", channel_id, e);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(CHANNEL_NAME_CONCURRENCY)
+        .filter_map(|found| async move { found })
+        .collect()
+        .await
+}
+
+/// Put resolved names on every result from those channels
+fn backfill_channel_names(messages: &mut [Message], names: &HashMap<String, String>) {
+    for message in messages.iter_mut().filter(|m| is_placeholder_channel_name(m)) {
+        if let Some(name) = names.get(&message.channel) {
+            message.channel_name = name.clone();
+        }
+    }
+}
+
+/// Name the result channels search didn't (history results, other channels in a
+/// cross-channel search), caching what conversations.info returns
+async fn fill_channel_names(client: &SlackClient, state: &AppState, messages: &mut [Message]) {
+    let channel_ids = unnamed_channel_ids(messages);
+    if channel_ids.is_empty() {
+        return;
+    }
+
+    info!("Looking up names for {} result channels", channel_ids.len());
+    let user_names = state.get_user_cache().await;
+    let found = lookup_channel_names(channel_ids, |channel_id| {
+        let user_names = &user_names;
+        async move {
+            let info = client.get_channel_info(&channel_id).await?;
+            let is_im = info.is_im.unwrap_or(false);
+            let is_mpim = info.is_mpim.unwrap_or(false);
+            // DMs have no name of their own; show the other person like the channel list does
+            let name = match (is_im, info.user.as_ref()) {
+                (true, Some(user_id)) => user_names.get(user_id).map(|name| format!("@{}", name)),
+                _ => info.name.clone(),
+            };
+            if let Some(name) = name.as_ref().filter(|n| !n.is_empty() && **n != channel_id) {
+                state.cache_channel(channel_id.clone(), name.clone(), is_im, is_mpim).await;
+            }
+            Ok(name)
+        }
+    })
+    .await;
+
+    backfill_channel_names(messages, &found);
+}
+
+/// Extra weight per repeat of a term, capped so repeats never outweigh another term
+const RELEVANCE_TF_BONUS: f64 = 0.1;
+const RELEVANCE_TF_CAP: usize = 5;
+/// Bonus when a multi-word query appears verbatim
+const RELEVANCE_PHRASE_BONUS: f64 = 0.5;
+
+/// Client-side relevance of `text` to `query`, for history results Slack didn't rank
+///
+/// Each query term found counts 1, plus a small bonus for repeats; the whole
+/// phrase appearing as typed adds a bonus on top.
+fn relevance_score(text: &str, query: &str) -> f64 {
+    let text = text.to_lowercase();
+    let query = query.to_lowercase();
+    let mut terms: Vec<&str> = query.split_whitespace().map(|t| t.trim_matches('"')).collect();
+    terms.retain(|t| !t.is_empty());
+    terms.dedup();
+
+    let mut score: f64 = terms
+        .iter()
+        .map(|term| match text.matches(term).count() {
+            0 => 0.0,
+            tf => 1.0 + RELEVANCE_TF_BONUS * (tf.min(RELEVANCE_TF_CAP) - 1) as f64,
+        })
+        .sum();
+    if terms.len() > 1 && text.contains(&terms.join(" ")) {
+        score += RELEVANCE_PHRASE_BONUS;
+    }
+    score
+}
+
+/// Order messages by `relevance_score`, newest first among equal scores
+fn sort_by_relevance(messages: &mut [SlackMessage], query: &str) {
+    messages.sort_by(|a, b| {
+        relevance_score(&b.text, query)
+            .total_cmp(&relevance_score(&a.text, query))
+            .then_with(|| b.ts.cmp(&a.ts))
+    });
+}
+
+/// Put results in the order the search asked for
+///
+/// Timestamp sorts go newest first. Score sorts keep Slack's relevance order,
+/// except for history results filtered locally, which Slack never ranked.
+fn order_results(messages: &mut [SlackMessage], sort: SearchSort, ranked_locally: bool, query: &str) {
+    match sort {
+        SearchSort::Score if ranked_locally => sort_by_relevance(messages, query),
+        SearchSort::Score => {}
+        SearchSort::Timestamp => messages.sort_by(|a, b| b.ts.cmp(&a.ts)),
+    }
+}
+
+/// Keep only messages between the `from` and `to` bounds (inclusive)
+///
+/// Bounds go through `date_bound_ts`, so a plain `YYYY-MM-DD` covers the whole
+/// day and an exact timestamp is used as is. Comparison is on the numeric ts.
+/// A bound that doesn't parse is ignored.
+fn retain_within_dates(messages: &mut Vec<SlackMessage>, from: Option<&str>, to: Option<&str>) {
+    let bound = |date: Option<&str>, end_of_day| {
+        date.and_then(|d| match date_bound_ts(d, end_of_day) {
+            Ok(ts) => ts.parse::<f64>().ok(),
+            Err(e) => {
+                warn!("Ignoring exact date bound: {}", e);
+                None
+            }
+        })
+    };
+    let (oldest, latest) = (bound(from, false), bound(to, true));
+    if oldest.is_none() && latest.is_none() {
+        return;
+    }
+
+    messages.retain(|msg| {
+        let Ok(ts) = msg.ts.parse::<f64>() else {
+            return true;
+        };
+        oldest.is_none_or(|o| ts >= o) && latest.is_none_or(|l| ts <= l)
+    });
+}
+
+/// Channels need at least this many results before the bot-share heuristic applies
+const LOW_REACTION_MIN_MESSAGES: usize = 20;
+/// Share of bot messages at which a channel is treated as low-reaction
+const LOW_REACTION_BOT_SHARE: f64 = 0.9;
+
+fn is_bot_message(msg: &SlackMessage) -> bool {
+    msg.bot_id.is_some() || msg.subtype.as_deref() == Some("bot_message")
+}
+
+/// Channels whose results should not trigger per-message reaction fetches
+///
+/// Flagged channels always qualify. Other channels qualify once nearly all of
+/// their results come from bots, which is typical of CI and alert integrations.
+fn low_reaction_channels(messages: &[SlackMessage], flagged: &HashSet<String>) -> HashSet<String> {
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for msg in messages {
+        if let Some(channel) = &msg.channel {
+            let entry = counts.entry(channel.id.as_str()).or_default();
+            entry.0 += 1;
+            if is_bot_message(msg) {
+                entry.1 += 1;
+            }
+        }
+    }
+
+    let mut channels = flagged.clone();
+    channels.extend(
+        counts
+            .into_iter()
+            .filter(|(_, (total, bots))| {
+                *total >= LOW_REACTION_MIN_MESSAGES
+                    && *bots as f64 / *total as f64 >= LOW_REACTION_BOT_SHARE
+            })
+            .map(|(id, _)| id.to_string()),
+    );
+    channels
+}
+
+/// Default soft cap on a search result's serialized size
+const DEFAULT_PAYLOAD_BUDGET_BYTES: usize = 5 * 1024 * 1024;
+
+/// Drop reactions and files from later messages once the result nears `budget` bytes
+///
+/// Messages are kept in order and their core fields are never touched; once
+/// one message goes over, every message after it is left unenriched too.
+/// Returns whether anything was dropped.
+fn enforce_enrichment_budget(messages: &mut [Message], budget: usize) -> bool {
+    let mut used = 0;
+    let mut over_budget = false;
+
+    for message in messages.iter_mut() {
+        if !over_budget {
+            let size = serde_json::to_vec(&*message).map(|v| v.len()).unwrap_or(0);
+            if used + size <= budget {
+                used += size;
+                continue;
+            }
+            over_budget = true;
+        }
+
+        message.reactions = None;
+        message.files = None;
+        used += serde_json::to_vec(&*message).map(|v| v.len()).unwrap_or(0);
+    }
+
+    over_budget
+}
+
+fn apply_payload_budget(mut result: SearchResult, max_payload_bytes: Option<usize>) -> SearchResult {
+    let budget = max_payload_bytes.unwrap_or(DEFAULT_PAYLOAD_BUDGET_BYTES);
+    if enforce_enrichment_budget(&mut result.messages, budget) {
+        warn!(
+            "Search result for '{}' exceeded the {} byte budget; later messages were returned without reactions/files",
+            result.query, budget
+        );
+        result.partial_enrichment = true;
+    }
+    result
+}
+
+/// History fetches inline thread replies unless the caller asked for lazy threads
+fn thread_replies_mode(lazy_threads: Option<bool>) -> ThreadReplies {
+    if lazy_threads.unwrap_or(false) {
+        ThreadReplies::Lazy
+    } else {
+        ThreadReplies::Inline
+    }
+}
+
+/// Pull Slack's highlight markers out of a message's text when highlighting was requested
+fn split_highlights(text: String, highlight: bool) -> (String, Option<Vec<crate::slack::HighlightRange>>) {
+    if !highlight {
+        return (text, None);
+    }
+    let (clean, ranges) = crate::slack::parser::parse_highlight_ranges(&text);
+    (clean, Some(ranges))
+}
+
 /// Extract file extension from filename
 fn get_file_extension(filename: &str) -> Option<String> {
     filename.rsplit('.').next()
@@ -43,116 +542,740 @@ fn matches_file_extensions(msg: &Message, extensions: &[String]) -> bool {
     false
 }
 
-#[tauri::command]
-pub async fn search_messages(
-    query: String,
-    channel: Option<String>,
-    user: Option<String>,
-    from_date: Option<String>,
-    to_date: Option<String>,
-    limit: Option<usize>,
-    force_refresh: Option<bool>, // Add this parameter
-    last_timestamp: Option<String>, // For incremental updates
-    has_files: Option<bool>, // Deprecated: Filter messages with attachments
-    file_extensions: Option<Vec<String>>, // Filter by file extensions
-    state: State<'_, AppState>,
-) -> AppResult<SearchResult> {
-    let start_time = Instant::now();
+/// Whether a file is of `file_type`: a MIME family (`image`), a Slack filetype
+/// (`pdf`) or a file extension, compared case-insensitively
+fn file_matches_type(file: &crate::slack::SlackFile, file_type: &str) -> bool {
+    let wanted = file_type.trim().trim_start_matches('.').to_lowercase();
+    file.mimetype.to_lowercase().starts_with(&format!("{}/", wanted))
+        || file.filetype.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(&wanted))
+        || get_file_extension(&file.name).is_some_and(|ext| ext == wanted)
+}
 
-    info!("[SEARCH DEBUG] search_messages called with force_refresh: {:?}, query: '{}', channel: {:?}, file_extensions: {:?}",
-          force_refresh, query, channel, file_extensions);
+/// Keep messages with at least one available file, optionally of `file_type`
+fn retain_file_messages(messages: Vec<SlackMessage>, file_type: Option<&str>) -> Vec<SlackMessage> {
+    let file_type = file_type.filter(|t| !t.trim().is_empty());
+    messages
+        .into_iter()
+        .filter(|msg| {
+            msg.files.as_ref().is_some_and(|files| {
+                files
+                    .iter()
+                    .filter(|file| !file.unavailable)
+                    .any(|file| file_type.is_none_or(|t| file_matches_type(file, t)))
+            })
+        })
+        .collect()
+}
 
-    // Check cache first (skip if force_refresh is true)
-    if !force_refresh.unwrap_or(false) {
-        if let Some(cached_result) = state
-            .get_cached_search(&query, &channel, &user, &from_date, &to_date, &limit, &has_files, &file_extensions)
-            .await
-        {
-            info!(
-                "Returning cached search result in {}ms",
-                start_time.elapsed().as_millis()
-            );
-            return Ok(cached_result);
-        }
+/// Decide why a single-channel search returned nothing
+fn classify_empty_result(validity: &ChannelValidity, has_messages: bool) -> ResultReason {
+    if !validity.accessible {
+        ResultReason::NoAccess
+    } else if has_messages {
+        ResultReason::NoMatches
     } else {
-        info!("Force refresh enabled, skipping cache");
+        ResultReason::Empty
     }
+}
 
-    // Get the Slack client from app state
-    let client = state.get_client().await?;
-    let client = Arc::new(client);
-
-    // Set default limit if not provided
-    let max_results = limit.unwrap_or(100);
+/// Probe the searched channel to explain an empty result
+///
+/// Multi-channel searches are left unexplained since each channel may differ.
+async fn explain_empty_result(client: &SlackClient, channel: Option<&str>) -> Option<ResultReason> {
+    let channel_id = match channel {
+        None => return Some(ResultReason::NoMatches),
+        Some(ch) if ch.contains(',') => return None,
+        Some(ch) => ch.trim(),
+    };
 
-    // Handle multi-channel or multi-user search
-    let mut all_slack_messages = Vec::new();
+    // Channel names (e.g. "general") can't be probed with conversations.info
+    let looks_like_id = channel_id.starts_with(['C', 'G', 'D'])
+        && channel_id.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    if !looks_like_id {
+        return None;
+    }
 
-    // Check if we have multi-user search (no longer needed for special handling)
-    // Multi-user is now handled directly in build_search_query with OR logic
-    let _is_multi_channel = channel.as_ref().map_or(false, |c| c.contains(','));
+    let validity = match client.probe_channel(channel_id).await {
+        Ok((channel, error)) => match channel_validity(channel.as_ref(), error.as_deref()) {
+            Ok(validity) => validity,
+            Err(e) => {
+                warn!("Failed to probe channel {} for empty result: {}", channel_id, e);
+                return None;
+            }
+        },
+        Err(e) => {
+            warn!("Failed to probe channel {} for empty result: {}", channel_id, e);
+            return None;
+        }
+    };
 
-    if let Some(ref channel_param) = channel {
-        if channel_param.contains(',') {
-            // Multi-channel search: search each channel separately IN PARALLEL
-            // Note: Multi-user is now handled with OR logic in a single query
-            let channels: Vec<String> = if channel_param.contains(',') {
-                channel_param
-                    .split(',')
-                    .map(|ch| ch.trim().to_string())
-                    .filter(|ch| !ch.is_empty())
-                    .collect()
-            } else {
-                vec![channel_param.clone()]
-            };
+    // Only look for messages when the channel is readable
+    let has_messages = if validity.accessible {
+        match client.get_oldest_message_ts(channel_id).await {
+            Ok(oldest) => oldest.is_some(),
+            Err(e) => {
+                warn!("Failed to check messages in channel {}: {}", channel_id, e);
+                return None;
+            }
+        }
+    } else {
+        false
+    };
 
-            info!(
-                "Performing parallel search for {} channels",
-                channels.len()
-            );
+    let reason = classify_empty_result(&validity, has_messages);
+    info!("Empty search result for channel {}: {:?}", channel_id, reason);
+    Some(reason)
+}
 
-            // Determine if this includes DM channels
-            let dm_channels: Vec<String> = channels.iter()
-                .filter(|ch| ch.starts_with("D") || ch.starts_with("G"))
-                .cloned()
-                .collect();
-            let _regular_channels: Vec<String> = channels.iter()
-                .filter(|ch| !ch.starts_with("D") && !ch.starts_with("G"))
-                .cloned()
-                .collect();
+const RECENT_SEARCHES_STORE: &str = "search.dat";
+const RECENT_SEARCHES_KEY: &str = "recent_searches";
+
+fn recent_search_from_key(key: &SearchCacheKey) -> RecentSearch {
+    RecentSearch {
+        query: key.query.clone(),
+        channel: key.channel.clone(),
+        user: key.user.clone(),
+        from_date: key.from_date.clone(),
+        to_date: key.to_date.clone(),
+        has_files: key.has_files,
+        file_extensions: key.file_extensions.clone(),
+        searched_at: AppState::current_timestamp(),
+    }
+}
 
-            if !dm_channels.is_empty() {
-                info!("Multi-channel search includes {} DM/Group DM channels", dm_channels.len());
-            }
+/// Get recent searches, loading them from the store on first use
+async fn recent_searches(app: &AppHandle, state: &AppState) -> AppResult<Vec<RecentSearch>> {
+    if let Some(searches) = state.get_recent_searches().await {
+        return Ok(searches);
+    }
 
-            // Create futures for parallel execution
-            use std::pin::Pin;
-            use futures::future::Future;
+    let store = app.store(RECENT_SEARCHES_STORE)?;
+    let searches: Vec<RecentSearch> = store
+        .get(RECENT_SEARCHES_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
 
-            let mut search_futures: Vec<Pin<Box<dyn Future<Output = Result<Vec<SlackMessage>, anyhow::Error>> + Send>>> = Vec::new();
+    info!("Loaded {} recent searches", searches.len());
+    state.load_recent_searches(searches.clone()).await;
+    Ok(searches)
+}
 
-            // Multi-channel search
-            for single_channel in &channels {
-                let client = Arc::clone(&client);
-                let query = query.clone();
-                let channel = single_channel.clone();
-                let user = user.clone();  // This might be multiple users with commas
-                let from_date = from_date.clone();
-                let to_date = to_date.clone();
-                let has_files = has_files;
-                let file_extensions = file_extensions.clone();
+fn save_recent_searches(app: &AppHandle, searches: &[RecentSearch]) -> AppResult<()> {
+    let store = app.store(RECENT_SEARCHES_STORE)?;
+    store.set(RECENT_SEARCHES_KEY, serde_json::to_value(searches)?);
+    store.save()?;
+    Ok(())
+}
 
-                search_futures.push(Box::pin(async move {
-                    // Check if this is a DM/Group DM channel
-                    let is_dm_channel = channel.starts_with("D") || channel.starts_with("G");
+/// Record a search for suggestions; failures never block the search itself
+async fn remember_search(app: &AppHandle, state: &AppState, search: RecentSearch) {
+    if search.query.trim().is_empty() && search.channel.is_none() && search.user.is_none() {
+        return;
+    }
 
-                    if is_dm_channel {
-                        // Use DM-specific search for DM/Group DM channels
-                        info!("Using DM search for channel: {}", channel);
+    // Make sure persisted searches are loaded so we don't overwrite them
+    if let Err(e) = recent_searches(app, state).await {
+        warn!("Failed to load recent searches: {}", e);
+    }
+    let searches = state.add_recent_search(search).await;
+    if let Err(e) = save_recent_searches(app, &searches) {
+        warn!("Failed to save recent searches: {}", e);
+    }
+}
 
-                        // For DMs, use the search_dm_messages function
-                        let query_str = if query.is_empty() {
-                            None
+/// Count messages matching the filters using only the first one-result page
+///
+/// Supports a single channel and a single user; multi-value filters are
+/// resolved client-side in full searches and can't be counted this way.
+#[tauri::command]
+pub async fn count_matches(
+    query: String,
+    channel: Option<String>,
+    user: Option<String>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<usize> {
+    if channel.as_deref().is_some_and(|c| c.contains(','))
+        || user.as_deref().is_some_and(|u| u.contains(','))
+    {
+        return Err(AppError::ParseError(
+            "Counting supports a single channel and a single user".to_string(),
+        ));
+    }
+
+    let users = state.get_user_cache_full().await;
+    let search_query = count_query(SearchRequest {
+        query,
+        channel,
+        user: user.map(|u| resolve_user_names(&u, &users)),
+        from_date,
+        to_date,
+        limit: None,
+        is_realtime: None,
+        has_files: None,
+        file_extensions: None,
+        dates_are_exact: None,
+    });
+
+    let client = state.get_client().await?;
+    Ok(client.count_search_matches(&search_query).await?)
+}
+
+/// Swap user names in a `from:` filter for cached user IDs
+///
+/// Slack search only matches handles and IDs, so a display name such as
+/// "John Smith" silently finds nothing. Each comma-separated entry that
+/// matches exactly one cached user's name or real name (ignoring case and a
+/// leading @) becomes that user's ID; anything else is left for Slack to interpret.
+fn resolve_user_names(user: &str, users: &HashMap<String, CachedUser>) -> String {
+    user.split(',')
+        .map(|entry| {
+            let name = entry.trim().trim_start_matches('@');
+            if name.is_empty() || name.starts_with("<@") || is_user_id(name) {
+                return entry.to_string();
+            }
+            match find_user_by_name(name, users) {
+                Some(id) => {
+                    debug!("Resolved from:{} to {}", name, id);
+                    id.clone()
+                }
+                None => entry.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// search.messages query for `request`
+///
+/// build_search_query hands channel + user searches to conversations.history;
+/// a count needs them as search modifiers instead.
+fn count_query(request: SearchRequest) -> String {
+    let query = build_search_query(&request);
+    if query != "USE_CONVERSATIONS_HISTORY" {
+        return query;
+    }
+
+    let channel = request.channel.clone().unwrap_or_default();
+    let without_channel = build_search_query(&SearchRequest {
+        channel: None,
+        ..request
+    });
+    format!("{} in:{}", without_channel, channel.trim_start_matches('#').trim())
+        .trim()
+        .to_string()
+}
+
+/// Channel activity summary for health dashboards
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelFrequency {
+    pub channel: String,
+    pub days: u32,
+    pub total_messages: usize,
+    pub msgs_per_day_avg: f64,
+    /// Busiest day (YYYY-MM-DD, local time); None when the window is empty
+    pub peak_day: Option<String>,
+    pub peak_day_count: usize,
+    pub active_users: usize,
+}
+
+/// conversations.history pages are capped at 200 messages and ~10 calls
+const FREQUENCY_HISTORY_LIMIT: usize = 2000;
+
+/// Count messages per calendar day in the given UTC offset
+fn bucket_by_day(
+    messages: &[SlackMessage],
+    offset: chrono::FixedOffset,
+) -> std::collections::BTreeMap<chrono::NaiveDate, usize> {
+    let mut buckets = std::collections::BTreeMap::new();
+    for msg in messages {
+        let Some(secs) = msg.ts.split('.').next().and_then(|s| s.parse::<i64>().ok()) else {
+            continue;
+        };
+        if let Some(dt) = chrono::DateTime::from_timestamp(secs, 0) {
+            *buckets.entry(dt.with_timezone(&offset).date_naive()).or_insert(0) += 1;
+        }
+    }
+    buckets
+}
+
+fn channel_frequency_from_messages(
+    channel: &str,
+    messages: &[SlackMessage],
+    days: u32,
+    offset: chrono::FixedOffset,
+) -> ChannelFrequency {
+    let buckets = bucket_by_day(messages, offset);
+    // Earliest date wins ties so the result is stable
+    let peak = buckets
+        .iter()
+        .fold(None, |best: Option<(&chrono::NaiveDate, &usize)>, (day, count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((day, count)),
+        });
+    let active_users: HashSet<&str> = messages.iter().filter_map(|m| m.user.as_deref()).collect();
+
+    ChannelFrequency {
+        channel: channel.to_string(),
+        days,
+        total_messages: messages.len(),
+        // Quiet days count too, so divide by the whole window
+        msgs_per_day_avg: messages.len() as f64 / days.max(1) as f64,
+        peak_day: peak.map(|(day, _)| day.format("%Y-%m-%d").to_string()),
+        peak_day_count: peak.map(|(_, count)| *count).unwrap_or(0),
+        active_users: active_users.len(),
+    }
+}
+
+/// Messages per day, busiest day and distinct posters over the last `days` days
+#[tauri::command]
+pub async fn channel_frequency(
+    channel: String,
+    days: Option<u32>,
+    state: State<'_, AppState>,
+) -> AppResult<ChannelFrequency> {
+    let days = days.unwrap_or(30).max(1);
+    let oldest = chrono::Utc::now().timestamp() - i64::from(days) * 86_400;
+
+    let client = state.get_client().await?;
+    let messages = client
+        .get_channel_messages_with_options(
+            &channel,
+            Some(oldest.to_string()),
+            None,
+            FREQUENCY_HISTORY_LIMIT,
+            HistoryOptions {
+                thread_replies: ThreadReplies::Lazy,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    if messages.len() >= FREQUENCY_HISTORY_LIMIT {
+        warn!("Channel {} hit the history limit; frequency covers the newest {} messages", channel, messages.len());
+    }
+
+    let offset = *chrono::Local::now().offset();
+    Ok(channel_frequency_from_messages(&channel, &messages, days, offset))
+}
+
+/// Recent searches, newest first, for the search box dropdown
+#[tauri::command]
+pub async fn get_recent_searches(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> AppResult<Vec<RecentSearch>> {
+    let mut searches = recent_searches(&app, &state).await?;
+    if let Some(limit) = limit {
+        searches.truncate(limit);
+    }
+    Ok(searches)
+}
+
+#[tauri::command]
+pub async fn clear_recent_searches(app: AppHandle, state: State<'_, AppState>) -> AppResult<()> {
+    state.clear_recent_searches().await;
+    save_recent_searches(&app, &[])?;
+    info!("Recent searches cleared");
+    Ok(())
+}
+
+/// Build the `Message` for a search hit once its names and text are resolved
+///
+/// Reactions are only carried over when the search fetched them; otherwise the
+/// frontend loads them progressively.
+pub(crate) fn search_result_message(
+    slack_msg: SlackMessage,
+    user_name: String,
+    (channel_id, channel_name): (String, String),
+    (text, highlights): (String, Option<Vec<crate::slack::HighlightRange>>),
+    fetch_reactions: bool,
+    reactor_cap: usize,
+) -> Message {
+    let is_pinned = slack_msg.is_pinned();
+    let is_saved = slack_msg.is_saved();
+    let is_shared_channel = slack_msg.in_shared_channel();
+    let block_urls = slack_msg.block_urls();
+    let is_thread_reply = slack_msg.is_thread_reply();
+    Message {
+        ts: slack_msg.ts,
+        thread_ts: slack_msg.thread_ts,
+        // For bot messages, use bot_id if available, otherwise use empty string
+        user: slack_msg.user.or(slack_msg.bot_id).unwrap_or_default(),
+        user_name,
+        text,
+        channel: channel_id,
+        channel_name,
+        permalink: slack_msg.permalink.unwrap_or_default(),
+        // search.messages doesn't return reply_count, so thread parents can't be told apart
+        is_thread_parent: false,
+        is_thread_reply,
+        reply_count: None,
+        reactions: if fetch_reactions {
+            capped_reactions(slack_msg.reactions, reactor_cap)
+        } else {
+            None
+        },
+        files: slack_msg.files,
+        highlights,
+        metadata: slack_msg.metadata,
+        is_pinned,
+        is_saved,
+        is_shared_channel,
+        block_urls,
+    }
+}
+
+/// Parameters shared by `search_messages` and `search_messages_fast`
+#[derive(Debug, Clone, Default)]
+struct SearchParams {
+    query: String,
+    channel: Option<String>,
+    user: Option<String>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    limit: Option<usize>,
+    force_refresh: Option<bool>,
+    last_timestamp: Option<String>, // For incremental updates
+    has_files: Option<bool>, // Deprecated: Filter messages with attachments
+    file_extensions: Option<Vec<String>>, // Filter by file extensions
+    highlight: Option<bool>, // Ask Slack to mark matches in the text
+    sort: Option<String>, // "timestamp" (default) or "score"
+    lazy_threads: Option<bool>, // Channel browsing: leave thread replies for on-demand loading
+    auto_join: Option<bool>, // Channel browsing: join public channels on not_in_channel
+    max_payload_bytes: Option<usize>, // Soft cap on the serialized result before reactions/files are dropped
+    fetch_low_reaction: Option<bool>, // Fetch reactions even in channels flagged or detected as low-reaction
+    dates_are_exact: Option<bool>, // Treat from/to as exact timestamps instead of whole days
+    dedupe: Option<bool>, // Fetch pages sequentially, dropping matches repeated across pages
+    enrich_reply_counts: Option<bool>, // Look up each result to fill reply_count / is_thread_parent
+}
+
+impl SearchParams {
+    /// Fold operators typed into the query (`in:#ops`, `after:2024-03-01`) into the structured fields
+    ///
+    /// Fields the caller set explicitly win; `has:`/`is:` stay in the query text.
+    fn with_inline_operators(mut self) -> Self {
+        let (text, inline) = parse_inline_operators(&self.query);
+        self.channel = self.channel.or(inline.channel);
+        self.user = self.user.or(inline.user);
+        self.from_date = self.from_date.or(inline.from_date);
+        self.to_date = self.to_date.or(inline.to_date);
+        self.query = std::iter::once(text)
+            .chain(inline.filters)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self
+    }
+}
+
+#[tauri::command]
+pub async fn search_messages(
+    app: AppHandle,
+    query: String,
+    channel: Option<String>,
+    user: Option<String>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    limit: Option<usize>,
+    force_refresh: Option<bool>, // Add this parameter
+    last_timestamp: Option<String>, // For incremental updates
+    has_files: Option<bool>, // Deprecated: Filter messages with attachments
+    file_extensions: Option<Vec<String>>, // Filter by file extensions
+    highlight: Option<bool>, // Ask Slack to mark matches in the text
+    sort: Option<String>, // "timestamp" (default) or "score"
+    lazy_threads: Option<bool>, // Channel browsing: leave thread replies for on-demand loading
+    auto_join: Option<bool>, // Channel browsing: join public channels on not_in_channel
+    max_payload_bytes: Option<usize>, // Soft cap on the serialized result before reactions/files are dropped
+    fetch_low_reaction: Option<bool>, // Fetch reactions even in channels flagged or detected as low-reaction
+    dates_are_exact: Option<bool>, // Treat from/to as exact timestamps instead of whole days
+    fetch_reactions: Option<bool>, // Set to false to skip reaction enrichment entirely (e.g. exports)
+    dedupe: Option<bool>, // Drop matches repeated across pages (very active channels)
+    enrich_reply_counts: Option<bool>, // Look up each result so threads show "N replies"
+    state: State<'_, AppState>,
+) -> AppResult<SearchResult> {
+    let params = SearchParams {
+        query,
+        channel,
+        user,
+        from_date,
+        to_date,
+        limit,
+        force_refresh,
+        last_timestamp,
+        has_files,
+        file_extensions,
+        highlight,
+        sort,
+        lazy_threads,
+        auto_join,
+        max_payload_bytes,
+        fetch_low_reaction,
+        dates_are_exact,
+        dedupe,
+        enrich_reply_counts,
+    };
+    let reactions = if fetch_reactions.unwrap_or(true) {
+        ReactionMode::Fetch
+    } else {
+        ReactionMode::Skip
+    };
+    do_search(&app, &state, params, reactions).await
+}
+
+/// Search without fetching reactions; only already-cached reactions are attached
+///
+/// The frontend loads the rest progressively, so results come back immediately.
+#[tauri::command]
+pub async fn search_messages_fast(
+    app: AppHandle,
+    query: String,
+    channel: Option<String>,
+    user: Option<String>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    limit: Option<usize>,
+    force_refresh: Option<bool>,
+    has_files: Option<bool>,
+    file_extensions: Option<Vec<String>>,
+    highlight: Option<bool>,
+    sort: Option<String>,
+    lazy_threads: Option<bool>,
+    auto_join: Option<bool>,
+    max_payload_bytes: Option<usize>,
+    dates_are_exact: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<SearchResult> {
+    let params = SearchParams {
+        query,
+        channel,
+        user,
+        from_date,
+        to_date,
+        limit,
+        force_refresh,
+        has_files,
+        file_extensions,
+        highlight,
+        sort,
+        lazy_threads,
+        auto_join,
+        max_payload_bytes,
+        dates_are_exact,
+        ..Default::default()
+    };
+    do_search(&app, &state, params, ReactionMode::Cached).await
+}
+
+/// How a search attaches reactions to its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReactionMode {
+    /// Fetch missing reactions from Slack (`search_messages`)
+    Fetch,
+    /// Attach only reactions already in the reaction cache (`search_messages_fast`)
+    Cached,
+    /// Leave reactions off entirely (`search_messages` with `fetch_reactions: false`)
+    Skip,
+}
+
+/// Messages whose reactions a search should fetch, as (index, channel, ts)
+///
+/// Nothing is fetched unless the search is in `ReactionMode::Fetch`; messages
+/// that already carry reactions or sit in a low-reaction channel are skipped.
+fn reaction_fetch_targets(
+    mode: ReactionMode,
+    messages: &[SlackMessage],
+    skip_channels: &HashSet<String>,
+) -> Vec<(usize, String, String)> {
+    if mode != ReactionMode::Fetch {
+        return Vec::new();
+    }
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, msg)| msg.reactions.is_none())
+        .filter_map(|(idx, msg)| {
+            let channel_info = msg.channel.as_ref().filter(|c| !skip_channels.contains(&c.id))?;
+            Some((idx, channel_info.id.clone(), msg.ts.clone()))
+        })
+        .collect()
+}
+
+/// The search behind both search commands
+///
+/// Unless reactions are fetched, results are not stored in the search cache,
+/// so a later full search still fetches them.
+async fn do_search(
+    app: &AppHandle,
+    state: &AppState,
+    params: SearchParams,
+    reaction_mode: ReactionMode,
+) -> AppResult<SearchResult> {
+    let fetch_reactions = reaction_mode == ReactionMode::Fetch;
+    let SearchParams {
+        query,
+        channel,
+        user,
+        from_date,
+        to_date,
+        limit,
+        force_refresh,
+        last_timestamp,
+        has_files,
+        file_extensions,
+        highlight,
+        sort,
+        lazy_threads,
+        auto_join,
+        max_payload_bytes,
+        fetch_low_reaction,
+        dates_are_exact,
+        dedupe,
+        enrich_reply_counts,
+    } = params.with_inline_operators();
+    let user = match user {
+        Some(user) => Some(resolve_user_names(&user, &state.get_user_cache_full().await)),
+        None => None,
+    };
+    let dedupe = dedupe.unwrap_or(false);
+    let enrich_reply_counts = enrich_reply_counts.unwrap_or(false);
+    let limit = Some(resolve_result_limit(limit));
+    let start_time = Instant::now();
+    let highlight = highlight.unwrap_or(false);
+    let thread_replies = thread_replies_mode(lazy_threads);
+    let history_options = HistoryOptions {
+        thread_replies,
+        auto_join: auto_join.unwrap_or(false),
+        ..Default::default()
+    };
+    let search_options = SearchOptions {
+        highlight,
+        sort: SearchSort::from_param(sort.as_deref()),
+    };
+    let truncation = Arc::new(Truncation::default());
+    let cache_key = SearchCacheKey {
+        query: query.clone(),
+        channel: channel.clone(),
+        user: user.clone(),
+        from_date: from_date.clone(),
+        to_date: to_date.clone(),
+        limit,
+        has_files,
+        file_extensions: file_extensions.clone(),
+        highlight,
+        sort: search_options.sort,
+        thread_replies,
+        last_timestamp: last_timestamp.clone(),
+        dates_are_exact: dates_are_exact.unwrap_or(false),
+        reply_counts: enrich_reply_counts,
+        dedupe,
+        reactions: fetch_reactions,
+        fetch_low_reaction: fetch_low_reaction.unwrap_or(false),
+    };
+
+    info!("[SEARCH DEBUG] search called with force_refresh: {:?}, reactions: {:?}, query: '{}', channel: {:?}, file_extensions: {:?}",
+          force_refresh, reaction_mode, query, channel, file_extensions);
+
+    // Live-mode refreshes repeat the same search; only remember the first run
+    if last_timestamp.is_none() {
+        remember_search(app, state, recent_search_from_key(&cache_key)).await;
+    }
+
+    // Check cache first (skip if force_refresh is true)
+    if !force_refresh.unwrap_or(false) {
+        if let Some(cached_result) = state
+            .get_cached_search(&cache_key)
+            .await
+        {
+            info!(
+                "Returning cached search result in {}ms",
+                start_time.elapsed().as_millis()
+            );
+            return Ok(apply_payload_budget(cached_result, max_payload_bytes));
+        }
+    } else {
+        info!("Force refresh enabled, skipping cache");
+    }
+
+    // Get the Slack client from app state
+    let client = state.get_client().await?;
+    let client = Arc::new(client);
+
+    let max_results = resolve_result_limit(limit);
+
+    // Handle multi-channel or multi-user search
+    let mut all_slack_messages = Vec::new();
+    let mut failed_channels = Vec::new();
+    // Set when results come from conversations.history keyword filtering
+    let mut ranked_locally = false;
+
+    // Check if we have multi-user search (no longer needed for special handling)
+    // Multi-user is now handled directly in build_search_query with OR logic
+    let _is_multi_channel = channel.as_ref().map_or(false, |c| c.contains(','));
+
+    if let Some(ref channel_param) = channel {
+        if channel_param.contains(',') {
+            // Multi-channel search: search each channel separately IN PARALLEL
+            // Note: Multi-user is now handled with OR logic in a single query
+            let channels: Vec<String> = if channel_param.contains(',') {
+                channel_param
+                    .split(',')
+                    .map(|ch| ch.trim().to_string())
+                    .filter(|ch| !ch.is_empty())
+                    .collect()
+            } else {
+                vec![channel_param.clone()]
+            };
+
+            info!(
+                "Performing parallel search for {} channels",
+                channels.len()
+            );
+
+            // Determine if this includes DM channels
+            let dm_channels: Vec<String> = channels.iter()
+                .filter(|ch| ch.starts_with("D") || ch.starts_with("G"))
+                .cloned()
+                .collect();
+            let _regular_channels: Vec<String> = channels.iter()
+                .filter(|ch| !ch.starts_with("D") && !ch.starts_with("G"))
+                .cloned()
+                .collect();
+
+            if !dm_channels.is_empty() {
+                info!("Multi-channel search includes {} DM/Group DM channels", dm_channels.len());
+            }
+            let home_team = if dm_channels.is_empty() {
+                None
+            } else {
+                state.team_id().await
+            };
+            ranked_locally = !dm_channels.is_empty() && !query.is_empty();
+
+            // Multi-channel search
+            let search_channel = |channel: String| {
+                let client = Arc::clone(&client);
+                let query = query.clone();
+                let user = user.clone();  // This might be multiple users with commas
+                let from_date = from_date.clone();
+                let to_date = to_date.clone();
+                let file_extensions = file_extensions.clone();
+                let truncation = Arc::clone(&truncation);
+                let home_team = home_team.clone();
+
+                async move {
+                    // Check if this is a DM/Group DM channel
+                    let is_dm_channel = channel.starts_with("D") || channel.starts_with("G");
+
+                    if is_dm_channel {
+                        // Use DM-specific search for DM/Group DM channels
+                        info!("Using DM search for channel: {}", channel);
+
+                        // For DMs, use the search_dm_messages function
+                        let query_str = if query.is_empty() {
+                            None
                         } else {
                             Some(query.as_str())
                         };
@@ -161,28 +1284,11 @@ pub async fn search_messages(
                             &channel,
                             query_str,
                             max_results,
+                            home_team.as_deref(),
                         ).await?;
 
                         // Apply date filters if specified
-                        if let Some(ref from) = from_date {
-                            messages.retain(|msg| {
-                                let ts_float: f64 = msg.ts.parse().unwrap_or(0.0);
-                                let msg_date = chrono::DateTime::from_timestamp(ts_float as i64, 0)
-                                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                                    .unwrap_or_default();
-                                msg_date >= *from
-                            });
-                        }
-
-                        if let Some(ref to) = to_date {
-                            messages.retain(|msg| {
-                                let ts_float: f64 = msg.ts.parse().unwrap_or(0.0);
-                                let msg_date = chrono::DateTime::from_timestamp(ts_float as i64, 0)
-                                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                                    .unwrap_or_default();
-                                msg_date <= *to
-                            });
-                        }
+                        retain_within_dates(&mut messages, from_date.as_deref(), to_date.as_deref());
 
                         // Add channel info to DM messages
                         for msg in &mut messages {
@@ -190,6 +1296,7 @@ pub async fn search_messages(
                                 msg.channel = Some(SlackChannelInfo {
                                     id: channel.clone(),
                                     name: channel.clone(), // Will be resolved to proper name later
+                                    is_shared: false,
                                 });
                             }
                         }
@@ -207,6 +1314,7 @@ pub async fn search_messages(
                             is_realtime: force_refresh,
                             has_files,
                             file_extensions: file_extensions.clone(),
+                            dates_are_exact,
                         };
 
                         let search_query = build_search_query(&search_request);
@@ -215,7 +1323,10 @@ pub async fn search_messages(
                             channel, search_query
                         );
 
-                        let mut messages = fetch_all_results(&client, search_query, max_results).await?;
+                        let mut messages = take_fetched(
+                            fetch_all_results(&client, search_query, max_results, search_options, dedupe).await?,
+                            &truncation,
+                        );
 
                         // Filter by user IDs if multi-user search
                         if let Some(ref users) = user {
@@ -238,7 +1349,7 @@ pub async fn search_messages(
 
                                 // Debug: Log first few messages to see user field
                                 for (i, msg) in messages.iter().take(3).enumerate() {
-                                    info!("Message {}: user={:?}, text={:?}", i, msg.user, &msg.text[..msg.text.len().min(50)]);
+                                    info!("Message {}: user={:?}, text={:?}", i, msg.user, preview(&msg.text, 50));
                                 }
 
                                 let message_count = messages.len();
@@ -262,18 +1373,13 @@ pub async fn search_messages(
 
                         Ok::<Vec<SlackMessage>, anyhow::Error>(messages)
                     }
-                }));
-            }
-
-            // Execute all searches in parallel
-            let results = join_all(search_futures).await;
-
-            // Combine all results
-            for result in results {
-                if let Ok(messages) = result {
-                    all_slack_messages.extend(messages);
                 }
-            }
+            };
+
+            // Execute all searches in parallel, retrying failed channels once
+            let (messages, failed) = search_channels_with_retry(&channels, search_channel).await;
+            all_slack_messages.extend(messages);
+            failed_channels = failed;
         } else {
             // Single channel search
             // Check if we have a text query or just filters
@@ -383,13 +1489,13 @@ pub async fn search_messages(
                     info!("[REALTIME DEBUG] Using get_channel_messages_with_reactions for channel: {}, force_refresh: true", clean_channel);
                     (*client)
                         .clone()
-                        .get_channel_messages_with_reactions(clean_channel, oldest, latest, fetch_limit)
+                        .get_channel_messages_with_reactions(clean_channel, oldest, latest, fetch_limit, history_options)
                         .await
                 } else {
                     info!("[REALTIME DEBUG] Using get_channel_messages for channel: {}, force_refresh: false", clean_channel);
                     (*client)
                         .clone()
-                        .get_channel_messages(clean_channel, oldest, latest, fetch_limit)
+                        .get_channel_messages_with_options(clean_channel, oldest, latest, fetch_limit, history_options)
                         .await
                 };
 
@@ -464,6 +1570,7 @@ pub async fn search_messages(
                             is_realtime: force_refresh,
                             has_files,
                             file_extensions: file_extensions.clone(),
+                            dates_are_exact,
                         };
 
                         let search_query = build_search_query(&search_request);
@@ -472,8 +1579,10 @@ pub async fn search_messages(
                             search_query
                         );
 
-                        all_slack_messages =
-                            fetch_all_results(&client, search_query.clone(), max_results).await?;
+                        all_slack_messages = take_fetched(
+                            fetch_all_results(&client, search_query.clone(), max_results, search_options, dedupe).await?,
+                            &truncation,
+                        );
                     }
                 }
             } else {
@@ -488,6 +1597,7 @@ pub async fn search_messages(
                     is_realtime: force_refresh,
                     has_files,
                             file_extensions: file_extensions.clone(),
+                            dates_are_exact,
                 };
 
                 let search_query = build_search_query(&search_request);
@@ -496,10 +1606,66 @@ pub async fn search_messages(
                     search_query
                 );
 
-                // 🔥 CRITICAL: Detect USE_CONVERSATIONS_HISTORY flag for file extension filtering
-                // When file extensions are specified, we need conversations.history API (not search.messages)
-                // because only conversations.history includes file metadata in responses
-                if search_query == "USE_CONVERSATIONS_HISTORY" {
+                // Check if this is a DM or Group DM channel search based on cached channel info
+                let is_dm_search = if let Some(ref ch) = channel {
+                    // Get the full channel cache to check channel type
+                    let channel_cache_full = state.get_channel_cache_full().await;
+                    if let Some(cached_channel) = channel_cache_full.get(ch) {
+                        cached_channel.is_im || cached_channel.is_mpim
+                    } else {
+                        // Fallback to ID prefix check for channels not in cache
+                        (ch.starts_with("D") || ch.starts_with("G")) && ch.len() > 8
+                    }
+                } else {
+                    false
+                };
+
+                if is_dm_search {
+                    if let Some(ref ch) = channel {
+                        let channel_cache_full = state.get_channel_cache_full().await;
+                        let channel_type = if let Some(cached_channel) = channel_cache_full.get(ch) {
+                            if cached_channel.is_mpim {
+                                "Group DM"
+                            } else {
+                                "DM"
+                            }
+                        } else if ch.starts_with("G") {
+                            "Group DM"
+                        } else {
+                            "DM"
+                        };
+                        info!("Detected {} channel search for: {}", channel_type, ch);
+
+                        // Use the dedicated DM search function
+                        let query_str = if query.is_empty() {
+                            None
+                        } else {
+                            Some(query.as_str())
+                        };
+                        let home_team = state.team_id().await;
+                        let dm_messages = client.search_dm_messages(
+                            ch,
+                            query_str,
+                            max_results,
+                            home_team.as_deref(),
+                        ).await?;
+                        ranked_locally = query_str.is_some();
+
+                        info!("{} search returned {} messages", channel_type, dm_messages.len());
+
+                        // Filter by date if specified
+                        let mut filtered_messages = dm_messages;
+                        retain_within_dates(&mut filtered_messages, from_date.as_deref(), to_date.as_deref());
+
+                        info!("After date filtering: {} messages", filtered_messages.len());
+                        all_slack_messages = filtered_messages;
+
+                        // Skip the normal search flow
+                    }
+                } else if search_query == "USE_CONVERSATIONS_HISTORY" {
+                    // 🔥 CRITICAL: Detect USE_CONVERSATIONS_HISTORY flag for file extension filtering
+                    // When file extensions are specified, we need conversations.history API (not search.messages)
+                    // because only conversations.history includes file metadata in responses
                     info!("USE_CONVERSATIONS_HISTORY flag detected - using conversations.history API for file metadata");
 
                     // Extract channel name and resolve to ID
@@ -548,8 +1714,10 @@ pub async fn search_messages(
                         d.clone()
                     });
 
+                    let is_shared = is_shared_channel(&client, &channel_id).await;
+
                     // Get messages from conversations.history (includes file metadata)
-                    match client.get_channel_messages(&channel_id, oldest, latest, max_results).await {
+                    match client.get_channel_messages_with_options(&channel_id, oldest, latest, max_results, history_options).await {
                         Ok(mut messages) => {
                             info!("Retrieved {} messages from conversations.history for channel {}", messages.len(), channel_id);
 
@@ -559,6 +1727,7 @@ pub async fn search_messages(
                                     msg.channel = Some(SlackChannelInfo {
                                         id: channel_id.clone(),
                                         name: channel_name.to_string(),
+                                        is_shared,
                                     });
                                 }
                                 // Generate permalink if missing
@@ -578,22 +1747,59 @@ pub async fn search_messages(
                             return Err(AppError::from(e));
                         }
                     }
-                } else {
-                    // Normal search flow using search.messages API
-                    all_slack_messages =
-                        fetch_all_results(&client, search_query.clone(), max_results).await?;
-                }
 
-                // Filter by user IDs if multi-user search
-                if let Some(ref users) = user {
-                    if users.contains(',') {
-                        // Parse user IDs from comma-separated string
-                        let user_ids: Vec<String> = users
-                            .split(',')
-                            .map(|u| {
-                                let trimmed = u.trim();
-                                if trimmed.starts_with("<@") && trimmed.ends_with(">") {
-                                    trimmed[2..trimmed.len()-1].to_string()
+                    // Filter by user if specified (only for single user, multi-user is handled below)
+                    if let Some(ref user_filter) = user {
+                        // Skip single-user filter if this is a multi-user query (contains comma)
+                        if !user_filter.contains(',') {
+                            let user_id = if user_filter.starts_with("<@") && user_filter.ends_with(">") {
+                                &user_filter[2..user_filter.len()-1]
+                            } else {
+                                user_filter.trim_start_matches('@')
+                            };
+
+                            info!("Filtering {} messages for single user: {}", all_slack_messages.len(), user_id);
+
+                            // Debug: Log first few messages to check user field
+                            for (i, msg) in all_slack_messages.iter().take(5).enumerate() {
+                                debug!("Message {}: user={:?}, text preview={:?}",
+                                    i,
+                                    msg.user,
+                                    preview(&msg.text, 50)
+                                );
+                            }
+
+                            all_slack_messages = all_slack_messages.into_iter()
+                                .filter(|msg| {
+                                    let matches = msg.user.as_ref() == Some(&user_id.to_string());
+                                    if matches {
+                                        debug!("Found matching message from user {}: {:?}", user_id, preview(&msg.text, 50));
+                                    }
+                                    matches
+                                })
+                                .collect();
+
+                            info!("After single user filter: {} messages", all_slack_messages.len());
+                        }
+                    }
+                } else {
+                    // Normal search flow using search.messages API
+                    all_slack_messages = take_fetched(
+                        fetch_all_results(&client, search_query.clone(), max_results, search_options, dedupe).await?,
+                        &truncation,
+                    );
+                }
+
+                // Filter by user IDs if multi-user search
+                if let Some(ref users) = user {
+                    if users.contains(',') {
+                        // Parse user IDs from comma-separated string
+                        let user_ids: Vec<String> = users
+                            .split(',')
+                            .map(|u| {
+                                let trimmed = u.trim();
+                                if trimmed.starts_with("<@") && trimmed.ends_with(">") {
+                                    trimmed[2..trimmed.len()-1].to_string()
                                 } else {
                                     trimmed.trim_start_matches('@').to_string()
                                 }
@@ -605,7 +1811,7 @@ pub async fn search_messages(
 
                         // Debug: Log first few messages to see user field
                         for (i, msg) in all_slack_messages.iter().take(3).enumerate() {
-                            info!("Message {}: user={:?}, text={:?}", i, msg.user, &msg.text[..msg.text.len().min(50)]);
+                            info!("Message {}: user={:?}, text={:?}", i, msg.user, preview(&msg.text, 50));
                         }
 
                         let message_count = all_slack_messages.len();
@@ -640,12 +1846,16 @@ pub async fn search_messages(
             to_date: to_date.clone(),
             limit,
             is_realtime: force_refresh,
+            dates_are_exact,
         };
 
         let search_query = build_search_query(&search_request);
         info!("Executing search with query: {}", search_query);
 
-        all_slack_messages = fetch_all_results(&client, search_query.clone(), max_results).await?;
+        all_slack_messages = take_fetched(
+            fetch_all_results(&client, search_query.clone(), max_results, search_options, dedupe).await?,
+            &truncation,
+        );
 
         // Filter by user IDs if multi-user search
         if let Some(ref users) = user {
@@ -677,14 +1887,18 @@ pub async fn search_messages(
         }
     }
 
-    // Sort by timestamp (newest first) and limit to max_results
-    all_slack_messages.sort_by(|a, b| b.ts.cmp(&a.ts));
+    if dates_are_exact.unwrap_or(false) {
+        retain_within_dates(&mut all_slack_messages, from_date.as_deref(), to_date.as_deref());
+    }
+
+    // Sort as requested and limit to max_results
+    order_results(&mut all_slack_messages, search_options.sort, ranked_locally, &query);
     let mut slack_messages: Vec<_> = all_slack_messages.into_iter().take(max_results).collect();
 
     // Fetch reactions for each message if they don't have them
     // NOTE: search.messages API doesn't return reactions, so we need to fetch them separately
     // This was previously only done for Live mode, but reactions were missing in normal searches
-    if !slack_messages.is_empty() {
+    if fetch_reactions && !slack_messages.is_empty() {
         info!("Fetching reactions for {} messages", slack_messages.len());
 
         // Debug: Log channel types for all messages
@@ -709,22 +1923,21 @@ pub async fn search_messages(
         }
         info!("DEBUG: Channel type breakdown: {:?}", channel_type_counts);
 
+        let skip_reactions = if fetch_low_reaction.unwrap_or(false) {
+            HashSet::new()
+        } else {
+            let flagged = low_reaction_channel_ids(app).unwrap_or_else(|e| {
+                warn!("Could not load low-reaction channels: {}", e);
+                HashSet::new()
+            });
+            low_reaction_channels(&slack_messages, &flagged)
+        };
+        if !skip_reactions.is_empty() {
+            info!("Skipping reaction fetches for low-reaction channels: {:?}", skip_reactions);
+        }
+
         // Collect indices of messages that need reactions
-        let messages_needing_reactions: Vec<(usize, String, String)> = slack_messages
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, msg)| {
-                if msg.reactions.is_none() {
-                    if let Some(channel_info) = &msg.channel {
-                        Some((idx, channel_info.id.clone(), msg.ts.clone()))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let messages_needing_reactions = reaction_fetch_targets(reaction_mode, &slack_messages, &skip_reactions);
         
         if !messages_needing_reactions.is_empty() {
             info!("Fetching reactions for {} messages in parallel", messages_needing_reactions.len());
@@ -770,6 +1983,7 @@ pub async fn search_messages(
     // Pre-fetch all unique users in parallel for better performance
     let unique_user_ids: Vec<String> = slack_messages
         .iter()
+        .filter(|msg| inline_author(msg).is_none())
         .filter_map(|msg| msg.user.as_ref())
         .filter(|user_id| !user_cache_simple.contains_key(*user_id))
         .map(|s| s.to_string())
@@ -793,6 +2007,12 @@ pub async fn search_messages(
                             .as_ref()
                             .and_then(|p| p.display_name.clone().filter(|s| !s.is_empty()))
                             .or_else(|| user_info.real_name.clone().filter(|s| !s.is_empty()))
+                            .or_else(|| {
+                                user_info
+                                    .profile
+                                    .as_ref()
+                                    .and_then(|p| p.real_name.clone().filter(|s| !s.is_empty()))
+                            })
                             .unwrap_or_else(|| user_info.name.clone());
                         Some((user_id, name))
                     }
@@ -817,22 +2037,20 @@ pub async fn search_messages(
     let client_for_loop = client.clone();
 
     // Convert Slack messages to our Message format
+    let reactor_cap = state.reactor_cap().await;
     let mut messages = Vec::new();
     for slack_msg in slack_messages {
-        // Check if this message has thread information
-        // The search.messages API doesn't return reply_count, so we need to infer from other fields
-        let is_thread_parent = false; // We can't reliably determine this from search results alone
-        let reply_count = None; // Not available in search.messages response
-        
         // Log what we're getting
         info!(
             "Processing search result: ts={}, thread_ts={:?}, text_preview={}",
             slack_msg.ts, 
             slack_msg.thread_ts,
-            &slack_msg.text.chars().take(50).collect::<String>()
+            preview(&slack_msg.text, 50)
         );
         
-        let user_name = if let Some(user_id) = &slack_msg.user {
+        let user_name = if let Some(name) = inline_author(&slack_msg) {
+            name
+        } else if let Some(user_id) = &slack_msg.user {
             // Try to get from cache first
             if let Some(cached_name) = user_cache_simple.get(user_id) {
                 cached_name.clone()
@@ -881,10 +2099,13 @@ pub async fn search_messages(
                 cached_name.clone()
             } else {
                 let name = channel_info.name.clone();
-                // For now, assume regular channels (not DMs) when caching from search results
-                state
-                    .cache_channel(channel_info.id.clone(), name.clone(), false, false)
-                    .await;
+                // History results only carry the ID; fill_channel_names looks those up
+                if !name.is_empty() && name != channel_info.id {
+                    // For now, assume regular channels (not DMs) when caching from search results
+                    state
+                        .cache_channel(channel_info.id.clone(), name.clone(), false, false)
+                        .await;
+                }
                 name
             };
             (channel_info.id.clone(), channel_name)
@@ -897,25 +2118,31 @@ pub async fn search_messages(
         let user_cache_full = state.get_user_cache_full().await;
 
         // Replace user mentions in the text
-        let processed_text = replace_user_mentions(&slack_msg.text, &user_cache_full);
-
-        messages.push(Message {
-            ts: slack_msg.ts.clone(),
-            thread_ts: slack_msg.thread_ts.clone(),
-            user: slack_msg.user.clone().unwrap_or_else(|| {
-                // For bot messages, use bot_id if available, otherwise use empty string
-                slack_msg.bot_id.clone().unwrap_or_else(|| String::new())
-            }),
+        let processed_text = state
+            .resolve_subteam_mentions(replace_user_mentions(&slack_msg.text, &user_cache_full))
+            .await;
+        messages.push(search_result_message(
+            slack_msg,
             user_name,
-            text: processed_text,
-            channel: channel_id,
-            channel_name,
-            permalink: slack_msg.permalink.unwrap_or_else(|| String::new()),
-            is_thread_parent,
-            reply_count,
-            reactions: slack_msg.reactions.clone(),
-            files: slack_msg.files.clone(),
-        });
+            (channel_id, channel_name),
+            split_highlights(processed_text, highlight),
+            fetch_reactions,
+            reactor_cap,
+        ));
+    }
+
+    // The fast path attaches whatever reactions are already cached (instant)
+    // BUT skip cache for force_refresh (used in realtime updates)
+    if reaction_mode == ReactionMode::Cached {
+        if !force_refresh.unwrap_or(false) {
+            for message in messages.iter_mut() {
+                if let Some(cached_reactions) = state.get_cached_reactions(&message.channel, &message.ts).await {
+                    message.reactions = Some(cached_reactions);
+                }
+            }
+        } else {
+            info!("Skipping reaction cache due to force_refresh=true");
+        }
     }
 
     // REVERTED: The optimization was causing reactions to not display
@@ -964,6 +2191,12 @@ pub async fn search_messages(
         execution_time_ms
     );
 
+    flag_saved(&client, state, &mut messages).await;
+    fill_channel_names(&client, state, &mut messages).await;
+    if enrich_reply_counts {
+        fill_reply_counts(&client, state, &mut messages).await;
+    }
+
     let total = messages.len();
 
     // Build display query for multi-channel search
@@ -979,6 +2212,7 @@ pub async fn search_messages(
                 is_realtime: force_refresh,
                 has_files,
                             file_extensions: file_extensions.clone(),
+                            dates_are_exact,
             };
             build_search_query(&search_request)
         } else {
@@ -993,6 +2227,7 @@ pub async fn search_messages(
                 is_realtime: force_refresh,
                 has_files,
                             file_extensions: file_extensions.clone(),
+                            dates_are_exact,
             };
             build_search_query(&search_request)
         }
@@ -1007,15 +2242,27 @@ pub async fn search_messages(
             is_realtime: force_refresh,
             has_files,
                             file_extensions: file_extensions.clone(),
+                            dates_are_exact,
         };
         build_search_query(&search_request)
     };
 
+    let result_reason = if messages.is_empty() {
+        explain_empty_result(&client, channel.as_deref()).await
+    } else {
+        None
+    };
+
     let result = SearchResult {
         messages,
         total,
         query: display_query,
         execution_time_ms,
+        result_reason,
+        truncated_at_api_limit: truncation.api_limit.load(Ordering::Relaxed),
+        truncated_by_slack: truncation.by_slack.load(Ordering::Relaxed),
+        partial_enrichment: false,
+        failed_channels,
     };
 
     // Invalidate stale cache entries when new messages are found in live mode
@@ -1028,36 +2275,59 @@ pub async fn search_messages(
         }
     }
 
-    // Cache the result for future use (skip if force_refresh was used)
-    if !force_refresh.unwrap_or(false) {
+    // Cache the result for future use (skip if force_refresh was used, if reactions
+    // were left to the frontend, or if some channels failed so the next search gets
+    // another chance at them)
+    if fetch_reactions && !force_refresh.unwrap_or(false) && result.failed_channels.is_empty() {
         state
-            .cache_search_result(
-                &query,
-                &channel,
-                &user,
-                &from_date,
-                &to_date,
-                &limit,
-                &has_files,
-                &file_extensions,
-                result.clone(),
-            )
+            .cache_search_result(&cache_key, result.clone())
             .await;
     }
 
-    Ok(result)
+    Ok(apply_payload_budget(result, max_payload_bytes))
+}
+
+/// Sort channels for the picker: favorites (in saved order), then channels
+/// alphabetically, then DMs and group DMs, most recently active first
+fn sort_channel_list(channels: &mut [SlackConversation], favorites: &[String]) {
+    let favorite_rank: HashMap<&str, usize> = favorites
+        .iter()
+        .enumerate()
+        .map(|(rank, id)| (id.as_str(), rank))
+        .collect();
+    let is_dm = |c: &SlackConversation| c.is_im.unwrap_or(false) || c.is_mpim.unwrap_or(false);
+    let sort_name = |c: &SlackConversation| c.name.clone().unwrap_or_default().to_lowercase();
+
+    channels.sort_by(|a, b| {
+        let group = |c: &SlackConversation| match favorite_rank.get(c.id.as_str()) {
+            Some(_) => 0,
+            None if is_dm(c) => 2,
+            None => 1,
+        };
+        group(a).cmp(&group(b)).then_with(|| match group(a) {
+            0 => favorite_rank[a.id.as_str()].cmp(&favorite_rank[b.id.as_str()]),
+            2 => b.updated.unwrap_or(0).cmp(&a.updated.unwrap_or(0)),
+            _ => std::cmp::Ordering::Equal,
+        })
+        .then_with(|| sort_name(a).cmp(&sort_name(b)))
+        .then_with(|| a.id.cmp(&b.id))
+    });
 }
 
 #[tauri::command]
 pub async fn get_user_channels(
+    app: AppHandle,
     state: State<'_, AppState>,
     include_dms: Option<bool>,
+    include_archived: Option<bool>, // Archived channels are left out of pickers unless requested
 ) -> AppResult<Vec<(String, String)>> {
-    info!("[DEBUG] get_user_channels called with include_dms: {:?}", include_dms);
+    info!("[DEBUG] get_user_channels called with include_dms: {:?}, include_archived: {:?}", include_dms, include_archived);
     let client = state.get_client().await?;
 
     // Get regular channels (public and private)
-    let mut channels = client.get_channels().await?;
+    let mut channels = client
+        .get_channels_with_archived(include_archived.unwrap_or(false))
+        .await?;
     info!("[DEBUG] Regular channels fetched: {}", channels.len());
 
     // If DMs are requested and feature is enabled, include them
@@ -1441,6 +2711,14 @@ pub async fn get_user_channels(
                         is_member: None,  // Not applicable for DMs
                         is_muted: None,   // Not applicable for DMs
                         is_archived: None, // Not applicable for DMs
+                        last_read: None,
+                        is_shared: None,
+                        is_ext_shared: None,
+                        context_team_id: None,
+                        updated: dm.updated,
+                        unread_count_display: None,
+                        created: None,
+                        creator: None,
                     });
 
                     let channel_type = if is_mpim { "Group DM" } else { "DM" };
@@ -1449,7 +2727,7 @@ pub async fn get_user_channels(
             }
             Err(e) => {
                 // Log but don't fail - DMs are optional
-                if e.to_string().contains("missing_scope") || e.to_string().contains("im:read") {
+                if matches!(SlackApiError::of(&e), Some(SlackApiError::MissingScope { .. })) {
                     info!("DM channels not available: Missing im:read permission");
                 } else {
                     warn!("Failed to fetch DM channels: {}", e);
@@ -1458,6 +2736,12 @@ pub async fn get_user_channels(
         }
     }
 
+    let favorites = crate::commands::channels::get_favorite_channels(app).await.unwrap_or_else(|e| {
+        warn!("Could not load favorite channels for sorting: {}", e);
+        Vec::new()
+    });
+    sort_channel_list(&mut channels, &favorites);
+
     let mut channel_list = Vec::new();
     let mut dm_count = 0;
     let mut group_dm_count = 0;
@@ -1489,6 +2773,179 @@ pub async fn get_user_channels(
     Ok(channel_list)
 }
 
+/// Channels a user belongs to, and how they were found
+#[derive(Debug, Clone, Serialize)]
+pub struct UserChannelsMembership {
+    pub user_id: String,
+    /// (channel id, channel name) pairs
+    pub channels: Vec<(String, String)>,
+    /// Set when users.conversations wasn't allowed and channels were scanned instead
+    pub note: Option<String>,
+}
+
+/// Maximum number of conversations.members requests in flight for the fallback scan
+const MEMBERSHIP_SCAN_CONCURRENCY: usize = 5;
+
+/// List the channels `user_id` is a member of
+///
+/// Uses users.conversations; if the token can't browse another user's
+/// membership, falls back to scanning the members of the caller's own
+/// channels, which only finds channels the two users share.
+#[tauri::command]
+pub async fn get_user_channels_membership(
+    user_id: String,
+    state: State<'_, AppState>,
+) -> AppResult<UserChannelsMembership> {
+    use futures::stream::{self, StreamExt};
+
+    let client = state.get_client().await?;
+
+    let (channels, note) = match client.get_user_conversations(&user_id).await {
+        Ok(channels) => (channels, None),
+        Err(e) if matches!(SlackApiError::of(&e), Some(SlackApiError::MissingScope { .. })) => {
+            warn!("users.conversations not allowed ({}), scanning channel members instead", e);
+            let own_channels: Vec<SlackConversation> = client
+                .get_channels()
+                .await?
+                .into_iter()
+                .filter(|c| c.is_member == Some(true))
+                .collect();
+            let scanned = own_channels.len();
+
+            let shared: Vec<SlackConversation> = stream::iter(own_channels)
+                .map(|channel| {
+                    let client = &client;
+                    let user_id = &user_id;
+                    async move {
+                        match client.get_channel_members(&channel.id).await {
+                            Ok(members) if members.contains(user_id) => Some(channel),
+                            Ok(_) => None,
+                            Err(e) => {
+                                warn!("Failed to get members of {}: {}", channel.id, e);
+                                None
+                            }
+                        }
+                    }
+                })
+                .buffer_unordered(MEMBERSHIP_SCAN_CONCURRENCY)
+                .filter_map(|channel| async move { channel })
+                .collect()
+                .await;
+
+            let note = format!(
+                "{} Only channels you share were checked ({} scanned).",
+                e, scanned
+            );
+            (shared, Some(note))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut channels: Vec<(String, String)> = channels
+        .into_iter()
+        .map(|c| {
+            let name = c.name.unwrap_or_else(|| c.id.clone());
+            (c.id, name)
+        })
+        .collect();
+    channels.sort_by(|a, b| a.1.cmp(&b.1));
+
+    info!("User {} is in {} channels", user_id, channels.len());
+    Ok(UserChannelsMembership {
+        user_id,
+        channels,
+        note,
+    })
+}
+
+/// Upper bound on history scanned by find_file_messages
+const FILE_SCAN_LIMIT: usize = 2000;
+
+/// Messages with files found in a channel's history
+#[derive(Debug, Clone, Serialize)]
+pub struct FileMessages {
+    /// Newest first
+    pub messages: Vec<Message>,
+    /// The scan stopped at `FILE_SCAN_LIMIT`, so older files in the range may be missing
+    pub truncated: bool,
+}
+
+/// Messages with attached files in a channel over a date range, newest first
+///
+/// Walks conversations.history instead of searching `has:file`, which misses
+/// files in private channels. `file_type` narrows the files as in
+/// `file_matches_type`; thread replies are not scanned. At most
+/// `FILE_SCAN_LIMIT` of the newest messages in the range are scanned.
+#[tauri::command]
+pub async fn find_file_messages(
+    channel: String,
+    from: Option<String>,
+    to: Option<String>,
+    file_type: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<FileMessages> {
+    use crate::commands::channels::{date_bound_ts, history_message};
+
+    let client = state.get_client().await?;
+    let channel_id = client.resolve_channel_id(channel.trim()).await?;
+    let oldest = from.as_deref().map(|d| date_bound_ts(d, false)).transpose()?;
+    let latest = to.as_deref().map(|d| date_bound_ts(d, true)).transpose()?;
+
+    let history = client
+        .get_channel_messages_with_options(
+            &channel_id,
+            oldest,
+            latest,
+            FILE_SCAN_LIMIT,
+            HistoryOptions {
+                thread_replies: ThreadReplies::Lazy,
+                full_history: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+    let scanned = history.len();
+    let truncated = scanned >= FILE_SCAN_LIMIT;
+    if truncated {
+        warn!("File scan of {} stopped at {} messages", channel_id, FILE_SCAN_LIMIT);
+    }
+    let file_messages = retain_file_messages(history, file_type.as_deref());
+    info!(
+        "Found {} messages with files (type: {:?}) out of {} in {}",
+        file_messages.len(),
+        file_type,
+        scanned,
+        channel_id
+    );
+
+    let user_cache = state.get_user_cache_full().await;
+    let channel_name = state
+        .get_channel_cache()
+        .await
+        .get(&channel_id)
+        .cloned()
+        .unwrap_or_else(|| channel.trim_start_matches('#').to_string());
+
+    let reactor_cap = state.reactor_cap().await;
+    let team_domain = state.team_domain().await;
+    let mut messages = Vec::with_capacity(file_messages.len());
+    for msg in file_messages {
+        let mut message = history_message(
+            msg,
+            &channel_id,
+            &channel_name,
+            &user_cache,
+            reactor_cap,
+            team_domain.as_deref(),
+        );
+        message.text = state.resolve_subteam_mentions(message.text).await;
+        messages.push(message);
+    }
+    messages.sort_by(|a, b| b.ts.cmp(&a.ts));
+
+    Ok(FileMessages { messages, truncated })
+}
+
 #[tauri::command]
 pub async fn get_users(
     state: State<'_, AppState>,
@@ -1526,7 +2983,11 @@ pub async fn get_users(
 }
 
 #[tauri::command]
-pub async fn test_connection(token: String, state: State<'_, AppState>) -> AppResult<bool> {
+pub async fn test_connection(
+    app: AppHandle,
+    token: String,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
     debug!("Testing Slack connection");
 
     // Create a temporary client to test the token
@@ -1540,6 +3001,7 @@ pub async fn test_connection(token: String, state: State<'_, AppState>) -> AppRe
             if let Some(uid) = user_id {
                 state.set_user_id(uid).await;
             }
+            crate::commands::auth::spawn_warm_caches(app);
             Ok(true)
         }
         Ok((false, _)) => {
@@ -1618,6 +3080,418 @@ pub async fn get_user_info(user_id: String, state: State<'_, AppState>) -> AppRe
     Ok(user)
 }
 
+/// A user's profile photo at the size closest to what was asked for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UserAvatar {
+    pub url: String,
+    /// Pixel size of the chosen image; `None` for the original upload
+    pub size: Option<u32>,
+    /// The user never set a photo, so this is Slack's generated gravatar
+    pub is_default: bool,
+}
+
+/// Pick the smallest profile image at least `size` pixels wide, else the largest there is
+///
+/// `None` asks for the largest. Users on the default gravatar have no original,
+/// so they top out at the biggest sized image.
+fn best_avatar(profile: &SlackUserProfile, size: Option<u32>) -> Option<UserAvatar> {
+    let images = [
+        (Some(48), &profile.image_48),
+        (Some(72), &profile.image_72),
+        (Some(192), &profile.image_192),
+        (Some(512), &profile.image_512),
+        (Some(1024), &profile.image_1024),
+        (None, &profile.image_original),
+    ];
+    let available: Vec<(Option<u32>, &String)> = images
+        .iter()
+        .filter_map(|(px, url)| url.as_ref().filter(|u| !u.is_empty()).map(|u| (*px, u)))
+        .collect();
+
+    let (px, url) = match size {
+        Some(want) => available
+            .iter()
+            .find(|(px, _)| px.is_none_or(|px| px >= want))
+            .or(available.last()),
+        None => available.last(),
+    }?;
+
+    Some(UserAvatar {
+        url: url.to_string(),
+        size: *px,
+        is_default: profile.is_custom_image == Some(false),
+    })
+}
+
+/// Best profile photo URL for `user_id` at `size` pixels (largest if omitted)
+#[tauri::command]
+pub async fn get_user_avatar(
+    user_id: String,
+    size: Option<u32>,
+    state: State<'_, AppState>,
+) -> AppResult<Option<UserAvatar>> {
+    let client = state.get_client().await?;
+    let user_info = client.get_user_info(&user_id).await?;
+
+    let avatar = user_info.profile.as_ref().and_then(|p| best_avatar(p, size));
+    debug!("Avatar for {} at {:?}: {:?}", user_id, size, avatar);
+    Ok(avatar)
+}
+
+/// Characters of message text kept in a MessageSummary preview
+const SUMMARY_PREVIEW_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchSummaryResult {
+    pub messages: Vec<MessageSummary>,
+    pub total: usize,
+    pub query: String,
+    #[serde(rename = "executionTimeMs")]
+    pub execution_time_ms: u64,
+    #[serde(rename = "resultReason", skip_serializing_if = "Option::is_none")]
+    pub result_reason: Option<ResultReason>,
+    #[serde(rename = "truncatedAtApiLimit")]
+    pub truncated_at_api_limit: bool,
+    #[serde(rename = "truncatedBySlack")]
+    pub truncated_by_slack: bool,
+}
+
+/// Same as search_messages, but returns compact summaries to keep the IPC payload small
+///
+/// The full result stays in the search cache, so `get_message_details` is
+/// usually answered without another API call.
+#[tauri::command]
+pub async fn search_messages_summary(
+    app: AppHandle,
+    query: String,
+    channel: Option<String>,
+    user: Option<String>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    limit: Option<usize>,
+    force_refresh: Option<bool>,
+    has_files: Option<bool>,
+    file_extensions: Option<Vec<String>>,
+    sort: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<SearchSummaryResult> {
+    let result = search_messages(
+        app,
+        query,
+        channel,
+        user,
+        from_date,
+        to_date,
+        limit,
+        force_refresh,
+        None,
+        has_files,
+        file_extensions,
+        None,
+        sort,
+        None,
+        None,
+        // Summaries are small anyway, and the cached details should stay complete
+        Some(usize::MAX),
+        None,
+        None,
+        None,
+        None,
+        None,
+        state.clone(),
+    )
+    .await?;
+
+    // Remember the full messages so details can be served from memory
+    state.cache_message_details(&result.messages).await;
+
+    Ok(SearchSummaryResult {
+        messages: result
+            .messages
+            .iter()
+            .map(|msg| MessageSummary::from_message(msg, SUMMARY_PREVIEW_CHARS))
+            .collect(),
+        total: result.total,
+        query: result.query,
+        execution_time_ms: result.execution_time_ms,
+        result_reason: result.result_reason,
+        truncated_at_api_limit: result.truncated_at_api_limit,
+        truncated_by_slack: result.truncated_by_slack,
+    })
+}
+
+/// Get the full message behind a MessageSummary
+#[tauri::command]
+pub async fn get_message_details(
+    channel_id: String,
+    ts: String,
+    state: State<'_, AppState>,
+) -> AppResult<Message> {
+    if let Some(message) = state.get_message_details(&channel_id, &ts).await {
+        return Ok(message);
+    }
+
+    let client = state.get_client().await?;
+    let msg = client
+        .get_single_message(&channel_id, &ts)
+        .await?
+        .ok_or_else(|| AppError::ApiError(format!("Message {} not found in {}", ts, channel_id)))?;
+    // Replies carry no channel object, so ask conversations.info
+    let is_shared_channel = is_shared_channel(&client, &channel_id).await;
+
+    let team_domain = state.team_domain().await;
+
+    let user_cache = state.get_user_cache_full().await;
+    let channel_cache = state.get_channel_cache().await;
+    let user_id = msg
+        .user
+        .clone()
+        .or_else(|| msg.bot_id.clone())
+        .unwrap_or_default();
+    let user_name = user_cache
+        .get(&user_id)
+        .map(|u| u.name.clone())
+        .or_else(|| msg.username.clone())
+        .unwrap_or_else(|| user_id.clone());
+
+    Ok(Message {
+        ts: msg.ts.clone(),
+        thread_ts: msg.thread_ts.clone(),
+        user: user_id,
+        user_name,
+        text: state
+            .resolve_subteam_mentions(replace_user_mentions(&msg.text, &user_cache))
+            .await,
+        channel: channel_id.clone(),
+        channel_name: channel_cache.get(&channel_id).cloned().unwrap_or_else(|| channel_id.clone()),
+        permalink: build_slack_url(team_domain.as_deref(), &channel_id, &msg.ts, msg.thread_ts.as_deref()),
+        is_thread_parent: msg.reply_count.unwrap_or(0) > 0,
+        is_thread_reply: msg.is_thread_reply(),
+        reply_count: msg.reply_count,
+        reactions: capped_reactions(msg.reactions.clone(), state.reactor_cap().await),
+        files: msg.files.clone(),
+        highlights: None,
+        metadata: msg.metadata.clone(),
+        is_pinned: msg.is_pinned(),
+        is_saved: msg.is_saved(),
+        is_shared_channel,
+        block_urls: msg.block_urls(),
+    })
+}
+
+/// Maximum number of users.info requests in flight for get_users_batch
+const USER_BATCH_CONCURRENCY: usize = 10;
+
+/// Convert a users.info result to the frontend shape, preferring the display name
+fn slack_user_from_info(user_info: crate::slack::SlackUserInfo) -> SlackUser {
+    let preferred_name = user_info
+        .profile
+        .as_ref()
+        .and_then(|p| p.display_name.clone().filter(|s| !s.is_empty()))
+        .or_else(|| user_info.real_name.clone().filter(|s| !s.is_empty()))
+        .unwrap_or_else(|| user_info.name.clone());
+
+    SlackUser {
+        id: user_info.id,
+        name: preferred_name,
+        real_name: user_info
+            .real_name
+            .clone()
+            .or_else(|| user_info.profile.as_ref().and_then(|p| p.real_name.clone())),
+        display_name: user_info
+            .profile
+            .as_ref()
+            .and_then(|p| p.display_name.clone()),
+        avatar: user_info.profile.as_ref().and_then(|p| p.image_48.clone()),
+    }
+}
+
+/// Split requested user IDs into users already in the cache and IDs that still need fetching
+fn partition_cached_users(
+    ids: &[String],
+    user_cache: &HashMap<String, CachedUser>,
+) -> (HashMap<String, SlackUser>, Vec<String>) {
+    let mut found = HashMap::new();
+    let mut missing = Vec::new();
+
+    for id in ids {
+        if found.contains_key(id) || missing.contains(id) {
+            continue;
+        }
+        match user_cache.get(id) {
+            Some(cached) => {
+                found.insert(
+                    id.clone(),
+                    SlackUser {
+                        id: id.clone(),
+                        name: cached.name.clone(),
+                        real_name: cached.real_name.clone(),
+                        display_name: Some(cached.name.clone()),
+                        avatar: None,
+                    },
+                );
+            }
+            None => missing.push(id.clone()),
+        }
+    }
+
+    (found, missing)
+}
+
+/// Resolve many users in one call: cached users are returned directly and
+/// only cache misses hit users.info, with bounded parallelism
+#[tauri::command]
+pub async fn get_users_batch(
+    ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<SlackUser>> {
+    use futures::stream::{self, StreamExt};
+
+    let user_cache = state.get_user_cache_full().await;
+    let (mut users, missing) = partition_cached_users(&ids, &user_cache);
+
+    info!(
+        "get_users_batch: {} requested, {} cached, {} to fetch",
+        ids.len(),
+        users.len(),
+        missing.len()
+    );
+
+    if !missing.is_empty() {
+        let client = state.get_client().await?;
+
+        // get_user_info already synthesizes a placeholder for external (user_not_found) users
+        let fetched: Vec<(String, anyhow::Result<crate::slack::SlackUserInfo>)> =
+            stream::iter(missing)
+                .map(|id| {
+                    let client = &client;
+                    async move {
+                        let result = client.get_user_info(&id).await;
+                        (id, result)
+                    }
+                })
+                .buffer_unordered(USER_BATCH_CONCURRENCY)
+                .collect()
+                .await;
+
+        for (id, result) in fetched {
+            match result {
+                Ok(user_info) => {
+                    let user = slack_user_from_info(user_info);
+                    state
+                        .cache_user(id.clone(), user.name.clone(), user.real_name.clone())
+                        .await;
+                    users.insert(id, user);
+                }
+                Err(e) => {
+                    warn!("Failed to fetch user {}: {}", id, e);
+                }
+            }
+        }
+    }
+
+    // Keep the caller's order; unresolved IDs are left out
+    let mut seen = std::collections::HashSet::new();
+    Ok(ids
+        .iter()
+        .filter(|id| seen.insert(id.as_str()))
+        .filter_map(|id| users.remove(id))
+        .collect())
+}
+
+/// Maximum number of chat.getPermalink requests in flight
+const PERMALINK_CONCURRENCY: usize = 10;
+
+/// Permalinks for a result set, keyed by each target's index in the request
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PermalinkBatch {
+    /// (index, permalink) pairs, in index order
+    pub permalinks: Vec<(usize, String)>,
+    /// (index, error) pairs for targets that couldn't be resolved
+    pub failed: Vec<(usize, String)>,
+}
+
+/// Resolve `(index, channel, ts)` targets with bounded parallelism
+///
+/// A failed target is reported under its index instead of failing the batch.
+async fn resolve_permalinks<F, Fut>(targets: Vec<(usize, String, String)>, fetch: F) -> PermalinkBatch
+where
+    F: Fn(String, String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<String>>,
+{
+    use futures::stream::{self, StreamExt};
+
+    let results: Vec<(usize, anyhow::Result<String>)> = stream::iter(targets)
+        .map(|(index, channel, ts)| {
+            let request = fetch(channel, ts);
+            async move { (index, request.await) }
+        })
+        .buffer_unordered(PERMALINK_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut batch = PermalinkBatch::default();
+    for (index, result) in results {
+        match result {
+            Ok(permalink) => batch.permalinks.push((index, permalink)),
+            Err(e) => batch.failed.push((index, e.to_string())),
+        }
+    }
+    batch.permalinks.sort_by_key(|(index, _)| *index);
+    batch.failed.sort_by_key(|(index, _)| *index);
+    batch
+}
+
+/// Resolve permalinks for many `(channel, ts)` targets, e.g. to copy all result links
+#[tauri::command]
+pub async fn get_permalinks_batch(
+    targets: Vec<(String, String)>,
+    state: State<'_, AppState>,
+) -> AppResult<PermalinkBatch> {
+    let mut cached = Vec::new();
+    let mut missing = Vec::new();
+    for (index, (channel, ts)) in targets.into_iter().enumerate() {
+        match state.get_cached_permalink(&channel, &ts).await {
+            Some(permalink) => cached.push((index, permalink)),
+            None => missing.push((index, channel, ts)),
+        }
+    }
+
+    info!(
+        "get_permalinks_batch: {} cached, {} to fetch",
+        cached.len(),
+        missing.len()
+    );
+
+    let mut batch = PermalinkBatch::default();
+    if !missing.is_empty() {
+        let client = state.get_client().await?;
+        let keys: HashMap<usize, (String, String)> = missing
+            .iter()
+            .map(|(index, channel, ts)| (*index, (channel.clone(), ts.clone())))
+            .collect();
+
+        batch = resolve_permalinks(missing, |channel, ts| {
+            let client = &client;
+            async move { client.get_permalink(&channel, &ts).await }
+        })
+        .await;
+
+        for (index, permalink) in &batch.permalinks {
+            if let Some((channel, ts)) = keys.get(index) {
+                state.cache_permalink(channel, ts, permalink.clone()).await;
+            }
+        }
+        for (index, error) in &batch.failed {
+            warn!("Failed to get permalink for target {}: {}", index, error);
+        }
+    }
+
+    batch.permalinks.extend(cached);
+    batch.permalinks.sort_by_key(|(index, _)| *index);
+    Ok(batch)
+}
+
 // Batch reaction fetching structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReactionRequest {
@@ -1644,6 +3518,48 @@ pub struct BatchReactionsResponse {
     pub reactions: Vec<ReactionResponse>,
     pub fetched_count: usize,
     pub error_count: usize,
+    /// One line per distinct failure reason, e.g. "reactions unavailable for 5 messages (rate limited)"
+    pub errors: Vec<String>,
+}
+
+/// Short, human-readable reason for a failed reactions.get
+fn reaction_error_reason(error: &str) -> &'static str {
+    let error = error.to_lowercase();
+    if error.contains("ratelimited") || error.contains("rate limit") || error.contains("429") {
+        "rate limited"
+    } else if error.contains("channel_not_found") {
+        "channel not found"
+    } else if error.contains("message_not_found") {
+        "message not found"
+    } else if error.contains("not_in_channel") {
+        "not a member of the channel"
+    } else if error.contains("missing permission") || error.contains("missing_scope") {
+        "missing the reactions:read scope"
+    } else if error.contains("network error") {
+        "network error"
+    } else {
+        "unexpected error"
+    }
+}
+
+/// Collapse per-message reaction errors into one line per reason, in first-seen order
+fn summarize_reaction_errors(responses: &[ReactionResponse]) -> Vec<String> {
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+    for error in responses.iter().filter_map(|r| r.error.as_deref()) {
+        let reason = reaction_error_reason(error);
+        match counts.iter_mut().find(|(r, _)| *r == reason) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((reason, 1)),
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(reason, count)| {
+            let noun = if count == 1 { "message" } else { "messages" };
+            format!("reactions unavailable for {} {} ({})", count, noun, reason)
+        })
+        .collect()
 }
 
 #[tauri::command]
@@ -1700,7 +3616,8 @@ pub async fn batch_fetch_reactions(
             let message_index = req.message_index;
             
             async move {
-                match client.get_reactions(&channel_id, &timestamp).await {
+                // Checked, so a failed lookup is reported (and not cached as "no reactions")
+                match client.get_reactions_checked(&channel_id, &timestamp).await {
                     Ok(reactions) => {
                         // Cache the reactions
                         state.cache_reactions(&channel_id, &timestamp, reactions.clone()).await;
@@ -1759,10 +3676,16 @@ pub async fn batch_fetch_reactions(
         error_count
     );
     
+    let errors = summarize_reaction_errors(&all_responses);
+    for error in &errors {
+        warn!("Batch reaction fetch: {}", error);
+    }
+
     Ok(BatchReactionsResponse {
         reactions: all_responses,
         fetched_count,
         error_count,
+        errors,
     })
 }
 
@@ -1772,770 +3695,6 @@ pub async fn clear_reaction_cache(state: State<'_, AppState>) -> AppResult<()> {
     Ok(())
 }
 
-#[tauri::command]
-pub async fn search_messages_fast(
-    query: String,
-    channel: Option<String>,
-    user: Option<String>,
-    from_date: Option<String>,
-    to_date: Option<String>,
-    limit: Option<usize>,
-    force_refresh: Option<bool>,
-    has_files: Option<bool>,
-    file_extensions: Option<Vec<String>>,
-    state: State<'_, AppState>,
-) -> AppResult<SearchResult> {
-    // This is an optimized version that returns messages immediately without reactions
-    // Reactions will be loaded progressively by the frontend
-
-    let start_time = Instant::now();
-
-    // Check cache first (skip if force_refresh is true)
-    if !force_refresh.unwrap_or(false) {
-        if let Some(cached_result) = state
-            .get_cached_search(&query, &channel, &user, &from_date, &to_date, &limit, &has_files, &file_extensions)
-            .await
-        {
-            info!("Fast search: returning cached result in {}ms", start_time.elapsed().as_millis());
-            return Ok(cached_result);
-        }
-    } else {
-        info!("Fast search: Skipping cache due to force_refresh=true");
-    }
-
-    // Get the Slack client from app state
-    let client = state.get_client().await?;
-    let client = Arc::new(client);
-    
-    // Set default limit if not provided
-    let max_results = limit.unwrap_or(100);
-    
-    // Handle multi-channel search
-    let mut all_slack_messages = Vec::new();
-    
-    if let Some(ref channel_param) = channel {
-        if channel_param.contains(',') {
-            // Multi-channel search: search each channel separately IN PARALLEL
-            let channels: Vec<String> = channel_param
-                .split(',')
-                .map(|ch| ch.trim().to_string())
-                .filter(|ch| !ch.is_empty())
-                .collect();
-            
-            info!(
-                "Fast search: Performing parallel multi-channel search for {} channels",
-                channels.len()
-            );
-            
-            // Determine if this includes DM channels
-            let dm_channel_count = channels.iter()
-                .filter(|ch| ch.starts_with("D") || ch.starts_with("G"))
-                .count();
-
-            if dm_channel_count > 0 {
-                info!("Fast search: Multi-channel search includes {} DM/Group DM channels", dm_channel_count);
-            }
-
-            // Create futures for parallel execution
-            let search_futures = channels.iter().map(|single_channel| {
-                let client = Arc::clone(&client);
-                let query = query.clone();
-                let channel = single_channel.clone();
-                let user = user.clone();
-                let from_date = from_date.clone();
-                let to_date = to_date.clone();
-                let has_files = has_files;
-                let file_extensions = file_extensions.clone();
-
-                async move {
-                    // Check if this is a DM/Group DM channel
-                    let is_dm_channel = channel.starts_with("D") || channel.starts_with("G");
-
-                    if is_dm_channel {
-                        // Use DM-specific search for DM/Group DM channels
-                        info!("Fast search: Using DM search for channel: {}", channel);
-
-                        // For DMs, use the search_dm_messages function
-                        let query_str = if query.is_empty() {
-                            None
-                        } else {
-                            Some(query.as_str())
-                        };
-
-                        match client.search_dm_messages(&channel, query_str, max_results).await {
-                            Ok(mut messages) => {
-                                // Apply date filters if specified
-                                if let Some(ref from) = from_date {
-                                    messages.retain(|msg| {
-                                        let ts_float: f64 = msg.ts.parse().unwrap_or(0.0);
-                                        let msg_date = chrono::DateTime::from_timestamp(ts_float as i64, 0)
-                                            .map(|dt| dt.format("%Y-%m-%d").to_string())
-                                            .unwrap_or_default();
-                                        msg_date >= *from
-                                    });
-                                }
-
-                                if let Some(ref to) = to_date {
-                                    messages.retain(|msg| {
-                                        let ts_float: f64 = msg.ts.parse().unwrap_or(0.0);
-                                        let msg_date = chrono::DateTime::from_timestamp(ts_float as i64, 0)
-                                            .map(|dt| dt.format("%Y-%m-%d").to_string())
-                                            .unwrap_or_default();
-                                        msg_date <= *to
-                                    });
-                                }
-
-                                // Add channel info to DM messages
-                                for msg in &mut messages {
-                                    if msg.channel.is_none() {
-                                        msg.channel = Some(SlackChannelInfo {
-                                            id: channel.clone(),
-                                            name: channel.clone(), // Will be resolved to proper name later
-                                        });
-                                    }
-                                }
-
-                                info!("Fast search: Found {} messages in DM channel '{}'", messages.len(), channel);
-                                Ok::<Vec<SlackMessage>, anyhow::Error>(messages)
-                            }
-                            Err(e) => {
-                                error!("Fast search: Failed to search DM channel '{}': {}", channel, e);
-                                // Return empty vec on error to continue with other channels
-                                Ok::<Vec<SlackMessage>, anyhow::Error>(vec![])
-                            }
-                        }
-                    } else {
-                        // Use regular search for normal channels
-                        let search_request = SearchRequest {
-                            query: query.clone(),
-                            channel: Some(channel.clone()),
-                            user,
-                            has_files,
-                            file_extensions: file_extensions.clone(),
-                            from_date,
-                            to_date,
-                            limit: Some(max_results),
-                            is_realtime: force_refresh,
-                        };
-
-                        let search_query = build_search_query(&search_request);
-                        info!(
-                            "Fast search: Searching channel '{}' with query: {}",
-                            channel, search_query
-                        );
-
-                        match fetch_all_results(&client, search_query, max_results).await {
-                            Ok(messages) => {
-                                info!("Fast search: Found {} messages in channel '{}'", messages.len(), channel);
-                                Ok::<Vec<SlackMessage>, anyhow::Error>(messages)
-                            }
-                            Err(e) => {
-                                error!("Fast search: Failed to search channel '{}': {}", channel, e);
-                                // Return empty vec on error to continue with other channels
-                                Ok::<Vec<SlackMessage>, anyhow::Error>(vec![])
-                            }
-                        }
-                    }
-                }
-            });
-            
-            // Execute all searches in parallel
-            let results = join_all(search_futures).await;
-            
-            // Combine all results
-            for result in results {
-                if let Ok(messages) = result {
-                    all_slack_messages.extend(messages);
-                }
-            }
-            
-            // Sort by timestamp (newest first) and limit to max_results
-            all_slack_messages.sort_by(|a, b| b.ts.cmp(&a.ts));
-            all_slack_messages = all_slack_messages.into_iter().take(max_results).collect();
-
-            // Filter by user IDs if multi-user search (AFTER combining all channel results)
-            if let Some(ref users) = user {
-                if users.contains(',') {
-                    // Parse user IDs from comma-separated string
-                    let user_ids: Vec<String> = users
-                        .split(',')
-                        .map(|u| {
-                            let trimmed = u.trim();
-                            if trimmed.starts_with("<@") && trimmed.ends_with(">") {
-                                trimmed[2..trimmed.len()-1].to_string()
-                            } else {
-                                trimmed.trim_start_matches('@').to_string()
-                            }
-                        })
-                        .filter(|u| !u.is_empty())
-                        .collect();
-
-                    info!("Fast search (multi-channel): Filtering {} messages for users: {:?}", all_slack_messages.len(), user_ids);
-
-                    // Debug: Log first few messages to see user field
-                    for (i, msg) in all_slack_messages.iter().take(3).enumerate() {
-                        info!("Fast search (multi-channel): Message {}: user={:?}, text={:?}", i, msg.user, &msg.text[..msg.text.len().min(50)]);
-                    }
-
-                    let message_count = all_slack_messages.len();
-                    all_slack_messages = all_slack_messages.into_iter()
-                        .filter(|msg| {
-                            let matches = msg.user.as_ref()
-                                .map(|u| {
-                                    let user_match = user_ids.contains(u);
-                                    if !user_match && message_count < 10 {
-                                        info!("Fast search (multi-channel): User '{}' not in filter list {:?}", u, user_ids);
-                                    }
-                                    user_match
-                                })
-                                .unwrap_or(false);
-                            matches
-                        })
-                        .collect();
-                    info!("Fast search (multi-channel): After filtering: {} messages", all_slack_messages.len());
-                }
-            }
-        } else {
-            // Single channel search
-            let search_request = SearchRequest {
-                query: query.clone(),
-                channel: channel.clone(),
-                user: user.clone(),
-                from_date: from_date.clone(),
-                to_date: to_date.clone(),
-                limit,
-                is_realtime: force_refresh,
-                has_files,
-                            file_extensions: file_extensions.clone(),
-            };
-            
-            let search_query = build_search_query(&search_request);
-            info!("Fast search with query: {}", search_query);
-
-            // Check if this is a DM or Group DM channel search based on cached channel info
-            let is_dm_search = if let Some(ref ch) = channel {
-                // Get the full channel cache to check channel type
-                let channel_cache_full = state.get_channel_cache_full().await;
-                if let Some(cached_channel) = channel_cache_full.get(ch) {
-                    cached_channel.is_im || cached_channel.is_mpim
-                } else {
-                    // Fallback to ID prefix check for channels not in cache
-                    (ch.starts_with("D") || ch.starts_with("G")) && ch.len() > 8
-                }
-            } else {
-                false
-            };
-
-            if is_dm_search {
-                if let Some(ref ch) = channel {
-                    let channel_cache_full = state.get_channel_cache_full().await;
-                    let channel_type = if let Some(cached_channel) = channel_cache_full.get(ch) {
-                        if cached_channel.is_mpim {
-                            "Group DM"
-                        } else {
-                            "DM"
-                        }
-                    } else if ch.starts_with("G") {
-                        "Group DM"
-                    } else {
-                        "DM"
-                    };
-                    info!("Detected {} channel search for: {}", channel_type, ch);
-
-                    // Use the dedicated DM search function
-                    let query_str = if query.is_empty() {
-                        None
-                    } else {
-                        Some(query.as_str())
-                    };
-                    let dm_messages = client.search_dm_messages(
-                        ch,
-                        query_str,
-                        limit.unwrap_or(100),
-                    ).await?;
-
-                    info!("{} search returned {} messages", channel_type, dm_messages.len());
-
-                    // Filter by date if specified
-                    let filtered_messages: Vec<SlackMessage> = dm_messages.into_iter()
-                        .filter(|msg| {
-                            if let Some(ref from) = from_date {
-                                // msg.ts is a String in SlackMessage
-                                let ts_float: f64 = msg.ts.parse().unwrap_or(0.0);
-                                let msg_date = chrono::DateTime::from_timestamp(ts_float as i64, 0)
-                                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                                    .unwrap_or_default();
-                                msg_date >= *from
-                            } else {
-                                true
-                            }
-                        })
-                        .filter(|msg| {
-                            if let Some(ref to) = to_date {
-                                // msg.ts is a String in SlackMessage
-                                let ts_float: f64 = msg.ts.parse().unwrap_or(0.0);
-                                let msg_date = chrono::DateTime::from_timestamp(ts_float as i64, 0)
-                                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                                    .unwrap_or_default();
-                                msg_date <= *to
-                            } else {
-                                true
-                            }
-                        })
-                        .collect();
-
-                    info!("After date filtering: {} messages", filtered_messages.len());
-                    all_slack_messages = filtered_messages;
-
-                    // Skip the normal search flow
-                }
-            } else if !is_dm_search && search_query == "USE_CONVERSATIONS_HISTORY" {
-                // Use conversations.history for better private channel support
-                info!("Using conversations.history for channel + user search");
-
-                // Extract channel name and resolve to ID
-                let channel_name = match channel.as_ref() {
-                    Some(ch) => ch.trim_start_matches('#'),
-                    None => {
-                        error!("Channel is required for conversations.history");
-                        return Err(AppError::ApiError("Channel is required".to_string()));
-                    }
-                };
-
-                // Resolve channel name to ID
-                let channel_id = match client.resolve_channel_id(channel_name).await {
-                    Ok(id) => {
-                        info!("Resolved channel '{}' to ID: {}", channel_name, id);
-                        id
-                    }
-                    Err(e) => {
-                        error!("Failed to resolve channel '{}': {}", channel_name, e);
-                        return Err(AppError::from(anyhow!("Channel '{}' not found", channel_name)));
-                    }
-                };
-
-                // Convert date formats
-                let oldest = from_date.as_ref().map(|d| {
-                    // Convert ISO date to Unix timestamp
-                    if let Some(date_part) = d.split('T').next() {
-                        if let Ok(date) = chrono::NaiveDate::parse_from_str(date_part, "%Y-%m-%d") {
-                            // Use a safe default if time construction fails
-                            if let Some(datetime) = date.and_hms_opt(0, 0, 0) {
-                                let timestamp = datetime.and_utc().timestamp();
-                                timestamp.to_string()
-                            } else {
-                                // Fall back to original string
-                                d.clone()
-                            }
-                        } else {
-                            d.clone()
-                        }
-                    } else {
-                        d.clone()
-                    }
-                });
-
-                let latest = to_date.as_ref().map(|d| {
-                    // Convert ISO date to Unix timestamp
-                    if let Some(date_part) = d.split('T').next() {
-                        if let Ok(date) = chrono::NaiveDate::parse_from_str(date_part, "%Y-%m-%d") {
-                            // Use a safe default if time construction fails
-                            if let Some(datetime) = date.and_hms_opt(23, 59, 59) {
-                                let timestamp = datetime.and_utc().timestamp();
-                                timestamp.to_string()
-                            } else {
-                                // Fall back to original string
-                                d.clone()
-                            }
-                        } else {
-                            d.clone()
-                        }
-                    } else {
-                        d.clone()
-                    }
-                });
-
-                // Get all messages from the channel
-                match client.get_channel_messages(&channel_id, oldest, latest, max_results).await {
-                    Ok(mut messages) => {
-                        info!("Retrieved {} messages from channel {}", messages.len(), channel_id);
-
-                        // Add channel info to messages from conversations.history
-                        // (conversations.history doesn't include channel info in each message)
-                        for msg in &mut messages {
-                            if msg.channel.is_none() {
-                                msg.channel = Some(SlackChannelInfo {
-                                    id: channel_id.clone(),
-                                    name: channel_name.to_string(),
-                                });
-                            }
-                            // Also generate permalink if missing
-                            if msg.permalink.is_none() {
-                                // Format: https://workspace.slack.com/archives/CHANNEL_ID/pTIMESTAMP
-                                msg.permalink = Some(format!("https://workspace.slack.com/archives/{}/p{}",
-                                    channel_id,
-                                    msg.ts.replace(".", "")
-                                ));
-                            }
-                        }
-
-                        all_slack_messages = messages;
-                    }
-                    Err(e) => {
-                        error!("Failed to get channel messages: {}", e);
-                        return Err(AppError::from(e));
-                    }
-                }
-
-                // Filter by user if specified (only for single user, multi-user is handled below)
-                if let Some(ref user_filter) = user {
-                    // Skip single-user filter if this is a multi-user query (contains comma)
-                    if !user_filter.contains(',') {
-                        let user_id = if user_filter.starts_with("<@") && user_filter.ends_with(">") {
-                            &user_filter[2..user_filter.len()-1]
-                        } else {
-                            user_filter.trim_start_matches('@')
-                        };
-
-                        info!("Filtering {} messages for single user: {}", all_slack_messages.len(), user_id);
-
-                        // Debug: Log first few messages to check user field
-                        for (i, msg) in all_slack_messages.iter().take(5).enumerate() {
-                            debug!("Message {}: user={:?}, text preview={:?}",
-                                i,
-                                msg.user,
-                                &msg.text.chars().take(50).collect::<String>()
-                            );
-                        }
-
-                        all_slack_messages = all_slack_messages.into_iter()
-                            .filter(|msg| {
-                                let matches = msg.user.as_ref() == Some(&user_id.to_string());
-                                if matches {
-                                    debug!("Found matching message from user {}: {:?}", user_id, &msg.text.chars().take(50).collect::<String>());
-                                }
-                                matches
-                            })
-                            .collect();
-
-                        info!("After single user filter: {} messages", all_slack_messages.len());
-                    }
-                }
-            } else {
-                // Use normal search.messages API
-                all_slack_messages = fetch_all_results(&client, search_query.clone(), max_results).await?;
-            }
-
-            // Filter by user IDs if multi-user search
-            if let Some(ref users) = user {
-                if users.contains(',') {
-                    // Parse user IDs from comma-separated string
-                    let user_ids: Vec<String> = users
-                        .split(',')
-                        .map(|u| {
-                            let trimmed = u.trim();
-                            if trimmed.starts_with("<@") && trimmed.ends_with(">") {
-                                trimmed[2..trimmed.len()-1].to_string()
-                            } else {
-                                trimmed.trim_start_matches('@').to_string()
-                            }
-                        })
-                        .filter(|u| !u.is_empty())
-                        .collect();
-
-                    info!("Fast search (single channel): Filtering {} messages for users: {:?}", all_slack_messages.len(), user_ids);
-
-                    // Debug: Log first few messages to see user field
-                    for (i, msg) in all_slack_messages.iter().take(3).enumerate() {
-                        info!("Fast search (single channel): Message {}: user={:?}, text={:?}", i, msg.user, &msg.text[..msg.text.len().min(50)]);
-                    }
-
-                    let message_count = all_slack_messages.len();
-                    all_slack_messages = all_slack_messages.into_iter()
-                        .filter(|msg| {
-                            let matches = msg.user.as_ref()
-                                .map(|u| {
-                                    let user_match = user_ids.contains(u);
-                                    if !user_match && message_count < 10 {
-                                        info!("Fast search (single channel): User '{}' not in filter list {:?}", u, user_ids);
-                                    }
-                                    user_match
-                                })
-                                .unwrap_or(false);
-                            matches
-                        })
-                        .collect();
-                    info!("Fast search (single channel): After filtering: {} messages", all_slack_messages.len());
-                }
-            }
-        }
-    } else {
-        // No channel specified
-        let search_request = SearchRequest {
-            query: query.clone(),
-            channel: channel.clone(),
-            has_files,
-                            file_extensions: file_extensions.clone(),
-            user: user.clone(),
-            from_date: from_date.clone(),
-            to_date: to_date.clone(),
-            limit,
-            is_realtime: force_refresh,
-        };
-        
-        let search_query = build_search_query(&search_request);
-        info!("Fast search with query: {}", search_query);
-        
-        all_slack_messages = fetch_all_results(&client, search_query.clone(), max_results).await?;
-
-        // Filter by user IDs if multi-user search
-        if let Some(ref users) = user {
-            if users.contains(',') {
-                // Parse user IDs from comma-separated string
-                let user_ids: Vec<String> = users
-                    .split(',')
-                    .map(|u| {
-                        let trimmed = u.trim();
-                        if trimmed.starts_with("<@") && trimmed.ends_with(">") {
-                            trimmed[2..trimmed.len()-1].to_string()
-                        } else {
-                            trimmed.trim_start_matches('@').to_string()
-                        }
-                    })
-                    .filter(|u| !u.is_empty())
-                    .collect();
-
-                info!("Fast search: Filtering {} messages for users: {:?}", all_slack_messages.len(), user_ids);
-
-                // Debug: Log first few messages to see user field
-                for (i, msg) in all_slack_messages.iter().take(3).enumerate() {
-                    info!("Fast search: Message {}: user={:?}, text={:?}", i, msg.user, &msg.text[..msg.text.len().min(50)]);
-                }
-
-                let message_count = all_slack_messages.len();
-                all_slack_messages = all_slack_messages.into_iter()
-                    .filter(|msg| {
-                        let matches = msg.user.as_ref()
-                            .map(|u| {
-                                let user_match = user_ids.contains(u);
-                                if !user_match && message_count < 10 {
-                                    info!("Fast search: User '{}' not in filter list {:?}", u, user_ids);
-                                }
-                                user_match
-                            })
-                            .unwrap_or(false);
-                        matches
-                    })
-                    .collect();
-                info!("Fast search: After filtering: {} messages", all_slack_messages.len());
-            }
-        }
-    }
-
-    // Get user cache from state
-    let mut user_cache_simple = state.get_user_cache().await;
-    let channel_cache = state.get_channel_cache().await;
-    
-    // Collect unique user IDs that need fetching
-    let mut users_to_fetch = Vec::new();
-    for slack_msg in &all_slack_messages {
-        if let Some(user_id) = &slack_msg.user {
-            if !user_cache_simple.contains_key(user_id) && !users_to_fetch.contains(user_id) {
-                users_to_fetch.push(user_id.clone());
-            }
-        }
-    }
-    
-    // Batch fetch user information in parallel
-    use futures::future::join_all;
-    if !users_to_fetch.is_empty() {
-        info!("Fetching {} unique users in parallel", users_to_fetch.len());
-        let user_futures: Vec<_> = users_to_fetch
-            .into_iter()
-            .map(|user_id| {
-                let client = client.clone();
-                let uid = user_id.clone();
-                async move {
-                    match client.get_user_info(&uid).await {
-                        Ok(user_info) => {
-                            let name = user_info
-                                .profile
-                                .as_ref()
-                                .and_then(|p| p.display_name.clone().filter(|s| !s.is_empty()))
-                                .or_else(|| {
-                                    user_info
-                                        .profile
-                                        .as_ref()
-                                        .and_then(|p| p.real_name.clone().filter(|s| !s.is_empty()))
-                                })
-                                .or_else(|| user_info.real_name.clone().filter(|s| !s.is_empty()))
-                                .unwrap_or_else(|| user_info.name.clone());
-                            Some((uid, name))
-                        }
-                        Err(e) => {
-                            error!("Failed to get user info for {}: {}", uid, e);
-                            None
-                        }
-                    }
-                }
-            })
-            .collect();
-        
-        let user_results = join_all(user_futures).await;
-        
-        // Update cache with all fetched users
-        for result in user_results {
-            if let Some((user_id, name)) = result {
-                state.cache_user(user_id, name, None).await;
-            }
-        }
-        
-        // Refresh cache after batch fetching
-        user_cache_simple = state.get_user_cache().await;
-    }
-    
-    // Convert to our Message format quickly
-    let mut messages = Vec::new();
-    for slack_msg in all_slack_messages {
-        let user_name = if let Some(user_id) = &slack_msg.user {
-            user_cache_simple.get(user_id).cloned().unwrap_or_else(|| user_id.clone())
-        } else if let Some(bot_profile) = &slack_msg.bot_profile {
-            // For bot/app messages, use bot profile name
-            bot_profile.name.clone().unwrap_or_else(|| {
-                slack_msg.username.clone().unwrap_or_else(|| "Unknown".to_string())
-            })
-        } else {
-            slack_msg.username.clone().unwrap_or_else(|| "Unknown".to_string())
-        };
-        
-        let (channel_id, channel_name) = if let Some(channel_info) = &slack_msg.channel {
-            let name = channel_cache.get(&channel_info.id)
-                .cloned()
-                .unwrap_or_else(|| channel_info.name.clone());
-            (channel_info.id.clone(), name)
-        } else {
-            ("unknown".to_string(), "Unknown Channel".to_string())
-        };
-        
-        // Get fresh user cache for mention replacement
-        let user_cache_full = state.get_user_cache_full().await;
-        let processed_text = replace_user_mentions(&slack_msg.text, &user_cache_full);
-        
-        messages.push(Message {
-            ts: slack_msg.ts.clone(),
-            thread_ts: slack_msg.thread_ts.clone(),
-            user: slack_msg.user.clone().unwrap_or_else(|| {
-                // For bot messages, use bot_id if available, otherwise use empty string
-                slack_msg.bot_id.clone().unwrap_or_else(|| String::new())
-            }),
-            user_name,
-            text: processed_text,
-            channel: channel_id,
-            channel_name,
-            permalink: slack_msg.permalink.unwrap_or_else(|| String::new()),
-            is_thread_parent: false,
-            reply_count: None,
-            reactions: None, // No reactions - frontend will load them
-            files: slack_msg.files.clone(),
-        });
-    }
-    
-    // Check cache for any already-fetched reactions (instant)
-    // BUT skip cache for force_refresh (used in realtime updates)
-    if !force_refresh.unwrap_or(false) {
-        for message in messages.iter_mut() {
-            if let Some(cached_reactions) = state.get_cached_reactions(&message.channel, &message.ts).await {
-                message.reactions = Some(cached_reactions);
-            }
-        }
-    } else {
-        info!("Fast search: Skipping reaction cache due to force_refresh=true");
-    }
-
-    // Apply file extension filter if requested (takes precedence over has_files)
-    if let Some(ref extensions) = file_extensions {
-        if !extensions.is_empty() {
-            let before_count = messages.len();
-            messages.retain(|msg| matches_file_extensions(msg, extensions));
-            info!(
-                "Fast search: Applied file extension filter ({:?}): {}/{} messages match",
-                extensions,
-                messages.len(),
-                before_count
-            );
-        }
-    } else if let Some(true) = has_files {
-        // Fallback to has_files filter for backward compatibility
-        let before_count = messages.len();
-        messages.retain(|msg| {
-            msg.files.is_some() && !msg.files.as_ref().unwrap().is_empty()
-        });
-        info!("Fast search: Applied file filter: {}/{} messages with attachments", messages.len(), before_count);
-    }
-
-    let execution_time_ms = start_time.elapsed().as_millis() as u64;
-
-    info!(
-        "Fast search completed: {} results in {}ms (no reaction fetching)",
-        messages.len(),
-        execution_time_ms
-    );
-
-    let total = messages.len();
-
-    // Build display query for multi-channel search
-    let display_query = if let Some(ref channel_param) = channel {
-        if channel_param.contains(',') {
-            let search_request = SearchRequest {
-                query: query.clone(),
-                channel: Some(format!("({})", channel_param.replace(",", " OR "))),
-                user: user.clone(),
-                from_date: from_date.clone(),
-                to_date: to_date.clone(),
-                limit,
-                is_realtime: force_refresh,
-                has_files,
-                            file_extensions: file_extensions.clone(),
-            };
-            build_search_query(&search_request)
-        } else {
-            // Use the original query building for single channel
-            let search_request = SearchRequest {
-                query: query.clone(),
-                channel: channel.clone(),
-                user: user.clone(),
-                from_date: from_date.clone(),
-                to_date: to_date.clone(),
-                limit,
-                is_realtime: force_refresh,
-                has_files,
-                            file_extensions: file_extensions.clone(),
-            };
-            build_search_query(&search_request)
-        }
-    } else {
-        let search_request = SearchRequest {
-            query: query.clone(),
-            channel: channel.clone(),
-            user: user.clone(),
-            from_date: from_date.clone(),
-            to_date: to_date.clone(),
-            limit,
-            is_realtime: force_refresh,
-            has_files,
-                            file_extensions: file_extensions.clone(),
-        };
-        build_search_query(&search_request)
-    };
-
-    Ok(SearchResult {
-        messages,
-        total,
-        query: display_query,
-        execution_time_ms,
-    })
-}
-
 #[tauri::command]
 pub async fn fetch_reactions_progressive(
     channel_id: String,
@@ -2581,3 +3740,864 @@ pub async fn fetch_reactions_progressive(
     // The frontend can call again for remaining messages
     Ok(results)
 }
+
+/// File formats export_search_results can write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchExportFormat {
+    Markdown,
+    Csv,
+}
+
+impl SearchExportFormat {
+    fn from_param(format: &str) -> AppResult<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(SearchExportFormat::Markdown),
+            "csv" => Ok(SearchExportFormat::Csv),
+            other => Err(AppError::ParseError(format!(
+                "Unknown export format '{}' (expected markdown or csv)",
+                other
+            ))),
+        }
+    }
+}
+
+const EXPORT_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Render search results as Markdown, one section per message
+fn render_results_markdown(result: &SearchResult, offset: chrono::FixedOffset) -> String {
+    let mut out = format!("# Search: {}\n\n_{} messages_\n", result.query, result.messages.len());
+
+    for msg in &result.messages {
+        out.push_str(&format!(
+            "\n## {} in #{}\n\n_{}_\n\n",
+            msg.user_name,
+            msg.channel_name,
+            format_ts(&msg.ts, offset, EXPORT_DATETIME_FORMAT)
+        ));
+        for line in msg.text.lines() {
+            out.push_str(line);
+            out.push('\n');
+        }
+        if !msg.permalink.is_empty() {
+            out.push_str(&format!("\n[View in Slack]({})\n", msg.permalink));
+        }
+    }
+
+    out
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render search results as CSV with a header row
+fn render_results_csv(result: &SearchResult, offset: chrono::FixedOffset) -> String {
+    let mut out = String::from("ts,channel,user_name,text,permalink\n");
+    for msg in &result.messages {
+        let row = [
+            format_ts(&msg.ts, offset, EXPORT_DATETIME_FORMAT),
+            msg.channel_name.clone(),
+            msg.user_name.clone(),
+            msg.text.clone(),
+            msg.permalink.clone(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Write search results to `path` as Markdown or CSV, with local-time timestamps
+#[tauri::command]
+pub async fn export_search_results(result: SearchResult, format: String, path: String) -> AppResult<()> {
+    let offset = *chrono::Local::now().offset();
+    let output = match SearchExportFormat::from_param(&format)? {
+        SearchExportFormat::Markdown => render_results_markdown(&result, offset),
+        SearchExportFormat::Csv => render_results_csv(&result, offset),
+    };
+
+    tokio::fs::write(&path, output).await?;
+    info!("Exported {} search results to {}", result.messages.len(), path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validity(exists: bool, accessible: bool) -> ChannelValidity {
+        ChannelValidity {
+            exists,
+            accessible,
+            is_member: accessible,
+            channel_type: "public_channel".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_channel_frequency_average_and_peak() {
+        // 2024-01-01 .. 2024-01-03 UTC: 1, 3 and 2 messages
+        let messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1704067200.000100", "user": "U1", "text": "a" },
+            { "ts": "1704153600.000100", "user": "U1", "text": "b" },
+            { "ts": "1704157200.000100", "user": "U2", "text": "c" },
+            { "ts": "1704160800.000100", "user": "U3", "text": "d" },
+            { "ts": "1704240000.000100", "user": "U2", "text": "e" },
+            { "ts": "1704243600.000100", "text": "bot" }
+        ]))
+        .unwrap();
+        let utc = chrono::FixedOffset::east_opt(0).unwrap();
+
+        let freq = channel_frequency_from_messages("C1", &messages, 4, utc);
+        assert_eq!(freq.total_messages, 6);
+        assert!((freq.msgs_per_day_avg - 1.5).abs() < f64::EPSILON);
+        assert_eq!(freq.peak_day.as_deref(), Some("2024-01-02"));
+        assert_eq!(freq.peak_day_count, 3);
+        assert_eq!(freq.active_users, 3);
+
+        // The same messages land on different days in JST
+        let jst = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let buckets = bucket_by_day(&messages, jst);
+        assert_eq!(buckets.values().sum::<usize>(), 6);
+
+        let empty = channel_frequency_from_messages("C1", &[], 7, utc);
+        assert_eq!(empty.peak_day, None);
+        assert_eq!(empty.msgs_per_day_avg, 0.0);
+    }
+
+    #[test]
+    fn test_search_result_message_only_differs_in_reactions() {
+        let slack_msg: SlackMessage = serde_json::from_value(serde_json::json!({
+            "ts": "1704067200.000100",
+            "thread_ts": "1704067200.000100",
+            "user": "U1",
+            "text": "deploy done",
+            "permalink": "https://example.slack.com/archives/C1/p1704067200000100",
+            "reactions": [{ "name": "tada", "count": 2, "users": ["U2", "U3"] }],
+            "files": [{ "id": "F1", "name": "log.txt" }],
+            "pinned_to": ["C1"]
+        }))
+        .unwrap();
+
+        let build = |fetch_reactions| {
+            search_result_message(
+                slack_msg.clone(),
+                "alice".to_string(),
+                ("C1".to_string(), "deploys".to_string()),
+                ("deploy done".to_string(), None),
+                fetch_reactions,
+                50,
+            )
+        };
+        let full = build(true);
+        let fast = build(false);
+
+        assert_eq!(full.reactions.as_ref().map(Vec::len), Some(1));
+        assert!(fast.reactions.is_none());
+
+        // Inline reactions get the same reactor cap as reactions.get
+        let capped = search_result_message(
+            slack_msg.clone(),
+            "alice".to_string(),
+            ("C1".to_string(), "deploys".to_string()),
+            ("deploy done".to_string(), None),
+            true,
+            1,
+        );
+        let tada = &capped.reactions.unwrap()[0];
+        assert_eq!((tada.users.len(), tada.count, tada.users_truncated), (1, 2, true));
+
+        let mut full_json = serde_json::to_value(&full).unwrap();
+        let mut fast_json = serde_json::to_value(&fast).unwrap();
+        full_json.as_object_mut().unwrap().remove("reactions");
+        fast_json.as_object_mut().unwrap().remove("reactions");
+        assert_eq!(full_json, fast_json);
+        assert_eq!(full_json["isPinned"], serde_json::json!(true));
+
+        // Bot messages fall back to the bot id as author
+        let bot: SlackMessage =
+            serde_json::from_value(serde_json::json!({ "ts": "1.1", "bot_id": "B1", "text": "ci" })).unwrap();
+        let msg = search_result_message(bot, "CI".to_string(), ("C1".to_string(), "ci".to_string()), ("ci".to_string(), None), false, 50);
+        assert_eq!(msg.user, "B1");
+        assert_eq!(msg.permalink, "");
+    }
+
+    #[test]
+    fn test_channel_list_sort_order() {
+        let mut channels: Vec<SlackConversation> = serde_json::from_value(serde_json::json!([
+            { "id": "D1", "name": "@old friend", "is_im": true, "updated": 1_700_000_000_000i64 },
+            { "id": "C3", "name": "random", "is_channel": true },
+            { "id": "G1", "name": "👥 alice, bob", "is_mpim": true, "updated": 1_700_000_500_000i64 },
+            { "id": "C1", "name": "General", "is_channel": true },
+            { "id": "C9", "name": "zeta", "is_channel": true },
+            { "id": "D2", "name": "@boss", "is_im": true, "updated": 1_700_000_900_000i64 },
+            { "id": "C2", "name": "announcements", "is_channel": true }
+        ]))
+        .unwrap();
+        let favorites = vec!["C9".to_string(), "D1".to_string(), "C404".to_string()];
+
+        sort_channel_list(&mut channels, &favorites);
+
+        let order: Vec<&str> = channels.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(order, vec!["C9", "D1", "C2", "C1", "C3", "D2", "G1"]);
+        assert_eq!(channels[6].is_mpim, Some(true));
+    }
+
+    #[test]
+    fn test_reactions_not_fetched_when_opted_out() {
+        let messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1.1", "text": "a", "channel": { "id": "C1", "name": "general" } },
+            { "ts": "1.2", "text": "b", "channel": { "id": "C2", "name": "quiet" } },
+            { "ts": "1.3", "text": "c", "channel": { "id": "C1", "name": "general" },
+              "reactions": [{ "name": "eyes", "count": 1, "users": ["U1"] }] }
+        ]))
+        .unwrap();
+        let low_reaction = HashSet::from(["C2".to_string()]);
+
+        let targets = reaction_fetch_targets(ReactionMode::Fetch, &messages, &low_reaction);
+        assert_eq!(targets, vec![(0, "C1".to_string(), "1.1".to_string())]);
+
+        assert!(reaction_fetch_targets(ReactionMode::Skip, &messages, &HashSet::new()).is_empty());
+        assert!(reaction_fetch_targets(ReactionMode::Cached, &messages, &HashSet::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_permalinks_maps_indices_and_reports_failures() {
+        let targets = vec![
+            (0, "C1".to_string(), "1.1".to_string()),
+            (1, "C1".to_string(), "1.2".to_string()),
+            (2, "C2".to_string(), "2.1".to_string()),
+        ];
+
+        let batch = resolve_permalinks(targets, |channel, ts| async move {
+            if channel == "C2" {
+                Err(anyhow!("channel_not_found"))
+            } else {
+                Ok(format!("https://example.slack.com/archives/{}/p{}", channel, ts.replace('.', "")))
+            }
+        })
+        .await;
+
+        assert_eq!(
+            batch.permalinks,
+            vec![
+                (0, "https://example.slack.com/archives/C1/p11".to_string()),
+                (1, "https://example.slack.com/archives/C1/p12".to_string()),
+            ]
+        );
+        assert_eq!(batch.failed.len(), 1);
+        assert_eq!(batch.failed[0].0, 2);
+        assert!(batch.failed[0].1.contains("channel_not_found"));
+    }
+
+    #[test]
+    fn test_retain_within_dates_trims_to_timestamps() {
+        // 2024-01-05 13:00, 15:00 and 23:00 UTC, then 2024-01-06 09:00 UTC
+        let mut messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1704459600.000100", "text": "before" },
+            { "ts": "1704466800.000100", "text": "inside" },
+            { "ts": "1704495600.000100", "text": "late" },
+            { "ts": "1704531600.000100", "text": "next day" }
+        ]))
+        .unwrap();
+
+        // A plain to_date still covers its whole day, while 13:00 falls before 14:00
+        retain_within_dates(&mut messages, Some("2024-01-05T14:00:00Z"), Some("2024-01-05"));
+        let texts: Vec<&str> = messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["inside", "late"]);
+
+        retain_within_dates(&mut messages, None, Some("2024-01-05T20:00:00+00:00"));
+        assert_eq!(messages.len(), 1);
+
+        // Unparseable bounds leave the results alone
+        retain_within_dates(&mut messages, Some("soon"), None);
+        assert_eq!(messages.len(), 1);
+    }
+
+    #[test]
+    fn test_retain_within_dates_keeps_same_day_exact_range() {
+        // An exact range inside one day must not be compared against the day string
+        let mut messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "1704459600.000100", "text": "13:00" },
+            { "ts": "1704466800.000100", "text": "15:00" }
+        ]))
+        .unwrap();
+
+        retain_within_dates(&mut messages, Some("2024-01-05T14:00:00Z"), Some("2024-01-05T16:00:00Z"));
+        let texts: Vec<&str> = messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["15:00"]);
+    }
+
+    #[test]
+    fn test_low_reaction_channels_skip_flagged_and_bot_heavy() {
+        let mut raw = vec![
+            serde_json::json!({ "ts": "1.1", "user": "U1", "text": "hi", "channel": { "id": "CFLAG", "name": "flagged" } }),
+            serde_json::json!({ "ts": "1.2", "user": "U1", "text": "hi", "channel": { "id": "CTEAM", "name": "team" } }),
+        ];
+        for i in 0..LOW_REACTION_MIN_MESSAGES {
+            raw.push(serde_json::json!({
+                "ts": format!("2.{}", i), "bot_id": "B1", "subtype": "bot_message", "text": "build ok",
+                "channel": { "id": "CCI", "name": "ci" }
+            }));
+        }
+        // A handful of bot posts is not enough to call a channel low-reaction
+        raw.push(serde_json::json!({ "ts": "3.1", "bot_id": "B1", "text": "deploy", "channel": { "id": "CTEAM", "name": "team" } }));
+        let messages: Vec<SlackMessage> = serde_json::from_value(serde_json::Value::Array(raw)).unwrap();
+
+        let flagged: HashSet<String> = ["CFLAG".to_string()].into_iter().collect();
+        let skipped = low_reaction_channels(&messages, &flagged);
+
+        assert!(skipped.contains("CFLAG"));
+        assert!(skipped.contains("CCI"));
+        assert!(!skipped.contains("CTEAM"));
+        assert!(low_reaction_channels(&messages[..2], &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_partition_cached_users_only_fetches_misses() {
+        let mut cache = HashMap::new();
+        cache.insert(
+            "U1".to_string(),
+            CachedUser {
+                name: "alice".to_string(),
+                real_name: Some("Alice".to_string()),
+                cached_at: 0,
+            },
+        );
+
+        let ids: Vec<String> = ["U1", "U2", "U1", "U3", "U2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (found, missing) = partition_cached_users(&ids, &cache);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found["U1"].name, "alice");
+        assert_eq!(missing, vec!["U2".to_string(), "U3".to_string()]);
+    }
+
+    fn message_with_text(text: &str) -> Message {
+        Message {
+            ts: "1700000000.000100".to_string(),
+            thread_ts: None,
+            user: "U1".to_string(),
+            user_name: "alice".to_string(),
+            text: text.to_string(),
+            channel: "C1".to_string(),
+            channel_name: "general".to_string(),
+            permalink: String::new(),
+            is_thread_parent: false,
+            is_thread_reply: false,
+            reply_count: None,
+            reactions: Some(vec![
+                SlackReaction { name: "eyes".to_string(), count: 2, users: vec![], users_truncated: false },
+                SlackReaction { name: "tada".to_string(), count: 3, users: vec![], users_truncated: false },
+            ]),
+            files: None,
+            highlights: None,
+            metadata: None,
+            is_pinned: false,
+            is_saved: false,
+            is_shared_channel: false,
+            block_urls: Vec::new(),
+        }
+    }
+
+    fn export_fixture() -> SearchResult {
+        let mut first = message_with_text("deploy done, see \"notes\"\nthanks");
+        first.permalink = "https://x.slack.com/archives/C1/p1700000000000100".to_string();
+        let mut second = message_with_text("rollback");
+        second.ts = "1700003600.000200".to_string();
+        second.user_name = "bob".to_string();
+        second.channel_name = "ops".to_string();
+        SearchResult {
+            messages: vec![first, second],
+            total: 2,
+            query: "deploy".to_string(),
+            execution_time_ms: 0,
+            result_reason: None,
+            truncated_at_api_limit: false,
+            truncated_by_slack: false,
+            partial_enrichment: false,
+            failed_channels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_export_search_results_markdown() {
+        let offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let markdown = render_results_markdown(&export_fixture(), offset);
+
+        assert!(markdown.starts_with("# Search: deploy\n\n_2 messages_\n"));
+        assert!(markdown.contains("\n## alice in #general\n\n_2023-11-15 07:13:20_\n\ndeploy done, see \"notes\"\nthanks\n"));
+        assert!(markdown.contains("[View in Slack](https://x.slack.com/archives/C1/p1700000000000100)"));
+        assert!(markdown.contains("\n## bob in #ops\n\n_2023-11-15 08:13:20_\n\nrollback\n"));
+    }
+
+    #[test]
+    fn test_export_search_results_csv() {
+        let offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let csv = render_results_csv(&export_fixture(), offset);
+        let lines: Vec<&str> = csv.split_terminator('\n').collect();
+
+        assert_eq!(lines[0], "ts,channel,user_name,text,permalink");
+        assert_eq!(
+            lines[1],
+            "2023-11-15 07:13:20,general,alice,\"deploy done, see \"\"notes\"\"",
+        );
+        assert_eq!(lines[2], "thanks\",https://x.slack.com/archives/C1/p1700000000000100");
+        assert_eq!(lines[3], "2023-11-15 08:13:20,ops,bob,rollback,");
+        assert!(SearchExportFormat::from_param("pdf").is_err());
+    }
+
+    #[test]
+    fn test_inline_operators_fill_unset_fields() {
+        let params = SearchParams {
+            query: "deploy has:link in:#ops from:alice".to_string(),
+            user: Some("U0123456789".to_string()),
+            ..Default::default()
+        }
+        .with_inline_operators();
+
+        assert_eq!(params.query, "deploy has:link");
+        assert_eq!(params.channel.as_deref(), Some("ops"));
+        assert_eq!(params.user.as_deref(), Some("U0123456789"));
+    }
+
+    #[tokio::test]
+    async fn test_uncached_channel_names_are_backfilled() {
+        let mut messages = vec![
+            message_with_text("from history"),
+            message_with_text("same channel"),
+            message_with_text("already named"),
+            message_with_text("unreadable"),
+        ];
+        messages[0].channel = "C2".to_string();
+        messages[0].channel_name = "C2".to_string();
+        messages[1].channel = "C2".to_string();
+        messages[1].channel_name = String::new();
+        messages[2].channel = "C1".to_string();
+        messages[2].channel_name = "general".to_string();
+        messages[3].channel = "C3".to_string();
+        messages[3].channel_name = "C3".to_string();
+
+        let channel_ids = unnamed_channel_ids(&messages);
+        assert_eq!(channel_ids, vec!["C2".to_string(), "C3".to_string()]);
+
+        let fetched = std::sync::Mutex::new(Vec::new());
+        let names = lookup_channel_names(channel_ids, |channel_id| {
+            fetched.lock().unwrap().push(channel_id.clone());
+            async move {
+                match channel_id.as_str() {
+                    "C2" => Ok(Some("releases".to_string())),
+                    // conversations.info's stand-in for inaccessible channels
+                    _ => Ok(Some(channel_id)),
+                }
+            }
+        })
+        .await;
+        backfill_channel_names(&mut messages, &names);
+
+        assert_eq!(fetched.lock().unwrap().len(), 2);
+        let channel_names: Vec<&str> = messages.iter().map(|m| m.channel_name.as_str()).collect();
+        assert_eq!(channel_names, vec!["releases", "releases", "general", "C3"]);
+    }
+
+    #[test]
+    fn test_display_name_from_resolves_to_user_id() {
+        let users = HashMap::from([(
+            "U0123ABCDEF".to_string(),
+            CachedUser {
+                name: "john".to_string(),
+                real_name: Some("John Smith".to_string()),
+                cached_at: 0,
+            },
+        )]);
+
+        let user = resolve_user_names("John Smith", &users);
+        assert_eq!(user, "U0123ABCDEF");
+        let query = build_search_query(&SearchRequest {
+            query: "deploy".to_string(),
+            channel: None,
+            user: Some(user),
+            from_date: None,
+            to_date: None,
+            limit: None,
+            is_realtime: None,
+            has_files: None,
+            file_extensions: None,
+            dates_are_exact: None,
+        });
+        assert_eq!(query, "deploy from:U0123ABCDEF");
+
+        assert_eq!(resolve_user_names("@JOHN,U0999ZZZZZZ", &users), "U0123ABCDEF,U0999ZZZZZZ");
+        assert_eq!(resolve_user_names("Jane Doe", &users), "Jane Doe");
+
+        // A second John Smith makes the name ambiguous, so it is left for Slack
+        let mut users = users;
+        users.insert(
+            "U0456GHIJKL".to_string(),
+            CachedUser {
+                name: "jsmith".to_string(),
+                real_name: Some("John Smith".to_string()),
+                cached_at: 0,
+            },
+        );
+        assert_eq!(resolve_user_names("John Smith", &users), "John Smith");
+        assert_eq!(resolve_user_names("jsmith", &users), "U0456GHIJKL");
+    }
+
+    #[tokio::test]
+    async fn test_reply_count_enrichment_marks_thread_parent() {
+        let mut messages = vec![message_with_text("parent"), message_with_text("standalone")];
+        messages[1].ts = "1700000000.000200".to_string();
+        let targets = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (i, m.channel.clone(), m.ts.clone()))
+            .collect();
+
+        let found = lookup_reply_infos(targets, |_, ts| async move {
+            Ok(Some(if ts == "1700000000.000100" {
+                ReplyInfo { thread_ts: Some(ts), reply_count: 4 }
+            } else {
+                ReplyInfo { thread_ts: None, reply_count: 0 }
+            }))
+        })
+        .await;
+        for (index, info) in found {
+            apply_reply_info(&mut messages[index], &info);
+        }
+
+        assert_eq!(messages[0].reply_count, Some(4));
+        assert!(messages[0].is_thread_parent);
+        assert_eq!(messages[0].thread_ts.as_deref(), Some("1700000000.000100"));
+        assert_eq!(messages[1].reply_count, Some(0));
+        assert!(!messages[1].is_thread_parent);
+    }
+
+    #[test]
+    fn test_message_summary_truncates_char_safe() {
+        let long_text = "日本語のテキスト".repeat(50);
+        let summary = MessageSummary::from_message(&message_with_text(&long_text), 10);
+        assert_eq!(summary.text_preview, "日本語のテキスト日本…");
+        assert_eq!(summary.reaction_count, 5);
+        assert!(!summary.has_files);
+
+        let short = MessageSummary::from_message(&message_with_text("short"), 10);
+        assert_eq!(short.text_preview, "short");
+    }
+
+    #[test]
+    fn test_classify_empty_result_no_matches() {
+        assert_eq!(
+            classify_empty_result(&validity(true, true), true),
+            ResultReason::NoMatches
+        );
+    }
+
+    #[test]
+    fn test_classify_empty_result_no_access() {
+        assert_eq!(
+            classify_empty_result(&validity(true, false), false),
+            ResultReason::NoAccess
+        );
+        assert_eq!(
+            classify_empty_result(&validity(false, false), false),
+            ResultReason::NoAccess
+        );
+    }
+
+    #[test]
+    fn test_classify_empty_result_empty_channel() {
+        assert_eq!(
+            classify_empty_result(&validity(true, true), false),
+            ResultReason::Empty
+        );
+    }
+
+    #[tokio::test]
+    async fn test_channel_failing_once_is_retried() {
+        use std::sync::atomic::AtomicUsize;
+
+        let flaky_calls = AtomicUsize::new(0);
+        let channels = vec!["C1".to_string(), "C2".to_string(), "C3".to_string()];
+
+        let (messages, failed) = search_channels_with_retry(&channels, |channel| {
+            let flaky_calls = &flaky_calls;
+            async move {
+                match channel.as_str() {
+                    "C2" if flaky_calls.fetch_add(1, Ordering::SeqCst) == 0 => {
+                        Err(anyhow!("Network error: connection reset"))
+                    }
+                    "C3" => Err(anyhow!("Slack API error: channel_not_found")),
+                    _ => Ok(vec![serde_json::from_value::<SlackMessage>(
+                        serde_json::json!({ "ts": "1.0", "text": channel }),
+                    )
+                    .unwrap()]),
+                }
+            }
+        })
+        .await;
+
+        let texts: Vec<&str> = messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["C1", "C2"]);
+        assert_eq!(flaky_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(failed, vec!["C3".to_string()]);
+    }
+
+    #[test]
+    fn test_relevance_ranks_all_terms_above_one() {
+        let msg = |ts: &str, text: &str| {
+            serde_json::from_value::<SlackMessage>(serde_json::json!({ "ts": ts, "text": text })).unwrap()
+        };
+        let mut messages = vec![
+            msg("1700000300.000000", "deploy deploy deploy deploy deploy deploy"),
+            msg("1700000100.000000", "the deploy of the api failed"),
+            msg("1700000200.000000", "api deploy failed again"),
+            msg("1700000400.000000", "nothing relevant"),
+        ];
+
+        sort_by_relevance(&mut messages, "api deploy failed");
+
+        let order: Vec<&str> = messages.iter().map(|m| m.ts.as_str()).collect();
+        // The exact phrase wins, then all terms, then a single repeated term
+        assert_eq!(
+            order,
+            vec!["1700000200.000000", "1700000100.000000", "1700000300.000000", "1700000400.000000"]
+        );
+        assert!(relevance_score("api deploy failed", "api deploy failed") > relevance_score("api deploy", "api deploy failed"));
+    }
+
+    #[test]
+    fn test_score_sort_keeps_slack_order() {
+        let mut messages: Vec<SlackMessage> = ["1700000100.000000", "1700000300.000000", "1700000200.000000"]
+            .iter()
+            .map(|ts| serde_json::from_value(serde_json::json!({ "ts": ts, "text": "deploy" })).unwrap())
+            .collect();
+
+        order_results(&mut messages, SearchSort::Score, false, "deploy");
+        let order: Vec<&str> = messages.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(order, vec!["1700000100.000000", "1700000300.000000", "1700000200.000000"]);
+
+        order_results(&mut messages, SearchSort::Timestamp, false, "deploy");
+        let order: Vec<&str> = messages.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(order, vec!["1700000300.000000", "1700000200.000000", "1700000100.000000"]);
+    }
+
+    #[test]
+    fn test_avatar_picks_size_with_fallback() {
+        let profile: SlackUserProfile = serde_json::from_value(serde_json::json!({
+            "image_48": "https://avatars.example/48.png",
+            "image_72": "https://avatars.example/72.png",
+            "image_192": "https://avatars.example/192.png",
+            "image_512": "https://avatars.example/512.png",
+            "image_original": "https://avatars.example/original.png",
+            "is_custom_image": true
+        }))
+        .unwrap();
+
+        let pick = |size| best_avatar(&profile, size).unwrap();
+        assert_eq!(pick(Some(48)).url, "https://avatars.example/48.png");
+        assert_eq!(pick(Some(100)).url, "https://avatars.example/192.png");
+        assert_eq!(pick(Some(600)).url, "https://avatars.example/original.png");
+        assert_eq!(pick(None).size, None);
+        assert!(!pick(None).is_default);
+
+        // Default gravatar: no original upload, so the largest sized image is the best
+        let gravatar: SlackUserProfile = serde_json::from_value(serde_json::json!({
+            "image_48": "https://secure.gravatar.com/avatar/abc.jpg?s=48",
+            "image_192": "https://secure.gravatar.com/avatar/abc.jpg?s=192",
+            "is_custom_image": false
+        }))
+        .unwrap();
+        let avatar = best_avatar(&gravatar, Some(512)).unwrap();
+        assert_eq!(avatar.size, Some(192));
+        assert!(avatar.is_default);
+
+        let empty: SlackUserProfile = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(best_avatar(&empty, Some(48)), None);
+    }
+
+    #[test]
+    fn test_reaction_errors_are_deduped_with_counts() {
+        use crate::slack::client::parse_reactions;
+
+        // Errors as get_reactions_checked reports them for real reactions.get bodies
+        let response = |index, body: &str| {
+            let result = parse_reactions(body);
+            ReactionResponse {
+                message_index: index,
+                error: result.as_ref().err().map(|e| e.to_string()),
+                reactions: result.ok(),
+            }
+        };
+        let responses = vec![
+            response(0, r#"{"ok":false,"error":"ratelimited"}"#),
+            response(1, r#"{"ok":true,"message":{"reactions":[]}}"#),
+            response(2, r#"{"ok":false,"error":"channel_not_found"}"#),
+            response(3, r#"{"ok":false,"error":"ratelimited"}"#),
+            response(4, r#"{"ok":false,"error":"message_not_found"}"#),
+            response(5, r#"{"ok":false,"error":"not_in_channel"}"#),
+            response(6, r#"{"ok":false,"error":"no_reaction"}"#),
+            response(7, r#"{"ok":false,"error":"missing_scope","needed":"reactions:read"}"#),
+        ];
+
+        assert_eq!(
+            summarize_reaction_errors(&responses),
+            vec![
+                "reactions unavailable for 2 messages (rate limited)".to_string(),
+                "reactions unavailable for 1 message (channel not found)".to_string(),
+                "reactions unavailable for 1 message (message not found)".to_string(),
+                "reactions unavailable for 1 message (not a member of the channel)".to_string(),
+                "reactions unavailable for 1 message (missing the reactions:read scope)".to_string(),
+            ]
+        );
+        assert_eq!(responses[6].reactions.as_deref().map(<[_]>::len), Some(0));
+        assert!(summarize_reaction_errors(&[responses[1].clone()]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_permanent_channel_error_is_not_retried() {
+        use std::sync::atomic::AtomicUsize;
+
+        let calls = AtomicUsize::new(0);
+        let channels = vec!["C1".to_string()];
+
+        let (messages, failed) = search_channels_with_retry(&channels, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(AppError::slack_api("channel_not_found").into()) }
+        })
+        .await;
+
+        assert!(messages.is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(failed, vec!["C1".to_string()]);
+    }
+
+    #[test]
+    fn test_shared_channel_flag_and_inline_author() {
+        let info: SlackConversation = serde_json::from_value(serde_json::json!({
+            "id": "C0SHARED",
+            "name": "acme-partners",
+            "is_channel": true,
+            "is_shared": true,
+            "is_ext_shared": true
+        }))
+        .unwrap();
+        assert!(info.is_slack_connect());
+        let org_shared = SlackConversation { is_ext_shared: Some(false), ..info.clone() };
+        assert!(!org_shared.is_slack_connect());
+
+        let shared: SlackMessage = serde_json::from_value(serde_json::json!({
+            "ts": "1.0",
+            "user": "U0EXTERNAL",
+            "text": "hello from acme",
+            "channel": { "id": "C0SHARED", "name": "acme-partners", "is_ext_shared": true },
+            "user_profile": { "display_name": "", "real_name": "Ada Partner" }
+        }))
+        .unwrap();
+        assert!(shared.in_shared_channel());
+        assert_eq!(inline_author(&shared).as_deref(), Some("Ada Partner"));
+
+        // The inline profile is ignored outside Connect channels
+        let internal: SlackMessage = serde_json::from_value(serde_json::json!({
+            "ts": "2.0",
+            "user": "U1",
+            "text": "hi",
+            "channel": { "id": "C1", "name": "general" },
+            "user_profile": { "display_name": "ada" }
+        }))
+        .unwrap();
+        assert!(!internal.in_shared_channel());
+        assert_eq!(inline_author(&internal), None);
+    }
+
+    #[test]
+    fn test_retain_file_messages_by_type() {
+        let messages: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+            { "ts": "4.0", "text": "screenshot", "files": [
+                { "id": "F1", "name": "shot.PNG", "mimetype": "image/png", "filetype": "png",
+                  "url_private": "https://files.slack.com/F1" }
+            ] },
+            { "ts": "3.0", "text": "report", "files": [
+                { "id": "F2", "name": "q3.pdf", "mimetype": "application/pdf", "filetype": "pdf",
+                  "url_private": "https://files.slack.com/F2" }
+            ] },
+            { "ts": "2.0", "text": "deleted image", "files": [
+                { "id": "F3", "mode": "tombstone", "mimetype": "image/jpeg" }
+            ] },
+            { "ts": "1.0", "text": "no files" }
+        ]))
+        .unwrap();
+
+        let images = retain_file_messages(messages.clone(), Some("image"));
+        let ts: Vec<&str> = images.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(ts, vec!["4.0"]);
+
+        assert_eq!(retain_file_messages(messages.clone(), Some("PDF")).len(), 1);
+        assert_eq!(retain_file_messages(messages.clone(), Some(".png")).len(), 1);
+        assert_eq!(retain_file_messages(messages, None).len(), 2);
+    }
+
+    #[test]
+    fn test_result_limit_defaults_and_clamps() {
+        assert_eq!(resolve_result_limit(None), DEFAULT_RESULT_LIMIT);
+        assert_eq!(resolve_result_limit(Some(250)), 250);
+        assert_eq!(resolve_result_limit(Some(1_000_000)), MAX_RESULT_LIMIT);
+    }
+
+    #[test]
+    fn test_take_fetched_propagates_slack_truncation() {
+        let truncation = Truncation::default();
+        let fetched = crate::slack::client::FetchedResults {
+            messages: Vec::new(),
+            truncated_at_api_limit: false,
+            truncated_by_slack: true,
+        };
+
+        take_fetched(fetched, &truncation);
+        assert!(truncation.by_slack.load(Ordering::Relaxed));
+        assert!(!truncation.api_limit.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_starred_message_is_flagged_saved() {
+        let mut messages = vec![message_with_text("saved one"), message_with_text("other")];
+        messages[1].ts = "1700000999.000100".to_string();
+        let saved = HashSet::from([(messages[0].channel.clone(), messages[0].ts.clone())]);
+
+        mark_saved(&mut messages, &saved);
+        assert!(messages[0].is_saved);
+        assert!(!messages[1].is_saved);
+    }
+
+    #[test]
+    fn test_enrichment_budget_keeps_core_fields() {
+        let mut messages: Vec<Message> = (0..10)
+            .map(|i| {
+                let mut msg = message_with_text(&format!("message {}", i));
+                msg.ts = format!("1700000000.{:06}", i);
+                msg
+            })
+            .collect();
+        let one = serde_json::to_vec(&messages[0]).unwrap().len();
+
+        // Room for three fully enriched messages
+        assert!(enforce_enrichment_budget(&mut messages, one * 3 + one / 2));
+        assert!(messages[..3].iter().all(|m| m.reactions.is_some()));
+        assert!(messages[3..].iter().all(|m| m.reactions.is_none() && m.files.is_none()));
+        assert_eq!(messages[9].text, "message 9");
+        assert_eq!(messages[9].ts, "1700000000.000009");
+
+        let mut small = vec![message_with_text("fits")];
+        assert!(!enforce_enrichment_budget(&mut small, usize::MAX));
+        assert!(small[0].reactions.is_some());
+    }
+}
@@ -1,7 +1,13 @@
 use crate::error::AppResult;
+use crate::slack::models::{
+    capped_reactions, SlackConversationsRepliesResponse, SlackFollowedThread, SlackReplyMessage,
+};
+use crate::slack::parser::preview;
+use crate::slack::parser::build_slack_url;
 use crate::slack::{parse_slack_url, Message, ParsedUrl, ThreadMessages};
 use crate::state::{AppState, CachedUser};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use tauri::State;
 use tracing::{debug, error, info, warn};
 
@@ -43,31 +49,17 @@ pub async fn get_thread(
         }
     };
 
-    // Check if we got a single reply message
-    let actual_thread_ts = if let Some(ref messages) = initial_response.messages {
-        if messages.len() == 1 {
-            if let Some(first_msg) = messages.first() {
-                // If this single message has a thread_ts different from its ts,
-                // it's a reply and we should use its thread_ts to get the full thread
-                if let Some(ref msg_thread_ts) = first_msg.thread_ts {
-                    if msg_thread_ts != &first_msg.ts {
-                        info!("Detected child message (ts={}, thread_ts={}). Fetching full thread using parent ts={}",
-                            first_msg.ts, msg_thread_ts, msg_thread_ts);
-                        msg_thread_ts.clone()
-                    } else {
-                        thread_ts.clone()
-                    }
-                } else {
-                    thread_ts.clone()
-                }
-            } else {
-                thread_ts.clone()
+    // A single message back means we may have been given a reply; use its parent's ts
+    let actual_thread_ts = match initial_response.messages.as_deref() {
+        Some([only]) => {
+            let root_ts = thread_root_ts(only);
+            if root_ts != thread_ts {
+                info!("Detected child message (ts={}). Fetching full thread using parent ts={}",
+                    only.ts, root_ts);
             }
-        } else {
-            thread_ts.clone()
+            root_ts
         }
-    } else {
-        thread_ts.clone()
+        _ => thread_ts.clone(),
     };
 
     // If we determined we need to use a different thread_ts, fetch again
@@ -82,7 +74,7 @@ pub async fn get_thread(
                             i,
                             msg.ts,
                             msg.thread_ts,
-                            &msg.text.chars().take(50).collect::<String>()
+                            preview(&msg.text, 50)
                         );
                     }
                 } else {
@@ -105,7 +97,7 @@ pub async fn get_thread(
                     i,
                     msg.ts,
                     msg.thread_ts,
-                    &msg.text.chars().take(50).collect::<String>()
+                    preview(&msg.text, 50)
                 );
             }
         } else {
@@ -113,6 +105,14 @@ pub async fn get_thread(
         }
         initial_response
     };
+
+    // Slack sometimes hands back only the parent even though it reports replies
+    let (response, replies_note) = retry_if_parent_only(response, |parent_ts| {
+        let client = client.clone();
+        let channel_id = channel_id.clone();
+        async move { client.get_thread(&channel_id, &parent_ts).await }
+    })
+    .await;
     
     // Special case: Check if we still need a synthetic parent
     // This happens when the parent message is deleted or inaccessible
@@ -153,11 +153,12 @@ pub async fn get_thread(
     let user_cache_simple = state.get_user_cache().await;
     let mut channel_cache = state.get_channel_cache().await;
 
-    // If channel name is not in cache, try to fetch it (but don't fail if it doesn't work)
-    if !channel_cache.contains_key(&channel_id) {
-        match client.get_channel_info(&channel_id).await {
-            Ok(channel_info) => {
-                if let Some(name) = channel_info.name {
+    // Channel info says whether this is Slack Connect, which replies don't carry,
+    // and names the channel if it isn't cached (but don't fail if it doesn't work)
+    let is_shared_channel = match client.get_channel_info(&channel_id).await {
+        Ok(channel_info) => {
+            if !channel_cache.contains_key(&channel_id) {
+                if let Some(name) = channel_info.name.clone() {
                     // Determine if this is a DM or Group DM based on channel info
                     let is_im = channel_info.is_im.unwrap_or(false);
                     let is_mpim = channel_info.is_mpim.unwrap_or(false);
@@ -165,13 +166,15 @@ pub async fn get_thread(
                     channel_cache.insert(channel_id.clone(), name);
                 }
             }
-            Err(e) => {
-                debug!("Could not fetch channel info for {}: {}", channel_id, e);
-                // Use channel ID as fallback name
-                channel_cache.insert(channel_id.clone(), channel_id.clone());
-            }
+            channel_info.is_slack_connect()
         }
-    }
+        Err(e) => {
+            debug!("Could not fetch channel info for {}: {}", channel_id, e);
+            // Use channel ID as fallback name
+            channel_cache.entry(channel_id.clone()).or_insert_with(|| channel_id.clone());
+            false
+        }
+    };
 
     // Collect unique user IDs that need fetching
     let mut users_to_fetch = Vec::new();
@@ -253,6 +256,10 @@ pub async fn get_thread(
                 latest_reply: None,
                 reactions: None,
                 files: None,
+                metadata: None,
+                pinned_to: None,
+                is_starred: None,
+                blocks: None,
             };
             // Insert at the beginning
             messages.insert(0, synthetic_parent);
@@ -260,6 +267,8 @@ pub async fn get_thread(
     }
     
     // Convert messages to our format
+    let team_domain = state.team_domain().await;
+    let reactor_cap = state.reactor_cap().await;
     let mut converted_messages = Vec::new();
 
     for msg in messages {
@@ -290,15 +299,17 @@ pub async fn get_thread(
             .cloned()
             .unwrap_or_else(|| channel_id.clone());
 
-        // Build permalink (approximate, as we don't have workspace info)
-        let permalink = format!(
-            "https://slack.com/archives/{}/p{}",
-            channel_id,
-            msg.ts.replace('.', "")
+        let permalink = build_slack_url(
+            team_domain.as_deref(),
+            &channel_id,
+            &msg.ts,
+            msg.thread_ts.as_deref(),
         );
 
         // Replace user mentions in the text
-        let processed_text = replace_user_mentions(&msg.text, &user_cache_full);
+        let processed_text = state
+            .resolve_subteam_mentions(replace_user_mentions(&msg.text, &user_cache_full))
+            .await;
 
         converted_messages.push(Message {
             ts: msg.ts.clone(),
@@ -313,9 +324,16 @@ pub async fn get_thread(
             channel_name: channel_name.clone(),
             permalink,
             is_thread_parent: msg.reply_count.unwrap_or(0) > 0,
+            is_thread_reply: msg.is_thread_reply(),
             reply_count: msg.reply_count,
-            reactions: msg.reactions.clone(),
+            reactions: capped_reactions(msg.reactions.clone(), reactor_cap),
             files: msg.files.clone(),
+            highlights: None,
+            metadata: msg.metadata.clone(),
+            is_pinned: msg.is_pinned(),
+            is_saved: msg.is_saved(),
+            is_shared_channel,
+            block_urls: msg.block_urls(),
         });
     }
 
@@ -361,18 +379,171 @@ pub async fn get_thread(
                 text: "Thread not found".to_string(),
                 channel: channel_id.clone(),
                 channel_name: channel_cache.get(&channel_id).cloned().unwrap_or_else(|| channel_id.clone()),
-                permalink: format!("https://slack.com/archives/{}/p{}", channel_id, thread_ts.replace('.', "")),
+                permalink: build_slack_url(team_domain.as_deref(), &channel_id, &thread_ts, None),
                 is_thread_parent: false,
+                is_thread_reply: false,
                 reply_count: Some(0),
                 reactions: None,
                 files: None,
+                highlights: None,
+                metadata: None,
+                is_pinned: false,
+                is_saved: false,
+                is_shared_channel,
+                block_urls: Vec::new(),
             }
         }
     });
 
     info!("Thread retrieved: parent ts={}, {} replies", parent.ts, replies.len());
 
-    Ok(ThreadMessages { parent, replies })
+    Ok(ThreadMessages {
+        parent,
+        replies,
+        note: replies_note,
+    })
+}
+
+const REPLIES_UNAVAILABLE_NOTE: &str =
+    "Replies unavailable: Slack reports replies for this thread but returned none (permissions?)";
+
+/// True when the response holds only a parent whose `reply_count` says otherwise
+fn is_parent_only(response: &SlackConversationsRepliesResponse) -> bool {
+    match response.messages.as_deref() {
+        Some([only]) => {
+            let context = thread_context_from_message(only);
+            context.is_parent && context.reply_count > 0
+        }
+        _ => false,
+    }
+}
+
+/// Retry a parent-only thread response once, returning a note if replies are still missing
+///
+/// `fetch` gets the parent ts. A failed retry keeps the original response.
+async fn retry_if_parent_only<F, Fut>(
+    response: SlackConversationsRepliesResponse,
+    fetch: F,
+) -> (SlackConversationsRepliesResponse, Option<String>)
+where
+    F: FnOnce(String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<SlackConversationsRepliesResponse>>,
+{
+    if !is_parent_only(&response) {
+        return (response, None);
+    }
+
+    let parent_ts = response
+        .messages
+        .as_ref()
+        .and_then(|messages| messages.first())
+        .map(|parent| parent.ts.clone())
+        .unwrap_or_default();
+    warn!("Thread {} returned only its parent despite reply_count > 0, retrying once", parent_ts);
+
+    match fetch(parent_ts).await {
+        Ok(retried) if !is_parent_only(&retried) => (retried, None),
+        Ok(retried) => (retried, Some(REPLIES_UNAVAILABLE_NOTE.to_string())),
+        Err(e) => {
+            warn!("Retry for parent-only thread failed: {}", e);
+            (response, Some(REPLIES_UNAVAILABLE_NOTE.to_string()))
+        }
+    }
+}
+
+/// Where a message sits in its thread, used to decide whether a search
+/// result should open the thread view
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThreadContext {
+    /// Parent ts of the thread, or None if the message isn't threaded
+    pub thread_ts: Option<String>,
+    pub is_parent: bool,
+    pub reply_count: usize,
+}
+
+fn thread_context_from_message(msg: &SlackReplyMessage) -> ThreadContext {
+    match msg.thread_ts {
+        Some(ref thread_ts) if thread_ts != &msg.ts => ThreadContext {
+            // A reply: the thread belongs to its parent
+            thread_ts: Some(thread_ts.clone()),
+            is_parent: false,
+            reply_count: 0,
+        },
+        Some(ref thread_ts) => ThreadContext {
+            thread_ts: Some(thread_ts.clone()),
+            is_parent: true,
+            reply_count: msg.reply_count.unwrap_or(0),
+        },
+        None => ThreadContext {
+            thread_ts: None,
+            is_parent: false,
+            reply_count: 0,
+        },
+    }
+}
+
+/// The ts that opens the message's thread: the parent for a reply, otherwise its own ts
+fn thread_root_ts(msg: &SlackReplyMessage) -> String {
+    thread_context_from_message(msg)
+        .thread_ts
+        .unwrap_or_else(|| msg.ts.clone())
+}
+
+/// Canonical thread ts for any message, so clicking a parent or any reply opens the same thread
+#[tauri::command]
+pub async fn find_thread_for_message(
+    channel: String,
+    ts: String,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    let client = state.get_client().await?;
+
+    match client.get_single_message(&channel, &ts).await {
+        Ok(Some(msg)) => {
+            let root_ts = thread_root_ts(&msg);
+            debug!("Thread for {}:{} -> {}", channel, ts, root_ts);
+            Ok(root_ts)
+        }
+        Ok(None) => Err(crate::error::AppError::ApiError(format!(
+            "Message {} not found in channel {}",
+            ts, channel
+        ))),
+        Err(e) => {
+            error!("Failed to find thread for {}:{}: {}", channel, ts, e);
+            Err(crate::error::AppError::ApiError(format!(
+                "Failed to find thread: {}",
+                e
+            )))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn resolve_thread_context(
+    channel: String,
+    ts: String,
+    state: State<'_, AppState>,
+) -> AppResult<ThreadContext> {
+    let client = state.get_client().await?;
+
+    match client.get_single_message(&channel, &ts).await {
+        Ok(Some(msg)) => {
+            let context = thread_context_from_message(&msg);
+            debug!("Resolved thread context for {}:{} -> {:?}", channel, ts, context);
+            Ok(context)
+        }
+        Ok(None) => Err(crate::error::AppError::ApiError(format!(
+            "Message {} not found in channel {}",
+            ts, channel
+        ))),
+        Err(e) => {
+            error!("Failed to resolve thread context for {}:{}: {}", channel, ts, e);
+            Err(crate::error::AppError::ApiError(format!(
+                "Failed to resolve thread context: {}",
+                e
+            )))
+        }
+    }
 }
 
 #[tauri::command]
@@ -447,6 +618,225 @@ pub async fn get_thread_from_url(
     }
 }
 
+/// How many linked messages are fetched at once
+const URL_FETCH_CONCURRENCY: usize = 8;
+
+/// One entry of `get_messages_from_urls`, in the same position as its URL
+#[derive(Debug, Clone, Serialize)]
+pub struct UrlMessage {
+    pub url: String,
+    pub message: Option<Message>,
+    pub error: Option<String>,
+}
+
+/// Parse each URL and fetch its message, keeping input order
+///
+/// A URL that doesn't parse, fails to fetch, or points at a missing message
+/// yields an error string in its slot instead of failing the whole batch.
+async fn fetch_url_messages<F, Fut>(
+    urls: &[String],
+    fetch: F,
+) -> Vec<Result<(ParsedUrl, SlackReplyMessage), String>>
+where
+    F: Fn(ParsedUrl) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<Option<SlackReplyMessage>>>,
+{
+    use futures::stream::{self, StreamExt};
+
+    stream::iter(urls.to_vec())
+        .map(|url| {
+            let parsed = parse_slack_url(&url).map_err(|e| format!("Invalid Slack URL format: {}", e));
+            let request = parsed.clone().map(&fetch);
+            async move {
+                let (parsed, request) = (parsed?, request?);
+                match request.await {
+                    Ok(Some(msg)) => Ok((parsed, msg)),
+                    Ok(None) => Err(format!(
+                        "Message {} not found in channel {}",
+                        parsed.message_ts, parsed.channel_id
+                    )),
+                    Err(e) => Err(format!("Failed to fetch message: {}", e)),
+                }
+            }
+        })
+        .buffered(URL_FETCH_CONCURRENCY)
+        .collect()
+        .await
+}
+
+#[tauri::command]
+pub async fn get_messages_from_urls(
+    urls: Vec<String>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<UrlMessage>> {
+    info!("Getting messages for {} URLs", urls.len());
+    let client = state.get_client().await?;
+
+    let fetched = fetch_url_messages(&urls, |parsed| {
+        let client = client.clone();
+        async move {
+            client
+                .get_single_message(&parsed.channel_id, &parsed.message_ts)
+                .await
+        }
+    })
+    .await;
+
+    // Resolve each channel and author once, however many links point at them
+    let mut channel_cache = state.get_channel_cache().await;
+    let user_cache = state.get_user_cache().await;
+    let mut channels_to_fetch = Vec::new();
+    let mut users_to_fetch = Vec::new();
+    for (parsed, msg) in fetched.iter().flatten() {
+        // Fetched even when the name is cached, to tell Slack Connect channels apart
+        if !channels_to_fetch.contains(&parsed.channel_id) {
+            channels_to_fetch.push(parsed.channel_id.clone());
+        }
+        if let Some(user_id) = &msg.user {
+            if !user_cache.contains_key(user_id) && !users_to_fetch.contains(user_id) {
+                users_to_fetch.push(user_id.clone());
+            }
+        }
+    }
+
+    use futures::future::join_all;
+    let channel_results = join_all(channels_to_fetch.into_iter().map(|channel_id| {
+        let client = client.clone();
+        async move {
+            let info = client.get_channel_info(&channel_id).await;
+            (channel_id, info)
+        }
+    }))
+    .await;
+    let mut shared_channels = HashSet::new();
+    for (channel_id, info) in channel_results {
+        match info {
+            Ok(info) => {
+                if info.is_slack_connect() {
+                    shared_channels.insert(channel_id.clone());
+                }
+                if let Some(name) = info.name.filter(|_| !channel_cache.contains_key(&channel_id)) {
+                    let is_im = info.is_im.unwrap_or(false);
+                    let is_mpim = info.is_mpim.unwrap_or(false);
+                    state.cache_channel(channel_id.clone(), name.clone(), is_im, is_mpim).await;
+                    channel_cache.insert(channel_id, name);
+                }
+            }
+            Err(e) => debug!("Could not fetch channel info for {}: {}", channel_id, e),
+        }
+    }
+
+    let user_results = join_all(users_to_fetch.into_iter().map(|user_id| {
+        let client = client.clone();
+        async move {
+            let info = client.get_user_info(&user_id).await;
+            (user_id, info)
+        }
+    }))
+    .await;
+    for (user_id, info) in user_results {
+        match info {
+            Ok(user_info) => {
+                let name = user_info
+                    .profile
+                    .as_ref()
+                    .and_then(|p| p.display_name.clone().filter(|s| !s.is_empty()))
+                    .or_else(|| {
+                        user_info
+                            .profile
+                            .as_ref()
+                            .and_then(|p| p.real_name.clone().filter(|s| !s.is_empty()))
+                    })
+                    .or_else(|| user_info.real_name.clone().filter(|s| !s.is_empty()))
+                    .unwrap_or_else(|| user_info.name.clone());
+                state.cache_user(user_id, name, None).await;
+            }
+            Err(e) => error!("Failed to get user info for {}: {}", user_id, e),
+        }
+    }
+
+    let user_cache = state.get_user_cache().await;
+    let user_cache_full = state.get_user_cache_full().await;
+    let team_domain = state.team_domain().await;
+    let reactor_cap = state.reactor_cap().await;
+
+    let mut results = Vec::with_capacity(urls.len());
+    for (url, fetched) in urls.into_iter().zip(fetched) {
+        let (parsed, msg) = match fetched {
+            Ok(found) => found,
+            Err(e) => {
+                warn!("Could not load message for '{}': {}", url, e);
+                results.push(UrlMessage {
+                    url,
+                    message: None,
+                    error: Some(e),
+                });
+                continue;
+            }
+        };
+
+        let channel_id = parsed.channel_id;
+        let user_name = if let Some(user_id) = &msg.user {
+            user_cache.get(user_id).cloned().unwrap_or_else(|| user_id.clone())
+        } else if let Some(bot_profile) = &msg.bot_profile {
+            bot_profile.name.clone().unwrap_or_else(|| {
+                msg.username.clone().unwrap_or_else(|| "Unknown".to_string())
+            })
+        } else {
+            msg.username.clone().unwrap_or_else(|| "Unknown".to_string())
+        };
+        let text = state
+            .resolve_subteam_mentions(replace_user_mentions(&msg.text, &user_cache_full))
+            .await;
+
+        let is_shared_channel = shared_channels.contains(&channel_id);
+        let message = Message {
+            ts: msg.ts.clone(),
+            thread_ts: msg.thread_ts.clone(),
+            user: msg.user.clone()
+                .or_else(|| msg.bot_id.clone())
+                .or_else(|| msg.username.clone())
+                .unwrap_or_default(),
+            user_name,
+            text,
+            channel_name: channel_cache
+                .get(&channel_id)
+                .cloned()
+                .unwrap_or_else(|| channel_id.clone()),
+            permalink: build_slack_url(
+                team_domain.as_deref(),
+                &channel_id,
+                &msg.ts,
+                msg.thread_ts.as_deref(),
+            ),
+            channel: channel_id,
+            is_thread_parent: msg.reply_count.unwrap_or(0) > 0,
+            is_thread_reply: msg.is_thread_reply(),
+            reply_count: msg.reply_count,
+            reactions: capped_reactions(msg.reactions.clone(), reactor_cap),
+            files: msg.files.clone(),
+            highlights: None,
+            metadata: msg.metadata.clone(),
+            is_pinned: msg.is_pinned(),
+            is_saved: msg.is_saved(),
+            is_shared_channel,
+            block_urls: msg.block_urls(),
+        };
+        results.push(UrlMessage {
+            url,
+            message: Some(message),
+            error: None,
+        });
+    }
+
+    info!(
+        "Loaded {} of {} linked messages",
+        results.iter().filter(|r| r.message.is_some()).count(),
+        results.len()
+    );
+    Ok(results)
+}
+
 #[tauri::command]
 pub async fn open_in_slack(permalink: String) -> AppResult<()> {
     debug!("Opening in Slack: {}", permalink);
@@ -480,3 +870,326 @@ pub async fn open_in_slack(permalink: String) -> AppResult<()> {
 
     Ok(())
 }
+
+/// A thread the user follows, for the "my threads" view
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FollowedThread {
+    pub channel: String,
+    pub thread_ts: String,
+    pub user: Option<String>,
+    /// Short preview of the parent message
+    pub text: String,
+    pub reply_count: usize,
+    /// ts of the newest reply, or of the parent when nobody has replied
+    pub last_activity_ts: String,
+    pub unread_count: usize,
+}
+
+/// Followed threads, or `available: false` when the token can't read the Threads view
+#[derive(Debug, Clone, Serialize)]
+pub struct FollowedThreads {
+    pub available: bool,
+    pub threads: Vec<FollowedThread>,
+    /// Why the view is unavailable, when it is
+    pub reason: Option<String>,
+}
+
+/// Turn subscriptions.thread.getView entries into references, most recent activity first
+fn followed_thread_refs(threads: Vec<SlackFollowedThread>) -> Vec<FollowedThread> {
+    let ts_value = |ts: &str| ts.parse::<f64>().unwrap_or(0.0);
+    let mut refs: Vec<FollowedThread> = threads
+        .into_iter()
+        .map(|thread| {
+            let root = thread.root_msg;
+            FollowedThread {
+                last_activity_ts: root.latest_reply.clone().unwrap_or_else(|| root.ts.clone()),
+                thread_ts: root.thread_ts.unwrap_or(root.ts),
+                channel: root.channel,
+                user: root.user,
+                text: preview(&root.text, 100),
+                reply_count: root.reply_count.unwrap_or(0),
+                unread_count: thread.unread_replies.len(),
+            }
+        })
+        .collect();
+    refs.sort_by(|a, b| {
+        ts_value(&b.last_activity_ts)
+            .partial_cmp(&ts_value(&a.last_activity_ts))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    refs
+}
+
+/// Threads the user follows, with last activity and unread reply counts
+///
+/// subscriptions.thread.getView is undocumented; when the token can't call it
+/// (missing scope, wrong token type) an empty, unavailable view is returned
+/// instead of an error.
+#[tauri::command]
+pub async fn list_followed_threads(state: State<'_, AppState>) -> AppResult<FollowedThreads> {
+    let client = state.get_client().await?;
+
+    match client.get_followed_threads().await {
+        Ok(threads) => Ok(FollowedThreads {
+            available: true,
+            threads: followed_thread_refs(threads),
+            reason: None,
+        }),
+        Err(e) => {
+            let err = crate::error::AppError::from(e);
+            match err {
+                crate::error::AppError::ApiError(_) | crate::error::AppError::MissingScope { .. } => {
+                    warn!("Followed threads unavailable: {}", err);
+                    Ok(FollowedThreads {
+                        available: false,
+                        threads: Vec::new(),
+                        reason: Some(err.to_string()),
+                    })
+                }
+                other => Err(other),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply_message(json: serde_json::Value) -> SlackReplyMessage {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_reply_resolves_to_parent_thread_ts() {
+        let msg = reply_message(serde_json::json!({
+            "ts": "1700000100.000200",
+            "thread_ts": "1700000000.000100",
+            "user": "U1",
+            "text": "a reply"
+        }));
+
+        let context = thread_context_from_message(&msg);
+        assert_eq!(context.thread_ts.as_deref(), Some("1700000000.000100"));
+        assert!(!context.is_parent);
+    }
+
+    #[test]
+    fn test_reply_message_keeps_metadata() {
+        let msg = reply_message(serde_json::json!({
+            "ts": "1700000000.000100",
+            "text": "deploy finished",
+            "bot_id": "B1",
+            "metadata": {
+                "event_type": "deploy_finished",
+                "event_payload": { "service": "api", "ok": true }
+            }
+        }));
+
+        let metadata = msg.metadata.clone().unwrap();
+        assert_eq!(metadata["event_type"], "deploy_finished");
+
+        let message = Message {
+            ts: msg.ts.clone(),
+            thread_ts: None,
+            user: String::new(),
+            user_name: String::new(),
+            text: msg.text.clone(),
+            channel: "C1".to_string(),
+            channel_name: "general".to_string(),
+            permalink: String::new(),
+            is_thread_parent: false,
+            is_thread_reply: false,
+            reply_count: None,
+            reactions: None,
+            files: None,
+            highlights: None,
+            metadata: msg.metadata.clone(),
+            is_pinned: false,
+            is_saved: false,
+            is_shared_channel: false,
+            block_urls: Vec::new(),
+        };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["metadata"]["event_payload"]["service"], "api");
+    }
+
+    #[test]
+    fn test_parent_and_unthreaded_messages() {
+        let parent = reply_message(serde_json::json!({
+            "ts": "1700000000.000100",
+            "thread_ts": "1700000000.000100",
+            "text": "parent",
+            "reply_count": 4
+        }));
+        let context = thread_context_from_message(&parent);
+        assert!(context.is_parent);
+        assert_eq!(context.reply_count, 4);
+
+        let standalone = reply_message(serde_json::json!({
+            "ts": "1700000000.000100",
+            "text": "no thread"
+        }));
+        assert_eq!(thread_context_from_message(&standalone).thread_ts, None);
+    }
+
+    #[test]
+    fn test_thread_root_ts_for_parent_reply_and_standalone() {
+        let parent = reply_message(serde_json::json!({
+            "ts": "1700000000.000100",
+            "thread_ts": "1700000000.000100",
+            "text": "parent",
+            "reply_count": 2
+        }));
+        assert_eq!(thread_root_ts(&parent), "1700000000.000100");
+
+        let reply = reply_message(serde_json::json!({
+            "ts": "1700000100.000200",
+            "thread_ts": "1700000000.000100",
+            "text": "a reply"
+        }));
+        assert_eq!(thread_root_ts(&reply), "1700000000.000100");
+
+        let standalone = reply_message(serde_json::json!({
+            "ts": "1700000200.000300",
+            "text": "no thread"
+        }));
+        assert_eq!(thread_root_ts(&standalone), "1700000200.000300");
+    }
+
+    fn replies_response(json: serde_json::Value) -> SlackConversationsRepliesResponse {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn parent_only_response() -> SlackConversationsRepliesResponse {
+        replies_response(serde_json::json!({
+            "ok": true,
+            "messages": [{
+                "ts": "1700000000.000100",
+                "thread_ts": "1700000000.000100",
+                "text": "parent",
+                "reply_count": 2
+            }]
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_parent_only_thread_is_retried() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = AtomicUsize::new(0);
+        let (response, note) = retry_if_parent_only(parent_only_response(), |parent_ts| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(parent_ts, "1700000000.000100");
+            async {
+                Ok(replies_response(serde_json::json!({
+                    "ok": true,
+                    "messages": [
+                        { "ts": "1700000000.000100", "thread_ts": "1700000000.000100", "text": "parent", "reply_count": 2 },
+                        { "ts": "1700000100.000200", "thread_ts": "1700000000.000100", "text": "one" },
+                        { "ts": "1700000200.000300", "thread_ts": "1700000000.000100", "text": "two" }
+                    ]
+                })))
+            }
+        })
+        .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(response.messages.unwrap().len(), 3);
+        assert!(note.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_url_messages_keep_input_order() {
+        let urls = vec![
+            "https://example.slack.com/archives/C111/p1700000000000100".to_string(),
+            "not a url".to_string(),
+            "https://example.slack.com/archives/C222/p1700000000000200".to_string(),
+            "https://example.slack.com/archives/C333/p1700000000000300".to_string(),
+        ];
+
+        let results = fetch_url_messages(&urls, |parsed| async move {
+            match parsed.channel_id.as_str() {
+                "C222" => Err(anyhow::anyhow!("channel_not_found")),
+                "C333" => Ok(None),
+                _ => Ok(Some(reply_message(serde_json::json!({
+                    "ts": parsed.message_ts,
+                    "text": "linked"
+                })))),
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 4);
+        let (parsed, msg) = results[0].as_ref().unwrap();
+        assert_eq!(parsed.channel_id, "C111");
+        assert_eq!(msg.ts, "1700000000.000100");
+        assert!(results[1].as_ref().unwrap_err().starts_with("Invalid Slack URL format"));
+        assert!(results[2].as_ref().unwrap_err().contains("channel_not_found"));
+        assert!(results[3].as_ref().unwrap_err().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_persistently_empty_thread_gets_note() {
+        let (response, note) =
+            retry_if_parent_only(parent_only_response(), |_| async { Ok(parent_only_response()) }).await;
+
+        assert_eq!(response.messages.unwrap().len(), 1);
+        assert_eq!(note.as_deref(), Some(REPLIES_UNAVAILABLE_NOTE));
+
+        // A thread that really has no replies is left alone
+        let standalone = replies_response(serde_json::json!({
+            "ok": true,
+            "messages": [{ "ts": "1700000000.000100", "text": "no thread" }]
+        }));
+        assert!(!is_parent_only(&standalone));
+    }
+
+    #[test]
+    fn test_followed_threads_parse_into_references() {
+        let threads: Vec<SlackFollowedThread> = serde_json::from_value(serde_json::json!([
+            {
+                "root_msg": {
+                    "ts": "1700000000.000100", "thread_ts": "1700000000.000100", "channel": "C1",
+                    "user": "U1", "text": "release checklist", "reply_count": 4,
+                    "latest_reply": "1700000300.000100", "last_read": "1700000200.000100"
+                },
+                "unread_replies": [
+                    { "ts": "1700000300.000100", "thread_ts": "1700000000.000100", "text": "done" }
+                ]
+            },
+            {
+                "root_msg": {
+                    "ts": "1700000500.000100", "channel": "C2", "user": "U2",
+                    "text": "quiet thread"
+                }
+            }
+        ]))
+        .unwrap();
+
+        let refs = followed_thread_refs(threads);
+        assert_eq!(
+            refs,
+            vec![
+                FollowedThread {
+                    channel: "C2".to_string(),
+                    thread_ts: "1700000500.000100".to_string(),
+                    user: Some("U2".to_string()),
+                    text: "quiet thread".to_string(),
+                    reply_count: 0,
+                    last_activity_ts: "1700000500.000100".to_string(),
+                    unread_count: 0,
+                },
+                FollowedThread {
+                    channel: "C1".to_string(),
+                    thread_ts: "1700000000.000100".to_string(),
+                    user: Some("U1".to_string()),
+                    text: "release checklist".to_string(),
+                    reply_count: 4,
+                    last_activity_ts: "1700000300.000100".to_string(),
+                    unread_count: 1,
+                },
+            ]
+        );
+    }
+}
@@ -1,5 +1,8 @@
+use crate::slack::parser::convert_message_id_to_ts;
+use crate::slack::ParsedUrl;
 use serde::{Deserialize, Serialize};
 use tokio::time::{sleep, Duration};
+use url::Url;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenUrlsResult {
@@ -22,6 +25,13 @@ pub async fn open_urls_smart(
 
     let delay = Duration::from_millis(delay_ms.unwrap_or(200));
 
+    // Text links and block (button) URLs often overlap; open each once
+    let mut seen = std::collections::HashSet::new();
+    let external_urls: Vec<String> = external_urls
+        .into_iter()
+        .filter(|url| Some(url) != slack_url.as_ref() && seen.insert(url.clone()))
+        .collect();
+
     // Open Slack URL first if provided
     if let Some(url) = slack_url {
         match open_url(&url) {
@@ -66,3 +76,205 @@ fn open_url(url: &str) -> Result<(), String> {
         Err(e) => Err(format!("Failed to open browser: {}", e)),
     }
 }
+
+/// Expand a pasted Slack link into channel/ts without touching the network
+#[tauri::command]
+pub async fn expand_archive_link(url: String) -> Result<ParsedUrl, String> {
+    parse_archive_link(&url)
+}
+
+/// Parse any of the Slack link shapes users paste into a `ParsedUrl`
+///
+/// Supported shapes:
+/// - `https://ws.slack.com/archives/C123/p1234567890123456`, optionally with
+///   `thread_ts` and `cid` query params
+/// - Enterprise Grid hosts and team path segments, e.g.
+///   `https://org.enterprise.slack.com/T123/archives/C123/p...`
+/// - Web client links: `https://app.slack.com/client/T123/C123[/thread/C123-1234567890.123456]`
+/// - Deep links: `slack://channel?team=T123&id=C123[&message=1234567890.123456]`
+///
+/// Channel-only links yield an empty `message_ts`.
+pub fn parse_archive_link(url_str: &str) -> Result<ParsedUrl, String> {
+    let url = Url::parse(url_str.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let parsed = if url.scheme() == "slack" {
+        if url.host_str() != Some("channel") {
+            return Err("Unsupported slack:// link".to_string());
+        }
+        ParsedUrl {
+            channel_id: query("id").ok_or("Deep link is missing the channel id")?,
+            message_ts: query("message").unwrap_or_default(),
+            thread_ts: query("thread_ts"),
+            team_id: query("team"),
+        }
+    } else {
+        if !url.host_str().is_some_and(|h| h == "slack.com" || h.ends_with(".slack.com")) {
+            return Err("Not a valid Slack URL".to_string());
+        }
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        parse_web_segments(&segments, query("cid"), query("thread_ts"))?
+    };
+
+    if !is_channel_id(&parsed.channel_id) {
+        return Err(format!("Invalid channel ID: {}", parsed.channel_id));
+    }
+
+    Ok(parsed)
+}
+
+fn parse_web_segments(
+    segments: &[&str],
+    cid: Option<String>,
+    thread_ts: Option<String>,
+) -> Result<ParsedUrl, String> {
+    if let Some(pos) = segments.iter().position(|s| *s == "archives") {
+        // Anything before "archives" is a team/enterprise path segment
+        let team_id = segments[..pos]
+            .iter()
+            .rev()
+            .find(|s| is_team_id(s))
+            .map(|s| s.to_string());
+        let channel_id = segments
+            .get(pos + 1)
+            .map(|s| s.to_string())
+            .or(cid)
+            .ok_or("Archive link is missing the channel")?;
+        let message_ts = match segments.get(pos + 2) {
+            Some(id) => convert_message_id_to_ts(id).map_err(|e| e.to_string())?,
+            None => String::new(),
+        };
+        return Ok(ParsedUrl {
+            channel_id,
+            message_ts,
+            thread_ts,
+            team_id,
+        });
+    }
+
+    if segments.first() == Some(&"client") {
+        let team_id = segments.get(1).map(|s| s.to_string());
+        let channel_id = segments
+            .get(2)
+            .map(|s| s.to_string())
+            .or(cid)
+            .ok_or("Client link is missing the channel")?;
+
+        let (message_ts, thread_ts) = match (segments.get(3), segments.get(4)) {
+            // /client/T/C/thread/C-1234567890.123456
+            (Some(&"thread"), Some(thread)) => {
+                let ts = thread.rsplit_once('-').map(|(_, ts)| ts).unwrap_or(thread).to_string();
+                (ts.clone(), Some(ts))
+            }
+            (Some(id), _) if id.starts_with('p') => {
+                (convert_message_id_to_ts(id).map_err(|e| e.to_string())?, thread_ts)
+            }
+            _ => (String::new(), thread_ts),
+        };
+
+        return Ok(ParsedUrl {
+            channel_id,
+            message_ts,
+            thread_ts,
+            team_id,
+        });
+    }
+
+    Err("Unrecognized Slack link format".to_string())
+}
+
+fn is_channel_id(id: &str) -> bool {
+    id.len() > 1
+        && matches!(id.chars().next(), Some('C' | 'D' | 'G'))
+        && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn is_team_id(id: &str) -> bool {
+    id.len() > 1
+        && matches!(id.chars().next(), Some('T' | 'E'))
+        && id.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_archive_link_plain() {
+        let parsed =
+            parse_archive_link("https://acme.slack.com/archives/C0123ABCD/p1700000000123456").unwrap();
+        assert_eq!(parsed.channel_id, "C0123ABCD");
+        assert_eq!(parsed.message_ts, "1700000000.123456");
+        assert_eq!(parsed.thread_ts, None);
+        assert_eq!(parsed.team_id, None);
+    }
+
+    #[test]
+    fn test_parse_archive_link_thread_and_cid() {
+        let parsed = parse_archive_link(
+            "https://acme.slack.com/archives/C0123ABCD/p1700000100654321?thread_ts=1700000000.123456&cid=C0123ABCD",
+        )
+        .unwrap();
+        assert_eq!(parsed.channel_id, "C0123ABCD");
+        assert_eq!(parsed.message_ts, "1700000100.654321");
+        assert_eq!(parsed.thread_ts.as_deref(), Some("1700000000.123456"));
+    }
+
+    #[test]
+    fn test_parse_archive_link_enterprise_team_segment() {
+        let parsed = parse_archive_link(
+            "https://org.enterprise.slack.com/E01ABCDEF/archives/G0123ABCD/p1700000000123456",
+        )
+        .unwrap();
+        assert_eq!(parsed.channel_id, "G0123ABCD");
+        assert_eq!(parsed.message_ts, "1700000000.123456");
+        assert_eq!(parsed.team_id.as_deref(), Some("E01ABCDEF"));
+    }
+
+    #[test]
+    fn test_parse_archive_link_web_client() {
+        let parsed = parse_archive_link(
+            "https://app.slack.com/client/T01ABCDEF/C0123ABCD/thread/C0123ABCD-1700000000.123456",
+        )
+        .unwrap();
+        assert_eq!(parsed.channel_id, "C0123ABCD");
+        assert_eq!(parsed.message_ts, "1700000000.123456");
+        assert_eq!(parsed.thread_ts.as_deref(), Some("1700000000.123456"));
+        assert_eq!(parsed.team_id.as_deref(), Some("T01ABCDEF"));
+
+        let channel_only = parse_archive_link("https://app.slack.com/client/T01ABCDEF/D0123ABCD").unwrap();
+        assert_eq!(channel_only.channel_id, "D0123ABCD");
+        assert_eq!(channel_only.message_ts, "");
+    }
+
+    #[test]
+    fn test_parse_archive_link_deep_link() {
+        let parsed = parse_archive_link("slack://channel?team=T01ABCDEF&id=C0123ABCD").unwrap();
+        assert_eq!(parsed.channel_id, "C0123ABCD");
+        assert_eq!(parsed.message_ts, "");
+        assert_eq!(parsed.team_id.as_deref(), Some("T01ABCDEF"));
+
+        let with_message =
+            parse_archive_link("slack://channel?team=T01ABCDEF&id=C0123ABCD&message=1700000000.123456")
+                .unwrap();
+        assert_eq!(with_message.message_ts, "1700000000.123456");
+    }
+
+    #[test]
+    fn test_parse_archive_link_rejects_other_links() {
+        assert!(parse_archive_link("https://example.com/archives/C0123ABCD/p1700000000123456").is_err());
+        assert!(parse_archive_link("https://notslack.com/archives/C0123ABCD/p1700000000123456").is_err());
+        assert!(parse_archive_link("slack://user?team=T01ABCDEF&id=U0123").is_err());
+        assert!(parse_archive_link("https://acme.slack.com/archives/X0123/p1700000000123456").is_err());
+    }
+}
@@ -23,16 +23,255 @@ pub enum AppError {
 
     #[error("Unknown error: {0}")]
     Unknown(String),
+
+    #[error("Missing permission: {method} requires the {required_scope} scope. Add {required_scope} to your Slack app's User Token Scopes and reinstall it.")]
+    MissingScope {
+        method: String,
+        required_scope: String,
+    },
+}
+
+/// A Slack `ok: false` error code, classified so callers can `match` on it
+///
+/// The client returns these (inside anyhow) for failed Slack responses;
+/// commands turn them into the matching `AppError` for the UI.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SlackApiError {
+    #[error("Missing permission: {method} requires the {required_scope} scope (missing_scope)")]
+    MissingScope {
+        method: String,
+        required_scope: String,
+    },
+
+    #[error("Rate limited by Slack (ratelimited)")]
+    RateLimited,
+
+    #[error("You don't have access to this channel (not_in_channel)")]
+    NotInChannel,
+
+    #[error("Invalid authentication token (invalid_auth). Please check your Slack token in Settings.")]
+    InvalidAuth,
+
+    #[error("Channel not found (channel_not_found)")]
+    ChannelNotFound,
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl SlackApiError {
+    /// Classify the `error` field of a failed response from Slack `method`
+    pub fn from_code(method: &str, code: &str) -> Self {
+        match code {
+            "missing_scope" => Self::missing_scope(method),
+            "ratelimited" => SlackApiError::RateLimited,
+            "not_in_channel" => SlackApiError::NotInChannel,
+            "invalid_auth" => SlackApiError::InvalidAuth,
+            "channel_not_found" => SlackApiError::ChannelNotFound,
+            _ => SlackApiError::Other(code.to_string()),
+        }
+    }
+
+    /// Like `from_code`, but a `missing_scope` names the scope from the response
+    /// body's `needed` field when Slack sends one
+    pub fn from_response(method: &str, code: &str, body: &str) -> Self {
+        match needed_scope(body) {
+            Some(needed) if code == "missing_scope" => Self::missing_scope_for(method, &needed),
+            _ => Self::from_code(method, code),
+        }
+    }
+
+    /// `MissingScope` for `method`, using the known scope table like `AppError::missing_scope`
+    pub fn missing_scope(method: &str) -> Self {
+        Self::missing_scope_for(method, &scope_or_family_read(method))
+    }
+
+    /// `MissingScope` when the scope depends on the conversation type
+    pub fn missing_scope_for(method: &str, required_scope: &str) -> Self {
+        SlackApiError::MissingScope {
+            method: method.to_string(),
+            required_scope: required_scope.to_string(),
+        }
+    }
+
+    /// The Slack error behind a client error, if it was one
+    pub fn of(err: &anyhow::Error) -> Option<&SlackApiError> {
+        err.downcast_ref::<SlackApiError>()
+    }
+
+    /// Whether retrying the same request may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            SlackApiError::RateLimited => true,
+            SlackApiError::Other(code) => TRANSIENT_ERROR_CODES.contains(&code.as_str()),
+            _ => false,
+        }
+    }
+}
+
+impl From<SlackApiError> for AppError {
+    fn from(err: SlackApiError) -> Self {
+        match err {
+            SlackApiError::MissingScope { method, required_scope } => {
+                AppError::missing_scope_for(&method, &required_scope)
+            }
+            SlackApiError::InvalidAuth => AppError::AuthError(err.to_string()),
+            SlackApiError::RateLimited => AppError::NetworkError(err.to_string()),
+            SlackApiError::NotInChannel | SlackApiError::ChannelNotFound => AppError::ApiError(err.to_string()),
+            // Other auth and transient codes keep their existing classification
+            SlackApiError::Other(code) => AppError::slack_api(&code),
+        }
+    }
+}
+
+/// Known Slack API methods and the user token scope each one needs
+const METHOD_SCOPES: &[(&str, &str)] = &[
+    ("search.messages", "search:read"),
+    ("reactions.get", "reactions:read"),
+    ("reactions.add", "reactions:write"),
+    ("reactions.remove", "reactions:write"),
+    ("conversations.history", "channels:history"),
+    ("conversations.replies", "channels:history"),
+    ("conversations.list", "channels:read"),
+    ("conversations.info", "channels:read"),
+    ("conversations.join", "channels:write"),
+    ("users.list", "users:read"),
+    ("users.info", "users:read"),
+    ("users.conversations", "channels:read"),
+    ("conversations.members", "channels:read"),
+    ("usergroups.list", "usergroups:read"),
+    ("emoji.list", "emoji:read"),
+    ("stars.list", "stars:read"),
+    ("chat.postMessage", "chat:write"),
+    ("chat.getPermalink", "channels:history"),
+    ("files.info", "files:read"),
+];
+
+/// Slack error codes that mean the token itself is bad; the fix is a new token
+const AUTH_ERROR_CODES: &[&str] = &[
+    "invalid_auth",
+    "not_authed",
+    "token_revoked",
+    "token_expired",
+    "account_inactive",
+];
+
+/// Slack error codes for failures on Slack's side that a retry can get past
+const TRANSIENT_ERROR_CODES: &[&str] = &[
+    "ratelimited",
+    "service_unavailable",
+    "request_timeout",
+    "internal_error",
+    "fatal_error",
+];
+
+/// Scope Slack requires for `method`, if it is one we know about
+pub fn required_scope(method: &str) -> Option<&'static str> {
+    METHOD_SCOPES
+        .iter()
+        .find(|(m, _)| *m == method)
+        .map(|(_, scope)| *scope)
+}
+
+/// The `needed` field of a failed Slack response: the scope(s) Slack says are missing
+pub fn needed_scope(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value
+        .get("needed")?
+        .as_str()
+        .filter(|needed| !needed.is_empty())
+        .map(String::from)
+}
+
+/// Known scope for `method`, falling back to Slack's `<family>:read` naming
+fn scope_or_family_read(method: &str) -> String {
+    match required_scope(method) {
+        Some(scope) => scope.to_string(),
+        None => format!("{}:read", method.split('.').next().unwrap_or(method)),
+    }
+}
+
+impl AppError {
+    /// Build a `MissingScope` error for a Slack method, using the known scope table
+    ///
+    /// Unlisted methods fall back to Slack's `<family>:read` naming.
+    pub fn missing_scope(method: &str) -> Self {
+        Self::missing_scope_for(method, &scope_or_family_read(method))
+    }
+
+    /// Map a Slack `ok: false` error code to the variant the UI acts on
+    ///
+    /// Bad tokens become `AuthError`, transient Slack-side failures become
+    /// `NetworkError` (safe to retry), and everything else stays an `ApiError`.
+    pub fn slack_api(code: &str) -> Self {
+        if AUTH_ERROR_CODES.contains(&code) {
+            AppError::AuthError(format!("Slack rejected the token ({}). Please check your Slack token in Settings.", code))
+        } else if TRANSIENT_ERROR_CODES.contains(&code) {
+            AppError::NetworkError(format!("Slack is temporarily unavailable ({})", code))
+        } else {
+            AppError::ApiError(code.to_string())
+        }
+    }
+
+    /// Map a non-success HTTP status from Slack the same way
+    pub fn http_status(status: u16, body: &str) -> Self {
+        match status {
+            401 | 403 => AppError::AuthError(format!("Slack returned {}: {}", status, body)),
+            429 | 500..=599 => AppError::NetworkError(format!("Slack returned {}: {}", status, body)),
+            _ => AppError::ApiError(format!("{} - {}", status, body)),
+        }
+    }
+
+    /// Whether retrying the same request may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(self, AppError::NetworkError(_))
+    }
+
+    /// Build a `MissingScope` error when the scope depends on the conversation type
+    pub fn missing_scope_for(method: &str, required_scope: &str) -> Self {
+        AppError::MissingScope {
+            method: method.to_string(),
+            required_scope: required_scope.to_string(),
+        }
+    }
 }
 
 impl From<reqwest::Error> for AppError {
     fn from(err: reqwest::Error) -> Self {
-        AppError::NetworkError(err.to_string())
+        // A body that doesn't deserialize is a parse problem, not a connection one
+        if err.is_decode() {
+            AppError::ParseError(err.to_string())
+        } else {
+            AppError::NetworkError(err.to_string())
+        }
     }
 }
 
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
+        // Client code wraps typed errors (e.g. MissingScope) in anyhow; keep them intact
+        let err = match err.downcast::<AppError>() {
+            Ok(app_err) => return app_err,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<SlackApiError>() {
+            Ok(slack_err) => return slack_err.into(),
+            Err(err) => err,
+        };
+
+        // Otherwise classify by the underlying source, wherever `?` put it in the chain
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                return if e.is_decode() {
+                    AppError::ParseError(err.to_string())
+                } else {
+                    AppError::NetworkError(err.to_string())
+                };
+            }
+            if cause.is::<serde_json::Error>() {
+                return AppError::ParseError(err.to_string());
+            }
+        }
         AppError::Unknown(err.to_string())
     }
 }
@@ -56,3 +295,95 @@ impl From<tauri_plugin_store::Error> for AppError {
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_scope_names_required_scope() {
+        let err = AppError::missing_scope("reactions.get");
+        let message = err.to_string();
+        assert!(message.contains("reactions.get requires the reactions:read scope"));
+
+        // Survives the round trip through the client's anyhow errors
+        let converted: AppError = anyhow::Error::from(err).into();
+        match converted {
+            AppError::MissingScope { method, required_scope } => {
+                assert_eq!(method, "reactions.get");
+                assert_eq!(required_scope, "reactions:read");
+            }
+            other => panic!("expected MissingScope, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_sources_map_to_variants() {
+        // Nothing listens on port 1, so the connection is refused
+        let connect = reqwest::Client::new().get("http://127.0.0.1:1/").send().await.unwrap_err();
+        let err: AppError = anyhow::Error::from(connect).into();
+        assert!(matches!(err, AppError::NetworkError(_)), "got {:?}", err);
+        assert!(err.is_transient());
+
+        let json = serde_json::from_str::<serde_json::Value>("{\"ok\": tru").unwrap_err();
+        let err: AppError = anyhow::Error::from(json).context("parsing search.messages").into();
+        assert!(matches!(err, AppError::ParseError(_)), "got {:?}", err);
+        assert!(!err.is_transient());
+
+        // ok:false codes: a bad token, a transient failure, and a plain API error
+        let err: AppError = anyhow::Error::from(AppError::slack_api("invalid_auth")).into();
+        assert!(matches!(err, AppError::AuthError(_)), "got {:?}", err);
+        assert!(AppError::slack_api("ratelimited").is_transient());
+        match AppError::slack_api("channel_not_found") {
+            AppError::ApiError(code) => assert_eq!(code, "channel_not_found"),
+            other => panic!("expected ApiError, got {:?}", other),
+        }
+
+        assert!(matches!(AppError::http_status(503, ""), AppError::NetworkError(_)));
+        assert!(matches!(AppError::http_status(401, ""), AppError::AuthError(_)));
+        assert!(matches!(AppError::http_status(404, ""), AppError::ApiError(_)));
+    }
+
+    #[test]
+    fn test_slack_error_codes_are_classified() {
+        assert_eq!(SlackApiError::from_code("conversations.history", "not_in_channel"), SlackApiError::NotInChannel);
+        assert_eq!(SlackApiError::from_code("auth.test", "invalid_auth"), SlackApiError::InvalidAuth);
+        assert_eq!(
+            SlackApiError::from_code("reactions.get", "missing_scope"),
+            SlackApiError::missing_scope_for("reactions.get", "reactions:read")
+        );
+        assert_eq!(
+            SlackApiError::from_code("chat.postMessage", "is_archived"),
+            SlackApiError::Other("is_archived".to_string())
+        );
+        assert!(SlackApiError::RateLimited.is_transient());
+
+        // Slack's own `needed` wins over the table; without it the table is used
+        let body = r#"{"ok":false,"error":"missing_scope","needed":"groups:history","provided":"channels:history"}"#;
+        assert_eq!(
+            SlackApiError::from_response("conversations.history", "missing_scope", body),
+            SlackApiError::missing_scope_for("conversations.history", "groups:history")
+        );
+        assert_eq!(
+            SlackApiError::from_response("reactions.get", "missing_scope", r#"{"ok":false,"error":"missing_scope"}"#),
+            SlackApiError::missing_scope_for("reactions.get", "reactions:read")
+        );
+        assert_eq!(
+            SlackApiError::from_response("chat.update", "cant_update_message", body),
+            SlackApiError::Other("cant_update_message".to_string())
+        );
+        assert!(SlackApiError::from_code("chat.postMessage", "service_unavailable").is_transient());
+
+        // Matchable through the client's anyhow errors, and the UI sees the same variants as before
+        let err = anyhow::Error::from(SlackApiError::from_code("conversations.info", "channel_not_found"));
+        assert_eq!(SlackApiError::of(&err), Some(&SlackApiError::ChannelNotFound));
+        match AppError::from(err) {
+            AppError::ApiError(message) => assert!(message.contains("channel_not_found")),
+            other => panic!("expected ApiError, got {:?}", other),
+        }
+        let err: AppError = anyhow::Error::from(SlackApiError::from_code("users.list", "token_revoked")).into();
+        assert!(matches!(err, AppError::AuthError(_)), "got {:?}", err);
+        let err: AppError = anyhow::Error::from(SlackApiError::missing_scope_for("conversations.list", "im:read")).into();
+        assert!(matches!(err, AppError::MissingScope { ref required_scope, .. } if required_scope == "im:read"));
+    }
+}
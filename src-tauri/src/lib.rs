@@ -3,7 +3,7 @@ mod error;
 mod slack;
 mod state;
 
-use state::AppState;
+use state::{AppState, USER_CACHE_FILE};
 use tauri::Manager;
 use tracing_subscriber::fmt;
 use tracing_subscriber::layer::SubscriberExt;
@@ -40,6 +40,19 @@ pub fn run() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(AppState::new())
         .setup(|app| {
+            // Seed the user cache from the last run so the first search resolves names
+            match app.path().app_data_dir() {
+                Ok(dir) => {
+                    let state = app.state::<AppState>().inner().clone();
+                    state.set_user_cache_compression(commands::cache::compression_enabled(app.handle()));
+                    tauri::async_runtime::spawn(async move {
+                        let loaded = state.attach_user_cache_file(dir.join(USER_CACHE_FILE)).await;
+                        tracing::info!("Loaded {} users from the saved user cache", loaded);
+                    });
+                }
+                Err(e) => tracing::warn!("No app data dir, user cache won't be saved: {}", e),
+            }
+
             // Get the main window and maximize it on startup
             if let Some(window) = app.get_webview_window("main") {
                 // Enable devtools only in debug builds
@@ -69,40 +82,88 @@ pub fn run() {
             commands::auth::init_token_from_storage,
             commands::auth::migrate_tokens,
             commands::auth::get_current_user_id,
+            commands::auth::warm_caches,
+            commands::auth::clear_user_cache_disk,
             commands::channels::save_favorite_channels,
             commands::channels::get_favorite_channels,
+            commands::channels::save_low_reaction_channels,
+            commands::channels::get_low_reaction_channels,
             commands::channels::save_recent_channels,
             commands::channels::get_recent_channels,
             commands::channels::get_dm_channels,
+            commands::channels::get_channel_bookmarks,
             commands::channels::check_dm_permissions,
             commands::channels::search_dm_messages,
             commands::channels::get_unmuted_member_channels,
+            commands::channels::get_active_channels,
+            commands::channels::set_channel_muted,
+            commands::channels::validate_channel,
+            commands::channels::get_channel_oldest_ts,
+            commands::channels::export_channel,
+            commands::channels::search_favorites,
+            commands::channels::get_channel_change_history,
+            commands::channels::get_channel_timeline,
+            commands::channels::set_local_read,
+            commands::channels::get_local_unread,
+            commands::channels::catch_up,
+            commands::channels::build_digest,
             commands::emoji::get_emoji_list,
+            commands::emoji::channel_emoji_usage,
+            commands::emoji::download_emoji,
+            commands::cache::get_cache_compression,
+            commands::cache::set_cache_compression,
             commands::post::post_to_channel,
             commands::post::post_thread_reply,
+            commands::post::update_posted_message,
+            commands::post::render_preview,
             commands::post::check_posting_permissions,
             commands::reactions::add_reaction,
             commands::reactions::remove_reaction,
             commands::reactions::get_reactions,
+            commands::reactions::set_reactor_cap,
+            commands::reactions::set_reaction_cache_ttl,
             commands::search::search_messages,
+            commands::search::count_matches,
+            commands::search::channel_frequency,
+            commands::search::get_recent_searches,
+            commands::search::clear_recent_searches,
             commands::search::search_messages_fast,
             commands::search::get_user_channels,
+            commands::search::get_user_channels_membership,
+            commands::search::find_file_messages,
             commands::search::get_users,
             commands::search::test_connection,
             commands::search::get_all_users,
             commands::search::get_user_info,
+            commands::search::get_user_avatar,
+            commands::search::get_users_batch,
+            commands::search::get_permalinks_batch,
+            commands::search::search_messages_summary,
+            commands::search::get_message_details,
             commands::search::batch_fetch_reactions,
             commands::search::fetch_reactions_progressive,
             commands::search::clear_reaction_cache,
+            commands::search::export_search_results,
             commands::debug::debug_user_info,
             commands::debug::debug_dm_channels,
             commands::debug::debug_missing_users,
             commands::debug::debug_problematic_users,
+            commands::debug::set_advanced_mode,
+            commands::debug::call_slack_method,
+            commands::debug::get_rate_limit_status,
+            commands::debug::set_max_rate_limit_retries,
+            commands::debug::set_client_config,
+            commands::debug::preview_search_query,
             commands::thread::get_thread,
             commands::thread::parse_slack_url_command,
             commands::thread::get_thread_from_url,
+            commands::thread::get_messages_from_urls,
+            commands::thread::resolve_thread_context,
+            commands::thread::find_thread_for_message,
+            commands::thread::list_followed_threads,
             commands::thread::open_in_slack,
             commands::url::open_urls_smart,
+            commands::url::expand_archive_link,
             commands::files::get_slack_file,
             commands::files::get_authenticated_file_url,
             commands::files::download_slack_file,
@@ -113,6 +174,10 @@ pub fn run() {
             commands::files::download_file_binary,
             commands::files::get_file_content,
             commands::mark::mark_message_as_read,
+            commands::pins::get_pinned_messages,
+            commands::pins::pin_message,
+            commands::pins::unpin_message,
+            commands::channels::mark_channel_read,
             commands::upload::upload_file_to_slack,
             commands::upload::upload_clipboard_image,
             commands::upload::get_file_info,
@@ -2,55 +2,194 @@ use anyhow::{anyhow, Result};
 use chrono;
 use futures;
 use reqwest::{header, Client};
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
 use super::models::*;
+use super::parser::{preview, text_matches, MatchMode};
+use super::rate_limit::RateLimitState;
+use crate::error::{needed_scope, AppError, SlackApiError};
 
 const SLACK_API_BASE: &str = "https://slack.com/api";
 const RATE_LIMIT_DELAY_MS: u64 = 20; // Further reduced for better performance
-const MAX_CONCURRENT_REQUESTS: usize = 30; // Massively increased for 400+ message performance
+pub(crate) const MAX_CONCURRENT_REQUESTS: usize = 30; // Massively increased for 400+ message performance
+/// Reactors kept per reaction unless configured otherwise; busy messages can have hundreds
+pub(crate) const DEFAULT_REACTOR_CAP: usize = 50;
+/// Rate-limit retries per request unless configured otherwise
+pub(crate) const DEFAULT_MAX_RATE_LIMIT_RETRIES: u32 = 3;
+const RATE_LIMIT_BACKOFF_MS: u64 = 1000; // Doubled on each retry unless Slack sends Retry-After
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Throughput settings for a `SlackClient`, tunable per workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlackClientConfig {
+    /// Requests allowed in flight at once
+    pub max_concurrent: usize,
+    /// Pause between paginated requests
+    pub delay_ms: u64,
+    /// Timeout for each HTTP request
+    pub timeout_secs: u64,
+}
+
+impl Default for SlackClientConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: MAX_CONCURRENT_REQUESTS,
+            delay_ms: RATE_LIMIT_DELAY_MS,
+            timeout_secs: REQUEST_TIMEOUT_SECS,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct SlackClient {
     pub client: Client,
     token: String,
     rate_limiter: Arc<tokio::sync::Semaphore>,
+    rate_limit_state: Arc<RateLimitState>,
+    config: SlackClientConfig,
+    reactor_cap: usize,
+    max_retries: u32,
 }
 
 impl SlackClient {
     pub fn new(token: String) -> Result<Self> {
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            header::AUTHORIZATION,
-            header::HeaderValue::from_str(&format!("Bearer {}", token))?,
-        );
-        headers.insert(
-            header::CONTENT_TYPE,
-            header::HeaderValue::from_static("application/json"),
-        );
+        Self::with_config(token, SlackClientConfig::default())
+    }
 
-        let client = Client::builder()
-            .default_headers(headers)
-            .timeout(Duration::from_secs(30))
-            .build()?;
+    /// Build a client with its own request pool sized by `config`
+    pub fn with_config(token: String, config: SlackClientConfig) -> Result<Self> {
+        let rate_limit_state = Arc::new(RateLimitState::new(config.max_concurrent.max(1)));
+        Self::build(token, config, rate_limit_state)
+    }
+
+    /// Build a client that shares its request pool and backoff state with other clients
+    ///
+    /// The pool should be sized from `config.max_concurrent`, since batch sizes
+    /// are derived from it.
+    pub fn with_rate_limit_state(
+        token: String,
+        config: SlackClientConfig,
+        rate_limit_state: Arc<RateLimitState>,
+    ) -> Result<Self> {
+        Self::build(token, config, rate_limit_state)
+    }
+
+    fn build(token: String, config: SlackClientConfig, rate_limit_state: Arc<RateLimitState>) -> Result<Self> {
+        let client = http_client(&token, config.timeout_secs)?;
 
         Ok(Self {
             client,
             token,
-            rate_limiter: Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+            rate_limiter: rate_limit_state.semaphore(),
+            rate_limit_state,
+            config,
+            reactor_cap: DEFAULT_REACTOR_CAP,
+            max_retries: DEFAULT_MAX_RATE_LIMIT_RETRIES,
+        })
+    }
+
+    /// Pause between paginated requests, from the client config
+    fn pagination_delay(&self) -> Duration {
+        Duration::from_millis(self.config.delay_ms)
+    }
+
+    /// Keep at most `cap` reactor IDs per reaction in reactions.get results
+    pub fn with_reactor_cap(mut self, cap: usize) -> Self {
+        self.reactor_cap = cap;
+        self
+    }
+
+    /// Retry a rate-limited request at most `max_retries` times before giving up
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Send a request, backing off and retrying while Slack reports a rate limit
+    ///
+    /// Slack signals rate limits either as HTTP 429 or as a 200 with
+    /// `ok:false, error:"ratelimited"`; both are retried the same way, waiting
+    /// for Retry-After when Slack sends it. Once the retries are exhausted the
+    /// request fails with a rate-limit error. A request that cannot connect to
+    /// Slack (pooled connections gone stale after sleep or a network switch) is
+    /// sent once more through a freshly built HTTP client.
+    async fn send_with_backoff(&self, request: reqwest::RequestBuilder) -> Result<RawResponse> {
+        let pacing_delay = self.rate_limit_state.adaptive_delay();
+        if !pacing_delay.is_zero() {
+            debug!("Pacing request by {:?} after recent rate limits", pacing_delay);
+            sleep(pacing_delay).await;
+        }
+
+        let state = &self.rate_limit_state;
+        let base_delay = Duration::from_millis(RATE_LIMIT_BACKOFF_MS);
+        let request = &request;
+        retry_rate_limited(state, base_delay, self.max_retries, move || {
+            let rebuild = move || http_client(&self.token, self.config.timeout_secs);
+            with_reconnect(&self.client, rebuild, move |client| self.send_once(client, request))
+        })
+        .await
+    }
+
+    /// Send a copy of `request` through `client` and read the whole response
+    async fn send_once(&self, client: Client, request: &reqwest::RequestBuilder) -> Result<RawResponse> {
+        let request = request
+            .try_clone()
+            .ok_or_else(|| anyhow!("Request cannot be retried"))?
+            .build()?;
+        let response = client.execute(request).await?;
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        let body = response.text().await?;
+        log_response_warnings(&body);
+        Ok(RawResponse {
+            status,
+            retry_after,
+            body,
         })
     }
 
+    /// Number of messages matching `query`, read from a single one-result page
+    pub async fn count_search_matches(&self, query: &str) -> Result<usize> {
+        let _permit = self.rate_limiter.acquire().await?;
+        let url = format!("{}/search.messages", SLACK_API_BASE);
+
+        let mut params = HashMap::new();
+        params.insert("query", query.to_string());
+        params.insert("count", "1".to_string());
+        params.insert("page", "1".to_string());
+
+        debug!("Counting search matches for query: '{}'", query);
+
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
+
+        if !response.status.is_success() {
+            return Err(anyhow!("Failed to count search matches: {}", response.status));
+        }
+
+        let total = parse_search_total(&response.body)?;
+        info!("Query '{}' matches {} messages", query, total);
+        Ok(total)
+    }
+
+    /// Run search.messages for one page of results
+    ///
+    /// With `options.highlight`, Slack wraps matches in the message text with
+    /// \u{e000}/\u{e001} markers (see `parser::parse_highlight_ranges`).
     pub async fn search_messages(
         &self,
         query: &str,
         count: usize,
         page: usize,
+        options: SearchOptions,
     ) -> Result<SlackSearchResponse> {
         let url = format!("{}/search.messages", SLACK_API_BASE);
 
@@ -58,68 +197,60 @@ impl SlackClient {
         params.insert("query", query.to_string());
         params.insert("count", count.to_string());
         params.insert("page", page.to_string());
-        params.insert("sort", "timestamp".to_string());
+        params.insert("sort", options.sort.as_param().to_string());
         params.insert("sort_dir", "desc".to_string());
+        if options.highlight {
+            params.insert("highlight", "true".to_string());
+        }
 
         info!(
             "Searching messages with query: '{}', page: {}, count: {}",
             query, page, count
         );
 
-        let response = self.client.get(&url).query(&params).send().await?;
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let text = response.body;
             error!("Slack API HTTP error: {} - {}", status, text);
 
             // Provide more specific error messages
             if status == 401 {
-                return Err(anyhow!(
-                    "Authentication failed. Your Slack token may be invalid or expired."
-                ));
+                return Err(AppError::AuthError(
+                    "Authentication failed. Your Slack token may be invalid or expired.".to_string(),
+                ).into());
             } else if status == 403 {
-                return Err(anyhow!(
-                    "Access denied. Your token may not have the required permissions for search."
-                ));
-            } else if status == 429 {
-                return Err(anyhow!(
-                    "Rate limit exceeded. Please wait a moment and try again."
-                ));
+                return Err(AppError::AuthError(
+                    "Access denied. Your token may not have the required permissions for search.".to_string(),
+                ).into());
             }
 
-            return Err(anyhow!("Slack API error: {} - {}", status, text));
+            return Err(AppError::http_status(status.as_u16(), &text).into());
         }
 
-        let response_text = response.text().await?;
+        let response_text = response.body;
         
         // Debug log the raw response to see what fields we're getting
-        debug!("Raw Slack API response (first 1000 chars): {}", &response_text[..response_text.len().min(1000)]);
+        debug!("Raw Slack API response (first 1000 chars): {}", preview(&response_text, 1000));
         
         let result: SlackSearchResponse = serde_json::from_str(&response_text)?;
+        if result.is_truncated() {
+            warn!("Slack truncated search.messages results for query '{}': {:?}", query, result.warnings());
+        }
 
         if !result.ok {
             let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
             error!("Slack API returned error: {}", error_msg);
 
             // Provide more specific error messages based on Slack error codes
-            if error_msg.contains("invalid_auth") {
-                return Err(anyhow!(
-                    "Invalid authentication token. Please check your Slack token in Settings."
-                ));
-            } else if error_msg.contains("token_revoked") {
-                return Err(anyhow!(
-                    "Your Slack token has been revoked. Please generate a new token."
-                ));
-            } else if error_msg.contains("not_in_channel") {
-                return Err(anyhow!(
-                    "You don't have access to search in the specified channel."
-                ));
-            } else if error_msg.contains("missing_scope") {
-                return Err(anyhow!("Your token doesn't have the required permissions. Please ensure it has 'search:read' scope."));
+            if error_msg.contains("token_revoked") {
+                return Err(AppError::AuthError(
+                    "Your Slack token has been revoked. Please generate a new token.".to_string(),
+                ).into());
             }
 
-            return Err(anyhow!("Slack API error: {}", error_msg));
+            return Err(SlackApiError::from_response("search.messages", &error_msg, &response_text).into());
         }
 
         debug!(
@@ -129,10 +260,40 @@ impl SlackClient {
         Ok(result)
     }
 
+    /// Fetch a whole thread via conversations.replies, following cursors past
+    /// the 1000-message page size (the parent comes first)
     pub async fn get_thread(
         &self,
         channel_id: &str,
         thread_ts: &str,
+    ) -> Result<SlackConversationsRepliesResponse> {
+        let messages = collect_reply_pages(
+            |msg: &SlackReplyMessage| msg.ts.clone(),
+            |cursor| async move {
+                let page = self.get_thread_page(channel_id, thread_ts, cursor).await?;
+                let next_cursor = page.next_cursor();
+                Ok::<_, anyhow::Error>((page.messages.unwrap_or_default(), next_cursor))
+            },
+        )
+        .await?;
+
+        info!("[SlackClient] Final thread response has {} messages", messages.len());
+
+        Ok(SlackConversationsRepliesResponse {
+            ok: true,
+            messages: Some(messages),
+            error: None,
+            has_more: Some(false),
+            response_metadata: None,
+        })
+    }
+
+    /// One page of conversations.replies
+    async fn get_thread_page(
+        &self,
+        channel_id: &str,
+        thread_ts: &str,
+        cursor: Option<String>,
     ) -> Result<SlackConversationsRepliesResponse> {
         let url = format!("{}/conversations.replies", SLACK_API_BASE);
 
@@ -140,22 +301,26 @@ impl SlackClient {
         params.insert("channel", channel_id.to_string());
         params.insert("ts", thread_ts.to_string());
         params.insert("limit", "1000".to_string());
+        params.insert("include_all_metadata", "true".to_string());
+        if let Some(cursor) = cursor {
+            params.insert("cursor", cursor);
+        }
 
         info!(
             "[SlackClient] Getting thread for channel: {}, ts: {}, URL: {}, params: {:?}",
             channel_id, thread_ts, url, params
         );
 
-        let response = self.client.get(&url).query(&params).send().await?;
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let text = response.body;
             error!("Slack API error: {} - {}", status, text);
-            return Err(anyhow!("Slack API error: {} - {}", status, text));
+            return Err(AppError::http_status(status.as_u16(), &text).into());
         }
 
-        let result: SlackConversationsRepliesResponse = response.json().await?;
+        let result: SlackConversationsRepliesResponse = serde_json::from_str(&response.body)?;
         
         info!("[SlackClient] Thread API response: ok={}, messages_count={}", 
             result.ok, 
@@ -165,7 +330,7 @@ impl SlackClient {
         if !result.ok {
             let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
             error!("Slack API returned error: {}", error_msg);
-            return Err(anyhow!("Slack API error: {}", error_msg));
+            return Err(SlackApiError::from_response("conversations.replies", &error_msg, &response.body).into());
         }
 
         // Log the response for debugging
@@ -175,26 +340,65 @@ impl SlackClient {
                 info!("  Message {}: ts={}, thread_ts={:?}", i, msg.ts, msg.thread_ts);
             }
         }
-        
-        info!("[SlackClient] Final thread response has {} messages",
-            result.messages.as_ref().map(|m| m.len()).unwrap_or(0));
 
         Ok(result)
     }
 
+    /// Fetch a single message (parent or reply) by ts via conversations.replies
+    ///
+    /// Unlike search results, the returned message carries thread_ts and
+    /// reply_count. Returns `None` if Slack has no message at that ts.
+    pub async fn get_single_message(
+        &self,
+        channel_id: &str,
+        ts: &str,
+    ) -> Result<Option<SlackReplyMessage>> {
+        let url = format!("{}/conversations.replies", SLACK_API_BASE);
+
+        let mut params = HashMap::new();
+        params.insert("channel", channel_id.to_string());
+        params.insert("ts", ts.to_string());
+        params.insert("limit", "1".to_string());
+        params.insert("inclusive", "true".to_string());
+
+        debug!("Fetching single message: channel={}, ts={}", channel_id, ts);
+
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
+
+        if !response.status.is_success() {
+            return Err(anyhow!("Failed to fetch message: {}", response.status));
+        }
+
+        let result: SlackConversationsRepliesResponse = serde_json::from_str(&response.body)?;
+
+        if !result.ok {
+            let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+            if error_msg == "thread_not_found" || error_msg == "message_not_found" {
+                return Ok(None);
+            }
+            return Err(SlackApiError::from_response("conversations.replies", &error_msg, &response.body).into());
+        }
+
+        Ok(result
+            .messages
+            .unwrap_or_default()
+            .into_iter()
+            .find(|msg| msg.ts == ts))
+    }
+
     pub async fn get_user_info(&self, user_id: &str) -> Result<SlackUserInfo> {
         let url = format!("{}/users.info", SLACK_API_BASE);
 
         let mut params = HashMap::new();
         params.insert("user", user_id.to_string());
 
-        let response = self.client.get(&url).query(&params).send().await?;
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if !response.status().is_success() {
-            return Err(anyhow!("Failed to get user info: {}", response.status()));
+        if !response.status.is_success() {
+            return Err(anyhow!("Failed to get user info: {}", response.status));
         }
 
-        let result: SlackUserInfoResponse = response.json().await?;
+        let result: SlackUserInfoResponse = serde_json::from_str(&response.body)?;
 
         if !result.ok {
             let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
@@ -214,11 +418,16 @@ impl SlackClient {
                         real_name: Some("External User".to_string()),
                         image_48: None,
                         image_72: None,
+                        image_192: None,
+                        image_512: None,
+                        image_1024: None,
+                        image_original: None,
+                        is_custom_image: None,
                     }),
                 });
             }
 
-            return Err(anyhow!("Slack API error: {}", error_msg));
+            return Err(SlackApiError::from_response("users.info", &error_msg, &response.body).into());
         }
 
         result.user.ok_or_else(|| anyhow!("User not found"))
@@ -240,17 +449,17 @@ impl SlackClient {
 
             debug!("Fetching users page with cursor: {:?}", cursor);
 
-            let response = self.client.get(&url).query(&params).send().await?;
+            let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-            if !response.status().is_success() {
-                return Err(anyhow!("Failed to get users: {}", response.status()));
+            if !response.status.is_success() {
+                return Err(anyhow!("Failed to get users: {}", response.status));
             }
 
-            let result: SlackUsersListResponse = response.json().await?;
+            let result: SlackUsersListResponse = serde_json::from_str(&response.body)?;
 
             if !result.ok {
                 let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
-                return Err(anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("users.list", &error_msg, &response.body).into());
             }
 
             if let Some(users) = result.members {
@@ -263,7 +472,7 @@ impl SlackClient {
                     if !next.is_empty() {
                         cursor = Some(next);
                         // Rate limiting
-                        sleep(Duration::from_millis(RATE_LIMIT_DELAY_MS)).await;
+                        sleep(self.pagination_delay()).await;
                         continue;
                     }
                 }
@@ -284,7 +493,8 @@ impl SlackClient {
         }
 
         // Otherwise, fetch channel list and find the matching channel
-        let channels = self.get_channels().await?;
+        // (archived channels are still searchable, so include them)
+        let channels = self.get_channels_with_archived(true).await?;
 
         // Remove # prefix if present
         let clean_name = channel_name.trim_start_matches('#');
@@ -298,32 +508,35 @@ impl SlackClient {
         Err(anyhow!("Channel '{}' not found", clean_name))
     }
 
+    /// List public and private channels, leaving out archived ones
     pub async fn get_channels(&self) -> Result<Vec<SlackConversation>> {
+        self.get_channels_with_archived(false).await
+    }
+
+    /// List public and private channels, optionally including archived ones
+    pub async fn get_channels_with_archived(
+        &self,
+        include_archived: bool,
+    ) -> Result<Vec<SlackConversation>> {
         let url = format!("{}/conversations.list", SLACK_API_BASE);
 
         let mut all_channels = Vec::new();
         let mut cursor: Option<String> = None;
 
         loop {
-            let mut params = HashMap::new();
-            params.insert("types", "public_channel,private_channel".to_string());
-            params.insert("limit", "1000".to_string());
-
-            if let Some(ref cursor_value) = cursor {
-                params.insert("cursor", cursor_value.clone());
-            }
+            let params = channel_list_params(include_archived, cursor.as_deref());
 
-            let response = self.client.get(&url).query(&params).send().await?;
+            let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-            if !response.status().is_success() {
-                return Err(anyhow!("Failed to get channels: {}", response.status()));
+            if !response.status.is_success() {
+                return Err(anyhow!("Failed to get channels: {}", response.status));
             }
 
-            let result: SlackConversationsListResponse = response.json().await?;
+            let result: SlackConversationsListResponse = serde_json::from_str(&response.body)?;
 
             if !result.ok {
                 let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
-                return Err(anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("conversations.list", &error_msg, &response.body).into());
             }
 
             if let Some(channels) = result.channels {
@@ -336,7 +549,7 @@ impl SlackClient {
                     if !next.is_empty() {
                         cursor = Some(next);
                         // Rate limiting
-                        sleep(Duration::from_millis(RATE_LIMIT_DELAY_MS)).await;
+                        sleep(self.pagination_delay()).await;
                         continue;
                     }
                 }
@@ -359,13 +572,28 @@ impl SlackClient {
         dm_id: &str,
         query: Option<&str>,
         limit: usize,
+        home_team: Option<&str>,
+    ) -> Result<Vec<SlackMessage>> {
+        self.search_dm_messages_with_mode(dm_id, query, limit, MatchMode::Exact, home_team)
+            .await
+    }
+
+    /// Like `search_dm_messages`, choosing how the local keyword filter matches
+    ///
+    /// `home_team` is the token's team ID, used to spot Grid DMs that live in
+    /// another team.
+    pub async fn search_dm_messages_with_mode(
+        &self,
+        dm_id: &str,
+        query: Option<&str>,
+        limit: usize,
+        match_mode: MatchMode,
+        home_team: Option<&str>,
     ) -> Result<Vec<SlackMessage>> {
         // Acquire semaphore permit for rate limiting
         let _permit = self.rate_limiter.acquire().await
             .map_err(|e| anyhow!("Failed to acquire rate limit permit: {}", e))?;
 
-        let url = format!("{}/conversations.history", SLACK_API_BASE);
-
         // Determine if it's a DM or Group DM
         let channel_type = if dm_id.starts_with("D") {
             "DM"
@@ -380,19 +608,90 @@ impl SlackClient {
             channel_type, dm_id, query, limit
         );
 
-        let mut params = HashMap::new();
-        params.insert("channel", dm_id.to_string());
-        params.insert("limit", limit.min(100).to_string()); // Cap at 100 for safety
+        let mut messages = match self.fetch_dm_history(dm_id, limit, None).await {
+            Err(e) if is_not_in_channel(&e) => {
+                error!("User not in channel {} - this might be a cross-workspace DM", dm_id);
+                // In Grid, a DM can live in another team; retry once in that team's context
+                let retried = match self.dm_team_context(dm_id, home_team).await {
+                    Some(team) => {
+                        info!("Retrying DM {} in team context {}", dm_id, team);
+                        self.fetch_dm_history(dm_id, limit, Some(&team)).await
+                    }
+                    None => Err(e),
+                };
+                match retried {
+                    Err(e) if is_not_in_channel(&e) => {
+                        info!("Returning empty results for inaccessible DM channel {}", dm_id);
+                        return Ok(vec![]);
+                    }
+                    other => other?,
+                }
+            }
+            other => other?,
+        };
+
+        // Add channel information to each message for DMs
+        // This is important so the UI can identify the channel properly
+        for message in &mut messages {
+            if message.channel.is_none() {
+                message.channel = Some(SlackChannelInfo {
+                    id: dm_id.to_string(),
+                    name: dm_id.to_string(), // Will be resolved by the UI/cache
+                    is_shared: false,
+                });
+            }
+        }
+
+        info!(
+            "Retrieved {} messages from DM channel {} before filtering",
+            messages.len(),
+            dm_id
+        );
+
+        // If a query is provided, filter messages locally (Phase 2 approach)
+        if let Some(search_query) = query {
+            let before_filter = messages.len();
+            messages.retain(|msg| text_matches(&msg.text, search_query, match_mode));
+
+            info!(
+                "Filtered DM messages: {} -> {} results match query '{}'",
+                before_filter,
+                messages.len(),
+                search_query
+            );
+        }
+
+        info!(
+            "Successfully retrieved {} messages from DM channel {}",
+            messages.len(),
+            dm_id
+        );
+
+        Ok(messages)
+    }
+
+    /// One conversations.history call for a DM, in `team_id`'s context when given
+    ///
+    /// `not_in_channel` comes back as `SlackApiError::NotInChannel` so the caller
+    /// can retry in another team.
+    async fn fetch_dm_history(
+        &self,
+        dm_id: &str,
+        limit: usize,
+        team_id: Option<&str>,
+    ) -> Result<Vec<SlackMessage>> {
+        let url = format!("{}/conversations.history", SLACK_API_BASE);
+        let params = dm_history_params(dm_id, limit, team_id);
 
         // Small delay to prevent hitting rate limits
         // The semaphore already limits concurrent requests, but a small delay helps with burst prevention
-        sleep(Duration::from_millis(RATE_LIMIT_DELAY_MS)).await;
+        sleep(self.pagination_delay()).await;
 
-        let response = self.client.get(&url).query(&params).send().await?;
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let text = response.body;
             error!("Failed to search DM messages: {} - {}", status, text);
 
             if status == 403 {
@@ -405,22 +704,16 @@ impl SlackClient {
         }
 
         // Log the response size for debugging
-        let response_text = response.text().await?;
+        let response_text = response.body;
         info!("Conversations.history response size: {} bytes", response_text.len());
 
         // Log first 500 chars of response for debugging (to check structure)
-        if response_text.len() > 0 {
-            let preview = if response_text.len() > 500 {
-                &response_text[..500]
-            } else {
-                &response_text
-            };
-            debug!("Response preview: {}", preview);
-        }
+        debug!("Response preview: {}", preview(&response_text, 500));
 
         #[derive(Deserialize)]
         struct ConversationsHistoryResponse {
             ok: bool,
+            #[serde(default, deserialize_with = "deserialize_lenient_opt_vec")]
             messages: Option<Vec<SlackMessage>>,
             error: Option<String>,
             error_detail: Option<String>,
@@ -450,9 +743,12 @@ impl SlackClient {
 
             // Check for specific error conditions
             if error_msg.contains("missing_scope") {
-                return Err(anyhow!(
-                    "Missing required scope. Your token needs 'im:history' permission to search DM messages."
-                ));
+                let needed = needed_scope(&response_text);
+                return Err(SlackApiError::missing_scope_for(
+                    "conversations.history",
+                    needed.as_deref().unwrap_or("im:history"),
+                )
+                .into());
             }
 
             if error_msg.contains("channel_not_found") {
@@ -461,58 +757,13 @@ impl SlackClient {
             }
 
             if error_msg.contains("not_in_channel") {
-                error!("User not in channel {} - this might be a cross-workspace DM", dm_id);
-                // For cross-workspace DMs, we might need different handling
-                // Return empty messages for now instead of error
-                info!("Returning empty results for inaccessible DM channel {}", dm_id);
-                return Ok(vec![]);
+                return Err(SlackApiError::NotInChannel.into());
             }
 
             return Err(anyhow!("Slack API error for DM {}: {} {}", dm_id, error_msg, error_detail));
         }
 
-        let mut messages = result.messages.unwrap_or_default();
-
-        // Add channel information to each message for DMs
-        // This is important so the UI can identify the channel properly
-        for message in &mut messages {
-            if message.channel.is_none() {
-                message.channel = Some(SlackChannelInfo {
-                    id: dm_id.to_string(),
-                    name: dm_id.to_string(), // Will be resolved by the UI/cache
-                });
-            }
-        }
-
-        info!(
-            "Retrieved {} messages from DM channel {} before filtering",
-            messages.len(),
-            dm_id
-        );
-
-        // If a query is provided, filter messages locally (Phase 2 approach)
-        if let Some(search_query) = query {
-            let search_lower = search_query.to_lowercase();
-            let before_filter = messages.len();
-            messages.retain(|msg| {
-                msg.text.to_lowercase().contains(&search_lower)
-            });
-
-            info!(
-                "Filtered DM messages: {} -> {} results match query '{}'",
-                before_filter,
-                messages.len(),
-                search_query
-            );
-        }
-
-        info!(
-            "Successfully retrieved {} messages from DM channel {}",
-            messages.len(),
-            dm_id
-        );
-
-        Ok(messages)
+        Ok(result.messages.unwrap_or_default())
     }
 
     pub async fn get_dm_channels(&self) -> Result<Vec<SlackConversation>> {
@@ -533,11 +784,11 @@ impl SlackClient {
                 params.insert("cursor", cursor_value.clone());
             }
 
-            let response = self.client.get(&url).query(&params).send().await?;
+            let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await?;
+            if !response.status.is_success() {
+                let status = response.status;
+                let text = response.body;
                 error!("Failed to get DM channels: {} - {}", status, text);
 
                 if status == 403 {
@@ -549,19 +800,22 @@ impl SlackClient {
                 return Err(anyhow!("Failed to get DM channels: {} - {}", status, text));
             }
 
-            let result: SlackConversationsListResponse = response.json().await?;
+            let result: SlackConversationsListResponse = serde_json::from_str(&response.body)?;
 
             if !result.ok {
                 let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
 
                 // Check for specific permission errors
                 if error_msg.contains("missing_scope") {
-                    return Err(anyhow!(
-                        "Missing required scope. Your token needs 'im:read' permission to access DM channels."
-                    ));
+                    let needed = needed_scope(&response.body);
+                    return Err(SlackApiError::missing_scope_for(
+                        "conversations.list",
+                        needed.as_deref().unwrap_or("im:read"),
+                    )
+                    .into());
                 }
 
-                return Err(anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("conversations.list", &error_msg, &response.body).into());
             }
 
             if let Some(channels) = result.channels {
@@ -583,7 +837,7 @@ impl SlackClient {
                     if !next.is_empty() {
                         cursor = Some(next);
                         // Rate limiting - be extra conservative with DM fetching
-                        sleep(Duration::from_millis(RATE_LIMIT_DELAY_MS * 2)).await;
+                        sleep(self.pagination_delay() * 2).await;
                         continue;
                     }
                 }
@@ -613,17 +867,17 @@ impl SlackClient {
                 params.insert("cursor", cursor_value.clone());
             }
 
-            let response = self.client.get(&url).query(&params).send().await?;
+            let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-            if !response.status().is_success() {
-                return Err(anyhow!("Failed to get users: {}", response.status()));
+            if !response.status.is_success() {
+                return Err(anyhow!("Failed to get users: {}", response.status));
             }
 
-            let result: SlackUsersListResponse = response.json().await?;
+            let result: SlackUsersListResponse = serde_json::from_str(&response.body)?;
 
             if !result.ok {
                 let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
-                return Err(anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("users.list", &error_msg, &response.body).into());
             }
 
             if let Some(users) = result.members {
@@ -636,7 +890,7 @@ impl SlackClient {
                     if !next.is_empty() {
                         cursor = Some(next);
                         // Rate limiting
-                        sleep(Duration::from_millis(RATE_LIMIT_DELAY_MS)).await;
+                        sleep(self.pagination_delay()).await;
                         continue;
                     }
                 }
@@ -656,12 +910,12 @@ impl SlackClient {
 
         debug!("Getting channel info for: {}", channel_id);
 
-        let response = self.client.get(&url).query(&params).send().await?;
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             // Don't fail hard for channel info - it's not critical
-            debug!("Failed to get channel info: {}", response.status());
-            return Err(anyhow!("Failed to get channel info: {}", response.status()));
+            debug!("Failed to get channel info: {}", response.status);
+            return Err(anyhow!("Failed to get channel info: {}", response.status));
         }
 
         #[derive(Deserialize)]
@@ -671,7 +925,7 @@ impl SlackClient {
             error: Option<String>,
         }
 
-        let result: ChannelInfoResponse = response.json().await?;
+        let result: ChannelInfoResponse = serde_json::from_str(&response.body)?;
 
         if !result.ok {
             let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
@@ -691,71 +945,186 @@ impl SlackClient {
                     is_member: None,
                     is_muted: None,
                     is_archived: None,
+                    last_read: None,
+                    is_shared: None,
+                    is_ext_shared: None,
+                    context_team_id: None,
+                    updated: None,
+                    unread_count_display: None,
+                    created: None,
+                    creator: None,
                 });
             }
-            return Err(anyhow!("Slack API error: {}", error_msg));
+            return Err(SlackApiError::from_response("conversations.info", &error_msg, &response.body).into());
         }
 
         result.channel.ok_or_else(|| anyhow!("Channel not found"))
     }
 
-    pub async fn get_channel_messages(
+    /// Look up a channel via conversations.info without masking Slack errors
+    ///
+    /// Unlike `get_channel_info`, a Slack-level failure (e.g. `channel_not_found`)
+    /// is returned as the error code instead of a placeholder channel, so callers
+    /// can tell a stale ID apart from an inaccessible one. Only HTTP/network
+    /// failures are returned as `Err`.
+    pub async fn probe_channel(
         &self,
         channel_id: &str,
-        oldest: Option<String>,
-        latest: Option<String>,
-        limit: usize,
-    ) -> Result<Vec<SlackMessage>> {
-        let url = format!("{}/conversations.history", SLACK_API_BASE);
+    ) -> Result<(Option<SlackConversation>, Option<String>)> {
+        let url = format!("{}/conversations.info", SLACK_API_BASE);
 
         let mut params = HashMap::new();
         params.insert("channel", channel_id.to_string());
-        // Always use 200 per request for best pagination results
-        let per_request_limit = 200;
-        params.insert("limit", per_request_limit.to_string()); // Slack recommends 200 per request for pagination
-        params.insert("inclusive", "true".to_string());
 
-        info!("[DEBUG] Using limit {} per API request (total limit requested: {})", per_request_limit, limit);
+        debug!("Probing channel: {}", channel_id);
 
-        if let Some(ref oldest_ts) = oldest {
-            params.insert("oldest", oldest_ts.clone());
-            info!("[DEBUG] Setting oldest timestamp: {}", oldest_ts);
-            // Convert to human-readable for debugging
-            if let Ok(ts_float) = oldest_ts.parse::<f64>() {
-                if let Some(dt) = chrono::DateTime::from_timestamp(ts_float as i64, 0) {
-                    info!("[DEBUG] Oldest date: {} (JST: {})",
-                        dt.format("%Y-%m-%d %H:%M:%S UTC"),
-                        dt.with_timezone(&chrono::FixedOffset::east_opt(9 * 3600).unwrap()).format("%Y-%m-%d %H:%M:%S"));
-                }
-            }
-        } else {
-            info!("[DEBUG] No oldest timestamp specified - fetching ALL messages");
-        }
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if let Some(ref latest_ts) = latest {
-            params.insert("latest", latest_ts.clone());
-            info!("[DEBUG] Setting latest timestamp: {}", latest_ts);
-            // Convert to human-readable for debugging
-            if let Ok(ts_float) = latest_ts.parse::<f64>() {
-                if let Some(dt) = chrono::DateTime::from_timestamp(ts_float as i64, 0) {
-                    info!("[DEBUG] Latest date: {} (JST: {})",
-                        dt.format("%Y-%m-%d %H:%M:%S UTC"),
-                        dt.with_timezone(&chrono::FixedOffset::east_opt(9 * 3600).unwrap()).format("%Y-%m-%d %H:%M:%S"));
-                }
-            }
-        } else {
-            info!("[DEBUG] No latest timestamp specified - fetching up to now");
+        if !response.status.is_success() {
+            return Err(anyhow!("Failed to probe channel: {}", response.status));
         }
 
-        info!(
-            "Getting channel messages for channel: {}, limit: {}, date range: {:?} to {:?}",
-            channel_id, limit, oldest, latest
-        );
-
         #[derive(Deserialize)]
-        struct ConversationsHistoryResponse {
+        struct ChannelInfoResponse {
             ok: bool,
-            messages: Option<Vec<SlackMessage>>,
+            channel: Option<SlackConversation>,
+            error: Option<String>,
+        }
+
+        let result: ChannelInfoResponse = serde_json::from_str(&response.body)?;
+
+        if !result.ok {
+            let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+            debug!("Channel probe for {} returned error: {}", channel_id, error_msg);
+            return Ok((None, Some(error_msg)));
+        }
+
+        Ok((result.channel, None))
+    }
+
+    /// Get the timestamp of the oldest message in a channel
+    ///
+    /// With `oldest=0` and no `latest`, conversations.history starts its window
+    /// at the beginning of the channel, so a single-item page holds the first
+    /// message. Returns `None` for an empty channel.
+    pub async fn get_oldest_message_ts(&self, channel_id: &str) -> Result<Option<String>> {
+        let _permit = self.rate_limiter.acquire().await?;
+        let url = format!("{}/conversations.history", SLACK_API_BASE);
+
+        let mut params = HashMap::new();
+        params.insert("channel", channel_id.to_string());
+        params.insert("oldest", "0".to_string());
+        params.insert("limit", "1".to_string());
+        params.insert("inclusive", "true".to_string());
+
+        debug!("Fetching oldest message ts for channel: {}", channel_id);
+
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
+
+        if !response.status.is_success() {
+            return Err(anyhow!("Failed to get oldest message: {}", response.status));
+        }
+
+        let body = response.body;
+        let oldest = parse_oldest_message_ts(&body)?;
+
+        info!("Oldest message in channel {}: {:?}", channel_id, oldest);
+        Ok(oldest)
+    }
+
+    pub async fn get_channel_messages(
+        &self,
+        channel_id: &str,
+        oldest: Option<String>,
+        latest: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<SlackMessage>> {
+        self.get_channel_messages_with_options(channel_id, oldest, latest, limit, HistoryOptions::default())
+            .await
+    }
+
+    /// Fetch channel history, either inlining thread replies or leaving them
+    /// to be loaded on demand via `get_thread` (parents keep their reply_count)
+    ///
+    /// With `auto_join`, a `not_in_channel` failure joins the channel and retries once.
+    pub async fn get_channel_messages_with_options(
+        &self,
+        channel_id: &str,
+        oldest: Option<String>,
+        latest: Option<String>,
+        limit: usize,
+        options: HistoryOptions,
+    ) -> Result<Vec<SlackMessage>> {
+        let mut messages = with_auto_join(
+            options.auto_join,
+            || self.fetch_channel_history(channel_id, oldest.clone(), latest.clone(), limit, options),
+            || self.join_channel(channel_id),
+        )
+        .await?;
+        retain_exact_ts(&mut messages, oldest.as_deref(), latest.as_deref());
+        Ok(messages)
+    }
+
+    async fn fetch_channel_history(
+        &self,
+        channel_id: &str,
+        oldest: Option<String>,
+        latest: Option<String>,
+        limit: usize,
+        options: HistoryOptions,
+    ) -> Result<Vec<SlackMessage>> {
+        let url = format!("{}/conversations.history", SLACK_API_BASE);
+
+        let mut params = HashMap::new();
+        params.insert("channel", channel_id.to_string());
+        // Always use 200 per request for best pagination results
+        let per_request_limit = 200;
+        params.insert("limit", per_request_limit.to_string()); // Slack recommends 200 per request for pagination
+        params.insert("inclusive", "true".to_string());
+        params.insert("include_all_metadata", "true".to_string());
+
+        info!("[DEBUG] Using limit {} per API request (total limit requested: {})", per_request_limit, limit);
+
+        if let Some(ref oldest_ts) = oldest {
+            params.insert("oldest", oldest_ts.clone());
+            info!("[DEBUG] Setting oldest timestamp: {}", oldest_ts);
+            // Convert to human-readable for debugging
+            if let Ok(ts_float) = oldest_ts.parse::<f64>() {
+                if let Some(dt) = chrono::DateTime::from_timestamp(ts_float as i64, 0) {
+                    info!("[DEBUG] Oldest date: {} (JST: {})",
+                        dt.format("%Y-%m-%d %H:%M:%S UTC"),
+                        dt.with_timezone(&chrono::FixedOffset::east_opt(9 * 3600).unwrap()).format("%Y-%m-%d %H:%M:%S"));
+                }
+            }
+        } else {
+            info!("[DEBUG] No oldest timestamp specified - fetching ALL messages");
+        }
+
+        if let Some(ref latest_ts) = latest {
+            params.insert("latest", latest_ts.clone());
+            info!("[DEBUG] Setting latest timestamp: {}", latest_ts);
+            // Convert to human-readable for debugging
+            if let Ok(ts_float) = latest_ts.parse::<f64>() {
+                if let Some(dt) = chrono::DateTime::from_timestamp(ts_float as i64, 0) {
+                    info!("[DEBUG] Latest date: {} (JST: {})",
+                        dt.format("%Y-%m-%d %H:%M:%S UTC"),
+                        dt.with_timezone(&chrono::FixedOffset::east_opt(9 * 3600).unwrap()).format("%Y-%m-%d %H:%M:%S"));
+                }
+            }
+        } else {
+            info!("[DEBUG] No latest timestamp specified - fetching up to now");
+        }
+
+        info!(
+            "Getting channel messages for channel: {}, limit: {}, date range: {:?} to {:?}",
+            channel_id, limit, oldest, latest
+        );
+
+        #[derive(Deserialize)]
+        struct ConversationsHistoryResponse {
+            ok: bool,
+            #[serde(default, deserialize_with = "deserialize_lenient_opt_vec")]
+            messages: Option<Vec<SlackMessage>>,
             error: Option<String>,
             has_more: Option<bool>,
             response_metadata: Option<ResponseMetadata>,
@@ -768,6 +1137,7 @@ impl SlackClient {
 
         // Initialize variables for pagination
         let mut all_messages = Vec::new();
+        let mut seen_ts = HashSet::new();
         let mut cursor: Option<String> = None;
         let mut total_api_calls = 0;
 
@@ -782,11 +1152,11 @@ impl SlackClient {
             total_api_calls += 1;
             info!("API call {} for conversations.history (cursor: {:?})", total_api_calls, cursor);
 
-            let response = self.client.get(&url).query(&current_params).send().await?;
+            let response = self.send_with_backoff(self.client.get(&url).query(&current_params)).await?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await?;
+            if !response.status.is_success() {
+                let status = response.status;
+                let text = response.body;
                 error!("Failed to get channel messages: {} - {}", status, text);
                 return Err(anyhow!(
                     "Failed to get channel messages: {} - {}",
@@ -795,7 +1165,7 @@ impl SlackClient {
                 ));
             }
 
-            let response_text = response.text().await?;
+            let response_text = response.body;
             debug!("API Response size: {} bytes", response_text.len());
 
             // Debug: Check if raw response contains reactions
@@ -810,7 +1180,7 @@ impl SlackClient {
             if !result.ok {
                 let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
                 error!("Slack API error: {}", error_msg);
-                return Err(anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("conversations.history", &error_msg, &response_text).into());
             }
 
             let messages = result.messages.unwrap_or_default();
@@ -839,8 +1209,8 @@ impl SlackClient {
                 }
             }
 
-            // Add messages to our collection
-            all_messages.extend(messages);
+            // Add messages to our collection; `inclusive` can repeat the boundary message
+            extend_unique_ts(&mut all_messages, &mut seen_ts, messages);
 
             // Check if there are more messages to fetch
             let has_more = result.has_more.unwrap_or(false);
@@ -861,7 +1231,7 @@ impl SlackClient {
             }
 
             // Safety limit to prevent infinite loops
-            if total_api_calls > 10 {
+            if !options.full_history && total_api_calls > 10 {
                 warn!("Reached maximum API call limit (10) for conversations.history");
                 break;
             }
@@ -901,96 +1271,85 @@ impl SlackClient {
                 msg.user,
                 msg.subtype,
                 msg.username,
-                msg.text.chars().take(50).collect::<String>(),
+                preview(&msg.text, 50),
                 msg.reply_count
             );
         }
 
-        // Fetch thread replies for each message that has them
-        let mut messages_with_replies = Vec::new();
-        for msg in &all_messages {
-            messages_with_replies.push(msg.clone());
-
-            // Check if message has thread replies
-            if let Some(reply_count) = msg.reply_count {
-                if reply_count > 0 {
-                    info!("[DEBUG] Message {} has {} thread replies, fetching them...",
-                        msg.ts, reply_count);
-
-                    // Fetch thread replies
-                    match self.get_thread_replies(channel_id, &msg.ts).await {
-                        Ok(replies) => {
-                            // Skip the first message as it's the parent message we already have
-                            let thread_replies: Vec<SlackMessage> = replies.into_iter()
-                                .skip(1)
-                                .collect();
+        let messages = with_thread_replies(all_messages, options.thread_replies, |thread_ts| async move {
+            self.get_thread_replies(channel_id, &thread_ts).await
+        })
+        .await;
 
-                            info!("[DEBUG] Retrieved {} thread replies", thread_replies.len());
-                            messages_with_replies.extend(thread_replies);
-                        }
-                        Err(e) => {
-                            warn!("[DEBUG] Failed to fetch thread replies: {}", e);
-                        }
-                    }
-                }
-            }
-        }
+        info!("[DEBUG] Messages sorted by timestamp (newest first)");
 
-        info!("[DEBUG] Total messages including thread replies: {} (was {} without replies)",
-            messages_with_replies.len(), all_messages.len());
-
-        // Sort messages by timestamp (newest first)
-        messages_with_replies.sort_by(|a, b| {
-            // Parse timestamps as floats for accurate comparison
-            let ts_a = a.ts.parse::<f64>().unwrap_or(0.0);
-            let ts_b = b.ts.parse::<f64>().unwrap_or(0.0);
-            // Reverse order for newest first
-            ts_b.partial_cmp(&ts_a).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        Ok(messages)
+    }
 
-        info!("[DEBUG] Messages sorted by timestamp (newest first)");
+    /// Fetch a whole thread via conversations.replies (the parent comes first)
+    pub async fn get_thread_replies(&self, channel_id: &str, thread_ts: &str) -> Result<Vec<SlackMessage>> {
+        info!("[DEBUG] Fetching thread replies for ts={}", thread_ts);
 
-        Ok(messages_with_replies)
+        collect_reply_pages(
+            |msg: &SlackMessage| msg.ts.clone(),
+            |cursor| self.get_thread_replies_page(channel_id, thread_ts, cursor),
+        )
+        .await
     }
 
-    async fn get_thread_replies(&self, channel_id: &str, thread_ts: &str) -> Result<Vec<SlackMessage>> {
+    /// One page of conversations.replies, with the cursor for the next
+    async fn get_thread_replies_page(
+        &self,
+        channel_id: &str,
+        thread_ts: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<SlackMessage>, Option<String>)> {
         let url = format!("{}/conversations.replies", SLACK_API_BASE);
 
         let mut params = HashMap::new();
         params.insert("channel", channel_id.to_string());
         params.insert("ts", thread_ts.to_string());
         params.insert("limit", "1000".to_string());
+        params.insert("include_all_metadata", "true".to_string());
+        if let Some(cursor) = cursor {
+            params.insert("cursor", cursor);
+        }
 
-        info!("[DEBUG] Fetching thread replies for ts={}", thread_ts);
-
-        let response = self.client.get(&url)
-            .query(&params)
-            .send()
-            .await?;
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let text = response.body;
             return Err(anyhow!("Failed to get thread replies: {} - {}", status, text));
         }
 
-        let response_text = response.text().await?;
+        let response_text = response.body;
 
         #[derive(Deserialize)]
         struct ConversationsRepliesResponse {
             ok: bool,
+            #[serde(default, deserialize_with = "deserialize_lenient_opt_vec")]
             messages: Option<Vec<SlackMessage>>,
             error: Option<String>,
+            has_more: Option<bool>,
+            response_metadata: Option<SlackResponseMetadata>,
         }
 
         let result: ConversationsRepliesResponse = serde_json::from_str(&response_text)?;
 
         if !result.ok {
             let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
-            return Err(anyhow!("Slack API error: {}", error_msg));
+            return Err(SlackApiError::from_response("conversations.replies", &error_msg, &response_text).into());
         }
 
-        Ok(result.messages.unwrap_or_default())
+        let next_cursor = match result.has_more {
+            Some(false) => None,
+            _ => result
+                .response_metadata
+                .and_then(|m| m.next_cursor)
+                .filter(|c| !c.is_empty()),
+        };
+        Ok((result.messages.unwrap_or_default(), next_cursor))
     }
 
     pub async fn get_channel_messages_with_reactions(
@@ -999,6 +1358,33 @@ impl SlackClient {
         oldest: Option<String>,
         latest: Option<String>,
         limit: usize,
+        options: HistoryOptions,
+    ) -> Result<Vec<SlackMessage>> {
+        let mut messages = with_auto_join(
+            options.auto_join,
+            || {
+                self.fetch_channel_history_with_reactions(
+                    channel_id,
+                    oldest.clone(),
+                    latest.clone(),
+                    limit,
+                    options.thread_replies,
+                )
+            },
+            || self.join_channel(channel_id),
+        )
+        .await?;
+        retain_exact_ts(&mut messages, oldest.as_deref(), latest.as_deref());
+        Ok(messages)
+    }
+
+    async fn fetch_channel_history_with_reactions(
+        &self,
+        channel_id: &str,
+        oldest: Option<String>,
+        latest: Option<String>,
+        limit: usize,
+        thread_replies: ThreadReplies,
     ) -> Result<Vec<SlackMessage>> {
         let url = format!("{}/conversations.history", SLACK_API_BASE);
 
@@ -1011,7 +1397,8 @@ impl SlackClient {
 
         info!("[DEBUG] Using limit {} per API request for reactions (total limit requested: {})", per_request_limit, limit);
         // NOTE: conversations.history DOES return reactions by default
-        // There is no include_all_metadata parameter - removing it
+        // Message metadata is only returned when asked for
+        params.insert("include_all_metadata", "true".to_string());
 
         if let Some(oldest_ts) = oldest {
             params.insert("oldest", oldest_ts);
@@ -1029,6 +1416,7 @@ impl SlackClient {
         #[derive(Deserialize)]
         struct ConversationsHistoryResponse {
             ok: bool,
+            #[serde(default, deserialize_with = "deserialize_lenient_opt_vec")]
             messages: Option<Vec<SlackMessage>>,
             error: Option<String>,
             has_more: Option<bool>,
@@ -1042,6 +1430,7 @@ impl SlackClient {
 
         // Initialize variables for pagination
         let mut all_messages = Vec::new();
+        let mut seen_ts = HashSet::new();
         let mut cursor: Option<String> = None;
         let mut total_api_calls = 0;
 
@@ -1057,17 +1446,16 @@ impl SlackClient {
             info!("API call {} for conversations.history with reactions (cursor: {:?})",
                 total_api_calls, cursor);
 
-            let response = self
+            let request = self
                 .client
                 .get(&url)
                 .header("Authorization", format!("Bearer {}", self.token))
-                .query(&current_params)
-                .send()
-                .await?;
+                .query(&current_params);
+            let response = self.send_with_backoff(request).await?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await?;
+            if !response.status.is_success() {
+                let status = response.status;
+                let text = response.body;
                 error!("Failed to get channel messages with reactions: {} - {}", status, text);
                 return Err(anyhow!(
                     "Failed to get channel messages with reactions: {} - {}",
@@ -1076,7 +1464,7 @@ impl SlackClient {
                 ));
             }
 
-            let response_text = response.text().await?;
+            let response_text = response.body;
             debug!("API Response size: {} bytes", response_text.len());
 
             // Debug: Check if raw response contains reactions
@@ -1091,7 +1479,7 @@ impl SlackClient {
             if !result.ok {
                 let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
                 error!("Slack API error: {}", error_msg);
-                return Err(anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("conversations.history", &error_msg, &response_text).into());
             }
 
             let messages = result.messages.unwrap_or_default();
@@ -1114,8 +1502,8 @@ impl SlackClient {
                 }
             }
 
-            // Add messages to our collection
-            all_messages.extend(messages);
+            // Add messages to our collection; `inclusive` can repeat the boundary message
+            extend_unique_ts(&mut all_messages, &mut seen_ts, messages);
 
             // Check if there are more messages to fetch
             let has_more = result.has_more.unwrap_or(false);
@@ -1151,12 +1539,31 @@ impl SlackClient {
         info!("Retrieved total of {} messages from conversations.history in {} API calls",
             all_messages.len(), total_api_calls);
 
+        // Merge thread replies first so replies missing reactions go through
+        // the same backfill as top-level messages
+        let fetched_count = all_messages.len();
+        let mut messages = with_thread_replies(all_messages, thread_replies, |thread_ts| async move {
+            self.get_thread_replies(channel_id, &thread_ts).await
+        })
+        .await;
+
+        info!("[DEBUG] Total messages including thread replies: {} (was {} without replies)",
+            messages.len(), fetched_count);
+
+        self.backfill_missing_reactions(channel_id, &mut messages).await;
+        info!("[DEBUG] Messages sorted by timestamp (newest first)");
+
+        Ok(messages)
+    }
+
+    /// Fetch reactions.get for messages (including merged thread replies) that came back without them
+    async fn backfill_missing_reactions(&self, channel_id: &str, messages: &mut [SlackMessage]) {
         // PERFORMANCE FIX: conversations.history DOES return reactions!
         // We already have them, so we only need to fetch for messages that don't have them
         // (which should be none if the API is working correctly)
-        let messages_without_reactions = all_messages.iter().filter(|m| m.reactions.is_none()).count();
+        let messages_without_reactions = missing_reaction_count(messages);
         info!("[REACTIONS OPTIMIZATION] {} of {} messages need reaction fetch (should be 0!)",
-              messages_without_reactions, all_messages.len());
+              messages_without_reactions, messages.len());
 
         // Only fetch reactions if some are missing (shouldn't happen with modern API)
         if messages_without_reactions > 0 {
@@ -1169,9 +1576,9 @@ impl SlackClient {
             const REACTION_BATCH_SIZE: usize = 20; // Increased batch size
             let channel_id_arc = Arc::new(channel_id.to_string());
 
-            for chunk_start in (0..all_messages.len()).step_by(REACTION_BATCH_SIZE) {
-                let chunk_end = std::cmp::min(chunk_start + REACTION_BATCH_SIZE, all_messages.len());
-                let chunk = &mut all_messages[chunk_start..chunk_end];
+            for chunk_start in (0..messages.len()).step_by(REACTION_BATCH_SIZE) {
+                let chunk_end = std::cmp::min(chunk_start + REACTION_BATCH_SIZE, messages.len());
+                let chunk = &mut messages[chunk_start..chunk_end];
 
                 // Prepare futures for parallel fetching
                 let mut futures = Vec::new();
@@ -1214,92 +1621,170 @@ impl SlackClient {
                 }
 
                 // Reduced delay between batches
-                if chunk_end < all_messages.len() {
+                if chunk_end < messages.len() {
                     sleep(Duration::from_millis(50)).await; // Reduced from 100ms
                 }
             }
         } else {
             info!("[REACTIONS OPTIMIZATION] All reactions already included in API response!");
         }
+    }
 
-        // Fetch thread replies for each message that has them
-        let mut messages_with_replies = Vec::new();
-        for msg in &all_messages {
-            messages_with_replies.push(msg.clone());
-
-            // Check if message has thread replies
-            if let Some(reply_count) = msg.reply_count {
-                if reply_count > 0 {
-                    info!("[DEBUG] Message {} has {} thread replies, fetching them...",
-                        msg.ts, reply_count);
+    pub async fn test_auth(&self) -> Result<(bool, Option<String>)> {
+        info!("Testing Slack authentication");
 
-                    // Fetch thread replies
-                    match self.get_thread_replies(channel_id, &msg.ts).await {
-                        Ok(replies) => {
-                            // Skip the first message as it's the parent message we already have
-                            let thread_replies: Vec<SlackMessage> = replies.into_iter()
-                                .skip(1)
-                                .collect();
+        let result = self.auth_test().await?;
 
-                            info!("[DEBUG] Retrieved {} thread replies", thread_replies.len());
-                            messages_with_replies.extend(thread_replies);
-                        }
-                        Err(e) => {
-                            warn!("[DEBUG] Failed to fetch thread replies: {}", e);
-                        }
-                    }
-                }
-            }
+        if result.ok {
+            info!("Slack authentication successful, user_id: {:?}", result.identity.user_id);
+        } else {
+            let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+            error!("Slack auth test failed: {}", error_msg);
         }
 
-        info!("[DEBUG] Total messages including thread replies: {} (was {} without replies)",
-            messages_with_replies.len(), all_messages.len());
-
-        // Sort messages by timestamp (newest first)
-        messages_with_replies.sort_by(|a, b| {
-            // Parse timestamps as floats for accurate comparison
-            let ts_a = a.ts.parse::<f64>().unwrap_or(0.0);
-            let ts_b = b.ts.parse::<f64>().unwrap_or(0.0);
-            // Reverse order for newest first
-            ts_b.partial_cmp(&ts_a).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        Ok((result.ok, result.identity.user_id))
+    }
 
-        info!("[DEBUG] Messages sorted by timestamp (newest first)");
+    /// Who the token belongs to (user, team and workspace domain), from auth.test
+    pub async fn auth_identity(&self) -> Result<AuthIdentity> {
+        let result = self.auth_test().await?;
+        if !result.ok {
+            let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+            return Err(SlackApiError::from_code("auth.test", &error_msg).into());
+        }
 
-        Ok(messages_with_replies)
+        Ok(result.identity)
     }
 
-    pub async fn test_auth(&self) -> Result<(bool, Option<String>)> {
+    /// Call auth.test, reporting a failed HTTP status as `ok: false`
+    async fn auth_test(&self) -> Result<AuthTestResponse> {
         let url = format!("{}/auth.test", SLACK_API_BASE);
+        let response = self.send_with_backoff(self.client.get(&url)).await?;
+
+        if !response.status.is_success() {
+            error!("Auth test failed with status: {}", response.status);
+            return Ok(AuthTestResponse {
+                ok: false,
+                error: Some(format!("HTTP {}", response.status)),
+                identity: AuthIdentity::default(),
+            });
+        }
 
-        info!("Testing Slack authentication");
+        Ok(serde_json::from_str(&response.body)?)
+    }
 
-        let response = self.client.get(&url).send().await?;
+    /// The permalink for a message, from chat.getPermalink
+    pub async fn get_permalink(&self, channel: &str, ts: &str) -> Result<String> {
+        let url = format!("{}/chat.getPermalink", SLACK_API_BASE);
+        let builder = self
+            .client
+            .get(&url)
+            .query(&[("channel", channel), ("message_ts", ts)]);
+        let response = self.send_with_backoff(builder).await?;
 
-        if !response.status().is_success() {
-            error!("Auth test failed with status: {}", response.status());
-            return Ok((false, None));
+        if !response.status.is_success() {
+            return Err(anyhow!("Failed to get permalink: {}", response.status));
         }
 
         #[derive(Deserialize)]
-        struct AuthTestResponse {
+        struct PermalinkResponse {
             ok: bool,
             #[serde(default)]
             error: Option<String>,
             #[serde(default)]
-            user_id: Option<String>,
+            permalink: Option<String>,
         }
 
-        let result: AuthTestResponse = response.json().await?;
-
-        if result.ok {
-            info!("Slack authentication successful, user_id: {:?}", result.user_id);
-        } else {
+        let result: PermalinkResponse = serde_json::from_str(&response.body)?;
+        if !result.ok {
             let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
-            error!("Slack auth test failed: {}", error_msg);
+            return Err(SlackApiError::from_response("chat.getPermalink", &error_msg, &response.body).into());
+        }
+
+        result
+            .permalink
+            .ok_or_else(|| anyhow!("chat.getPermalink returned no permalink"))
+    }
+
+    /// Channels muted in the user's Slack preferences
+    ///
+    /// users.prefs is undocumented and many tokens can't call it, so callers
+    /// should treat any error as "mute sync unavailable". A response without
+    /// the pref is an error too, so the list is never rewritten from nothing.
+    pub async fn get_muted_channels(&self) -> Result<Vec<String>> {
+        let url = format!("{}/users.prefs.get", SLACK_API_BASE);
+        let response = self.send_with_backoff(self.client.get(&url)).await?;
+
+        if !response.status.is_success() {
+            return Err(AppError::http_status(response.status.as_u16(), &response.body).into());
+        }
+
+        parse_muted_channels(&response.body)
+    }
+
+    /// Replace the muted channel list in the user's Slack preferences
+    pub async fn set_muted_channels(&self, channels: &[String]) -> Result<()> {
+        let url = format!("{}/users.prefs.set", SLACK_API_BASE);
+        let params = [("name", "muted_channels".to_string()), ("value", channels.join(","))];
+        let response = self.send_with_backoff(self.client.post(&url).form(&params)).await?;
+
+        if !response.status.is_success() {
+            return Err(AppError::http_status(response.status.as_u16(), &response.body).into());
+        }
+
+        let result: serde_json::Value = serde_json::from_str(&response.body)?;
+        if !result.get("ok").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let error_msg = result
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown error");
+            return Err(SlackApiError::from_response("users.prefs.set", error_msg, &response.body).into());
         }
 
-        Ok((result.ok, result.user_id))
+        Ok(())
+    }
+
+    /// The team a DM belongs to, when that's not the token's home team
+    ///
+    /// Only Enterprise Grid DMs can live in another team; everywhere else this
+    /// is `None`, as it is when either lookup fails.
+    async fn dm_team_context(&self, dm_id: &str, home_team: Option<&str>) -> Option<String> {
+        let (dm, _) = self.probe_channel(dm_id).await.ok()?;
+        cross_team_context(&dm?, home_team)
+    }
+
+    /// Join a public channel so its history becomes readable
+    pub async fn join_channel(&self, channel_id: &str) -> Result<()> {
+        let _permit = self.rate_limiter.acquire().await?;
+
+        let url = format!("{}/conversations.join", SLACK_API_BASE);
+        let params = serde_json::json!({ "channel": channel_id });
+
+        info!("Joining channel {}", channel_id);
+
+        let request = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .json(&params);
+        let response = self.send_with_backoff(request).await?;
+
+        if !response.status.is_success() {
+            let error_text = response.body;
+            return Err(anyhow!("Failed to join channel: {}", error_text));
+        }
+
+        let result: serde_json::Value = serde_json::from_str(&response.body)?;
+        if !result.get("ok").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let error_msg = result
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown error");
+            error!("Failed to join channel {}: {}", channel_id, error_msg);
+            return Err(SlackApiError::from_response("conversations.join", &error_msg, &response.body).into());
+        }
+
+        Ok(())
     }
 
     pub async fn add_reaction(&self, channel: &str, timestamp: &str, emoji: &str) -> Result<()> {
@@ -1312,27 +1797,26 @@ impl SlackClient {
             "name": emoji
         });
 
-        let response = self
+        let request = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.token))
-            .json(&params)
-            .send()
-            .await?;
+            .json(&params);
+        let response = self.send_with_backoff(request).await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
+        if !response.status.is_success() {
+            let error_text = response.body;
             return Err(anyhow::anyhow!("Failed to add reaction: {}", error_text));
         }
 
-        let result: serde_json::Value = response.json().await?;
+        let result: serde_json::Value = serde_json::from_str(&response.body)?;
         if let Some(ok) = result.get("ok").and_then(|v| v.as_bool()) {
             if !ok {
                 let error_msg = result
                     .get("error")
                     .and_then(|v| v.as_str())
                     .unwrap_or("Unknown error");
-                return Err(anyhow::anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("reactions.add", &error_msg, &response.body).into());
             }
         }
 
@@ -1349,145 +1833,254 @@ impl SlackClient {
             "name": emoji
         });
 
-        let response = self
+        let request = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.token))
-            .json(&params)
-            .send()
-            .await?;
+            .json(&params);
+        let response = self.send_with_backoff(request).await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
+        if !response.status.is_success() {
+            let error_text = response.body;
             return Err(anyhow::anyhow!("Failed to remove reaction: {}", error_text));
         }
 
-        let result: serde_json::Value = response.json().await?;
+        let result: serde_json::Value = serde_json::from_str(&response.body)?;
         if let Some(ok) = result.get("ok").and_then(|v| v.as_bool()) {
             if !ok {
                 let error_msg = result
                     .get("error")
                     .and_then(|v| v.as_str())
                     .unwrap_or("Unknown error");
-                return Err(anyhow::anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("reactions.remove", &error_msg, &response.body).into());
             }
         }
 
         Ok(())
     }
 
-    pub async fn get_reactions(
-        &self,
-        channel: &str,
-        timestamp: &str,
-    ) -> Result<Vec<SlackReaction>> {
-        // DEBUG: Log every reaction fetch attempt
-        debug!("get_reactions called for channel: {} timestamp: {}", channel, timestamp);
-
-        // NOTE: Removed the DM channel skip logic as Slack API supports reactions in DMs
-        // Previously this code was skipping all DM channels, but reactions work in DMs too
-        info!("DEBUG: Proceeding with reaction fetch for channel: {} (type: {})",
-              channel,
-              if channel.starts_with('D') { "DM" }
-              else if channel.starts_with('G') { "Group_DM" }
-              else if channel.starts_with('C') { "Channel" }
-              else { "Unknown" });
+    /// Links on a channel's bookmark bar, via bookmarks.list
+    pub async fn get_bookmarks(&self, channel_id: &str) -> Result<Vec<SlackBookmark>> {
+        let url = format!("{}/bookmarks.list", SLACK_API_BASE);
 
-        let _ = self.rate_limiter.acquire().await;
+        let mut params = HashMap::new();
+        params.insert("channel_id", channel_id.to_string());
 
-        let url = format!("{}/reactions.get", SLACK_API_BASE);
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .query(&[
-                ("channel", channel),
-                ("timestamp", timestamp),
-                ("full", "true"),
-            ])
-            .send()
-            .await?;
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            // Handle rate limiting specifically
-            if status == 429 {
-                // Wait a bit and return empty to avoid cascading failures
-                sleep(Duration::from_millis(100)).await;
-                return Ok(vec![]);
-            }
-            return Err(anyhow::anyhow!("Failed to get reactions: {}", error_text));
+        if !response.status.is_success() {
+            return Err(AppError::http_status(response.status.as_u16(), &response.body).into());
         }
 
-        let result: serde_json::Value = response.json().await?;
-        if let Some(ok) = result.get("ok").and_then(|v| v.as_bool()) {
-            if !ok {
-                let error_msg = result
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error");
+        let bookmarks = parse_bookmarks(&response.body)?;
+        debug!("Channel {} has {} bookmarks", channel_id, bookmarks.len());
+        Ok(bookmarks)
+    }
 
-                info!("DEBUG: get_reactions API error for channel {}: {}", channel, error_msg);
+    /// Messages pinned in a channel, via pins.list (pinned files are skipped)
+    pub async fn get_pinned_messages(&self, channel: &str) -> Result<Vec<SlackMessage>> {
+        let url = format!("{}/pins.list", SLACK_API_BASE);
 
-                // Handle "no_reaction" as normal case - message has no reactions
-                if error_msg.contains("no_reaction") {
-                    debug!("Message has no reactions: {}", channel);
-                    return Ok(vec![]);
-                }
-                // Handle "channel_not_found" - this might indicate permission issues
-                if error_msg == "channel_not_found" {
-                    info!("Channel not found for reactions (permission issue?): {}", channel);
-                    return Ok(vec![]);
-                }
-                // Handle other known errors gracefully
-                if error_msg == "message_not_found" {
-                    debug!("Message not found for reactions: {}", channel);
-                    return Ok(vec![]);
-                }
+        let mut params = HashMap::new();
+        params.insert("channel", channel.to_string());
 
-                // Log unexpected errors but don't fail completely
-                error!("Unexpected reaction API error for channel {}: {}", channel, error_msg);
-                return Ok(vec![]);
-            }
-        }
+        let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
 
-        let reactions = result
-            .get("message")
-            .and_then(|msg| msg.get("reactions"))
-            .and_then(|r| serde_json::from_value::<Vec<SlackReaction>>(r.clone()).ok())
-            .unwrap_or_default();
+        if !response.status.is_success() {
+            return Err(AppError::http_status(response.status.as_u16(), &response.body).into());
+        }
+
+        let messages = parse_pinned_messages(&response.body)?;
+        debug!("Channel {} has {} pinned messages", channel, messages.len());
+        Ok(messages)
+    }
+
+    /// Pin a message; pinning an already pinned message is a no-op
+    pub async fn pin_message(&self, channel: &str, timestamp: &str) -> Result<()> {
+        self.change_pin("pins.add", channel, timestamp, "already_pinned").await
+    }
+
+    /// Unpin a message; unpinning a message that isn't pinned is a no-op
+    pub async fn unpin_message(&self, channel: &str, timestamp: &str) -> Result<()> {
+        self.change_pin("pins.remove", channel, timestamp, "no_pin").await
+    }
+
+    /// Call pins.add or pins.remove, treating `noop_error` as success
+    async fn change_pin(&self, method: &str, channel: &str, timestamp: &str, noop_error: &str) -> Result<()> {
+        let _permit = self.rate_limiter.acquire().await?;
+
+        let url = format!("{}/{}", SLACK_API_BASE, method);
+        let params = serde_json::json!({
+            "channel": channel,
+            "timestamp": timestamp
+        });
+
+        let response = self.send_with_backoff(self.client.post(&url).json(&params)).await?;
+
+        if !response.status.is_success() {
+            return Err(AppError::http_status(response.status.as_u16(), &response.body).into());
+        }
+
+        let result: serde_json::Value = serde_json::from_str(&response.body)?;
+        if result.get("ok").and_then(|v| v.as_bool()) == Some(false) {
+            let error_msg = result
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown error");
+            if error_msg == noop_error {
+                debug!("{} on {}:{} was a no-op ({})", method, channel, timestamp, error_msg);
+                return Ok(());
+            }
+            return Err(SlackApiError::from_response(method, error_msg, &response.body).into());
+        }
+
+        info!("{} succeeded for {}:{}", method, channel, timestamp);
+        Ok(())
+    }
+
+    /// Reactions on a message, treating lookup failures as "no reactions"
+    ///
+    /// Good enough for decorating results, where one unreadable message
+    /// shouldn't fail the whole list. Only a missing scope is surfaced, since
+    /// it affects every message. Use `get_reactions_checked` when an empty list
+    /// would be taken as fact.
+    pub async fn get_reactions(
+        &self,
+        channel: &str,
+        timestamp: &str,
+    ) -> Result<Vec<SlackReaction>> {
+        match self.get_reactions_checked(channel, timestamp).await {
+            Err(e) => match SlackApiError::of(&e) {
+                None | Some(SlackApiError::MissingScope { .. }) => Err(e),
+                // Handle "channel_not_found" - this might indicate permission issues
+                Some(SlackApiError::ChannelNotFound) => {
+                    info!("Channel not found for reactions (permission issue?): {}", channel);
+                    Ok(vec![])
+                }
+                // Log unexpected errors but don't fail completely
+                Some(api_error) => {
+                    error!("Unexpected reaction API error for channel {}: {}", channel, api_error);
+                    Ok(vec![])
+                }
+            },
+            reactions => reactions,
+        }
+    }
+
+    /// Reactions on a message, failing on any Slack error except `no_reaction`
+    pub async fn get_reactions_checked(
+        &self,
+        channel: &str,
+        timestamp: &str,
+    ) -> Result<Vec<SlackReaction>> {
+        // DEBUG: Log every reaction fetch attempt
+        debug!("get_reactions called for channel: {} timestamp: {}", channel, timestamp);
+
+        // NOTE: Removed the DM channel skip logic as Slack API supports reactions in DMs
+        // Previously this code was skipping all DM channels, but reactions work in DMs too
+        info!("DEBUG: Proceeding with reaction fetch for channel: {} (type: {})",
+              channel,
+              if channel.starts_with('D') { "DM" }
+              else if channel.starts_with('G') { "Group_DM" }
+              else if channel.starts_with('C') { "Channel" }
+              else { "Unknown" });
+
+        let _ = self.rate_limiter.acquire().await;
+
+        let url = format!("{}/reactions.get", SLACK_API_BASE);
+        let request = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .query(&[
+                ("channel", channel),
+                ("timestamp", timestamp),
+                ("full", "true"),
+            ]);
+        let response = self.send_with_backoff(request).await?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let error_text = response.body;
+            return Err(anyhow::anyhow!("Failed to get reactions: {} - {}", status, error_text));
+        }
+
+        let mut reactions = parse_reactions(&response.body).map_err(|e| {
+            info!("DEBUG: get_reactions API error for channel {}: {}", channel, e);
+            e
+        })?;
+        for reaction in &mut reactions {
+            reaction.cap_users(self.reactor_cap);
+        }
 
         info!("DEBUG: get_reactions for {} found {} reactions", channel, reactions.len());
 
         Ok(reactions)
     }
 
+    /// Call an arbitrary Web API method and return Slack's raw JSON response
+    ///
+    /// Params are sent form-encoded, which every Web API method accepts.
+    /// Slack-level failures (`ok: false`) are returned as-is for the caller to inspect.
+    pub async fn call_method(
+        &self,
+        method: &str,
+        params: &[(String, String)],
+    ) -> Result<serde_json::Value> {
+        let _permit = self.rate_limiter.acquire().await?;
+        let url = format!("{}/{}", SLACK_API_BASE, method);
+
+        info!("Calling Slack method: {} ({} params)", method, params.len());
+
+        let response = self.send_with_backoff(self.client.post(&url).form(params)).await?;
+
+        let status = response.status;
+        if !status.is_success() {
+            let text = response.body;
+            error!("Slack method {} failed: {} - {}", method, status, text);
+            return Err(AppError::http_status(status.as_u16(), &text).into());
+        }
+
+        Ok(serde_json::from_str(&response.body)?)
+    }
+
     /// Post a message to a Slack channel
     pub async fn post_message(
         &self,
         channel: &str,
         text: &str,
         thread_ts: Option<&str>,
+    ) -> Result<crate::slack::models::PostMessageResponse> {
+        let request = crate::slack::models::PostMessageRequest {
+            channel: channel.to_string(),
+            text: text.to_string(),
+            thread_ts: thread_ts.map(|ts| ts.to_string()),
+            username: None,
+            icon_emoji: None,
+            icon_url: None,
+        };
+
+        self.post_message_request(&request).await
+    }
+
+    /// Post a message using a fully specified chat.postMessage request
+    ///
+    /// Used when posting with a username/icon override, which Slack only
+    /// honours for bot tokens.
+    pub async fn post_message_request(
+        &self,
+        request: &crate::slack::models::PostMessageRequest,
     ) -> Result<crate::slack::models::PostMessageResponse> {
         let _permit = self.rate_limiter.acquire().await?;
         let url = format!("{}/chat.postMessage", SLACK_API_BASE);
+        let channel = request.channel.as_str();
 
         info!("Posting message to channel: {}", channel);
 
-        let mut body = serde_json::json!({
-            "channel": channel,
-            "text": text
-        });
-
-        if let Some(ts) = thread_ts {
-            body["thread_ts"] = serde_json::json!(ts);
-        }
-
-        let response = self.client.post(&url).json(&body).send().await?;
+        let response = self.send_with_backoff(self.client.post(&url).json(request)).await?;
 
-        let status = response.status();
-        let response_text = response.text().await?;
+        let status = response.status;
+        let response_text = response.body;
 
         if !status.is_success() {
             error!(
@@ -1512,7 +2105,58 @@ impl SlackClient {
                 .clone()
                 .unwrap_or_else(|| "Unknown error".to_string());
             error!("Slack API error: {}", error_msg);
-            return Err(anyhow::anyhow!("Slack API error: {}", error_msg));
+            return Err(SlackApiError::from_response("chat.postMessage", &error_msg, &response_text).into());
+        }
+
+        Ok(result)
+    }
+
+    /// Edit a message the user posted, via chat.update
+    pub async fn update_message(
+        &self,
+        channel: &str,
+        ts: &str,
+        new_text: &str,
+    ) -> Result<crate::slack::models::PostMessageResponse> {
+        let _permit = self.rate_limiter.acquire().await?;
+        let url = format!("{}/chat.update", SLACK_API_BASE);
+        let body = serde_json::json!({
+            "channel": channel,
+            "ts": ts,
+            "text": new_text,
+        });
+
+        info!("Updating message {} in channel: {}", ts, channel);
+
+        let response = self.send_with_backoff(self.client.post(&url).json(&body)).await?;
+
+        let status = response.status;
+        let response_text = response.body;
+
+        if !status.is_success() {
+            error!(
+                "Failed to update message. Status: {}, Response: {}",
+                status, response_text
+            );
+            return Err(anyhow::anyhow!("Failed to update message: {}", response_text));
+        }
+
+        let result: crate::slack::models::PostMessageResponse =
+            serde_json::from_str(&response_text).map_err(|e| {
+                error!("Failed to parse update message response: {}", e);
+                error!("Response text: {}", response_text);
+                anyhow::anyhow!("Failed to parse response: {}", e)
+            })?;
+
+        if result.ok {
+            info!("Successfully updated message {} in channel: {}", ts, channel);
+        } else {
+            let error_msg = result
+                .error
+                .clone()
+                .unwrap_or_else(|| "Unknown error".to_string());
+            error!("Slack API error: {}", error_msg);
+            return Err(SlackApiError::from_response("chat.update", &error_msg, &response_text).into());
         }
 
         Ok(result)
@@ -1544,10 +2188,10 @@ impl SlackClient {
             }
         }
 
-        let response = self.client.post(&url).json(&body).send().await?;
+        let response = self.send_with_backoff(self.client.post(&url).json(&body)).await?;
 
-        let status = response.status();
-        let response_text = response.text().await?;
+        let status = response.status;
+        let response_text = response.body;
 
         if !status.is_success() {
             error!(
@@ -1572,7 +2216,7 @@ impl SlackClient {
                 .clone()
                 .unwrap_or_else(|| "Unknown error".to_string());
             error!("Slack API error: {}", error_msg);
-            return Err(anyhow::anyhow!("Slack API error: {}", error_msg));
+            return Err(SlackApiError::from_response("chat.postMessage", &error_msg, &response_text).into());
         }
 
         Ok(result)
@@ -1583,24 +2227,24 @@ impl SlackClient {
         
         debug!("Fetching emoji list from Slack");
         
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_with_backoff(self.client.get(&url)).await?;
         
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let text = response.body;
             error!("Slack API error when fetching emojis: {} - {}", status, text);
             
             if status == 401 {
-                return Err(anyhow!(
-                    "Authentication failed. Your Slack token may be invalid or expired."
-                ));
+                return Err(AppError::AuthError(
+                    "Authentication failed. Your Slack token may be invalid or expired.".to_string(),
+                ).into());
             } else if status == 403 {
                 return Err(anyhow!(
                     "Access denied. Your token may not have the required permissions for emoji.list."
                 ));
             }
             
-            return Err(anyhow!("Slack API error: {} - {}", status, text));
+            return Err(AppError::http_status(status.as_u16(), &text).into());
         }
         
         #[derive(Deserialize)]
@@ -1610,23 +2254,13 @@ impl SlackClient {
             error: Option<String>,
         }
         
-        let result: EmojiListResponse = response.json().await?;
+        let result: EmojiListResponse = serde_json::from_str(&response.body)?;
         
         if !result.ok {
             let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
             error!("Slack API returned error for emoji.list: {}", error_msg);
-            
-            if error_msg.contains("invalid_auth") {
-                return Err(anyhow!(
-                    "Invalid authentication token. Please check your Slack token in Settings."
-                ));
-            } else if error_msg.contains("missing_scope") {
-                return Err(anyhow!(
-                    "Your token doesn't have the required permissions. Please ensure it has 'emoji:read' scope."
-                ));
-            }
-            
-            return Err(anyhow!("Slack API error: {}", error_msg));
+
+            return Err(SlackApiError::from_response("emoji.list", &error_msg, &response.body).into());
         }
         
         let emoji_map = result.emoji.unwrap_or_default();
@@ -1635,6 +2269,197 @@ impl SlackClient {
         Ok(emoji_map)
     }
 
+    /// List the workspace's user groups so subteam mentions can be resolved
+    pub async fn get_usergroups(&self) -> Result<Vec<SlackUsergroup>> {
+        let url = format!("{}/usergroups.list", SLACK_API_BASE);
+
+        debug!("Fetching user groups from Slack");
+
+        let response = self.send_with_backoff(self.client.get(&url)).await?;
+
+        if !response.status.is_success() {
+            let status = response.status;
+            let text = response.body;
+            error!("Slack API error when fetching user groups: {} - {}", status, text);
+            return Err(AppError::http_status(status.as_u16(), &text).into());
+        }
+
+        #[derive(Deserialize)]
+        struct UsergroupsListResponse {
+            ok: bool,
+            usergroups: Option<Vec<SlackUsergroup>>,
+            error: Option<String>,
+        }
+
+        let result: UsergroupsListResponse = serde_json::from_str(&response.body)?;
+
+        if !result.ok {
+            let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+            error!("Slack API returned error for usergroups.list: {}", error_msg);
+
+            return Err(SlackApiError::from_response("usergroups.list", &error_msg, &response.body).into());
+        }
+
+        let usergroups = result.usergroups.unwrap_or_default();
+        info!("Successfully fetched {} user groups", usergroups.len());
+
+        Ok(usergroups)
+    }
+
+    /// Threads the user follows, via subscriptions.thread.getView
+    ///
+    /// The method is undocumented and many tokens can't call it; errors other
+    /// than network/auth failures mean the view is unavailable.
+    pub async fn get_followed_threads(&self) -> Result<Vec<SlackFollowedThread>> {
+        let url = format!("{}/subscriptions.thread.getView", SLACK_API_BASE);
+
+        debug!("Fetching followed threads from Slack");
+
+        let response = self.send_with_backoff(self.client.get(&url)).await?;
+
+        if !response.status.is_success() {
+            return Err(AppError::http_status(response.status.as_u16(), &response.body).into());
+        }
+
+        #[derive(Deserialize)]
+        struct ThreadViewResponse {
+            ok: bool,
+            #[serde(default, deserialize_with = "deserialize_lenient_vec")]
+            threads: Vec<SlackFollowedThread>,
+            error: Option<String>,
+        }
+
+        let result: ThreadViewResponse = serde_json::from_str(&response.body)?;
+
+        if !result.ok {
+            let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+            return Err(SlackApiError::from_response("subscriptions.thread.getView", &error_msg, &response.body).into());
+        }
+
+        info!("Fetched {} followed threads", result.threads.len());
+        Ok(result.threads)
+    }
+
+    /// Channels `user_id` is a member of, via users.conversations
+    ///
+    /// Private channels only show up when the token's user shares them.
+    pub async fn get_user_conversations(&self, user_id: &str) -> Result<Vec<SlackConversation>> {
+        let url = format!("{}/users.conversations", SLACK_API_BASE);
+        let mut channels = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut params = HashMap::new();
+            params.insert("user", user_id.to_string());
+            params.insert("types", "public_channel,private_channel".to_string());
+            params.insert("exclude_archived", "true".to_string());
+            params.insert("limit", "200".to_string());
+            if let Some(ref c) = cursor {
+                params.insert("cursor", c.clone());
+            }
+
+            let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
+
+            if !response.status.is_success() {
+                return Err(anyhow!("Failed to get user conversations: {}", response.status));
+            }
+
+            let (page, next_cursor) = parse_user_conversations(&response.body)?;
+            channels.extend(page);
+
+            cursor = next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        debug!("User {} is a member of {} channels", user_id, channels.len());
+        Ok(channels)
+    }
+
+    /// Member user IDs of a channel, paging through conversations.members
+    pub async fn get_channel_members(&self, channel_id: &str) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct MembersResponse {
+            ok: bool,
+            #[serde(default)]
+            members: Vec<String>,
+            error: Option<String>,
+            response_metadata: Option<SlackResponseMetadata>,
+        }
+
+        let url = format!("{}/conversations.members", SLACK_API_BASE);
+        let mut members = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut params = HashMap::new();
+            params.insert("channel", channel_id.to_string());
+            params.insert("limit", "1000".to_string());
+            if let Some(ref c) = cursor {
+                params.insert("cursor", c.clone());
+            }
+
+            let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
+
+            if !response.status.is_success() {
+                return Err(anyhow!("Failed to get channel members: {}", response.status));
+            }
+
+            let result: MembersResponse = serde_json::from_str(&response.body)?;
+            if !result.ok {
+                let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+                return Err(SlackApiError::from_response("conversations.members", &error_msg, &response.body).into());
+            }
+            members.extend(result.members);
+
+            cursor = result
+                .response_metadata
+                .and_then(|m| m.next_cursor)
+                .filter(|c| !c.is_empty());
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// `(channel, ts)` of every message the user has saved, paging through stars.list
+    pub async fn get_saved_message_keys(&self) -> Result<HashSet<MessageKey>> {
+        let url = format!("{}/stars.list", SLACK_API_BASE);
+        let mut saved = HashSet::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut params = HashMap::new();
+            params.insert("limit", "1000".to_string());
+            if let Some(ref c) = cursor {
+                params.insert("cursor", c.clone());
+            }
+
+            let response = self.send_with_backoff(self.client.get(&url).query(&params)).await?;
+
+            if !response.status.is_success() {
+                let status = response.status;
+                let text = response.body;
+                error!("Slack API error when fetching saved items: {} - {}", status, text);
+                return Err(AppError::http_status(status.as_u16(), &text).into());
+            }
+
+            let (keys, next_cursor) = parse_starred_messages(&response.body)?;
+            saved.extend(keys);
+
+            cursor = next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        debug!("Found {} saved messages", saved.len());
+        Ok(saved)
+    }
+
     /// Mark a conversation as read up to a specific timestamp
     ///
     /// Sets the read cursor in a channel, marking all messages up to and including
@@ -1674,38 +2499,33 @@ impl SlackClient {
 
         info!("Marking conversation as read: channel={}, ts={}", channel, ts);
 
-        let response = self
+        let request = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.token))
-            .json(&params)
-            .send()
-            .await?;
+            .json(&params);
+        let response = self.send_with_backoff(request).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let error_text = response.body;
             error!("Failed to mark as read: {} - {}", status, error_text);
 
             // Provide specific error messages
             if status == 401 {
-                return Err(anyhow!(
-                    "Authentication failed. Your Slack token may be invalid or expired."
-                ));
+                return Err(AppError::AuthError(
+                    "Authentication failed. Your Slack token may be invalid or expired.".to_string(),
+                ).into());
             } else if status == 403 {
                 return Err(anyhow!(
                     "Access denied. You may not have permission to mark this channel as read."
                 ));
-            } else if status == 429 {
-                return Err(anyhow!(
-                    "Rate limit exceeded. Please wait a moment and try again."
-                ));
             }
 
             return Err(anyhow!("Failed to mark as read: {}", error_text));
         }
 
-        let result: serde_json::Value = response.json().await?;
+        let result: serde_json::Value = serde_json::from_str(&response.body)?;
         if let Some(ok) = result.get("ok").and_then(|v| v.as_bool()) {
             if !ok {
                 let error_msg = result
@@ -1715,21 +2535,13 @@ impl SlackClient {
                 error!("Slack API error when marking as read: {}", error_msg);
 
                 // Provide specific error messages based on Slack error codes
-                if error_msg.contains("invalid_auth") {
-                    return Err(anyhow!(
-                        "Invalid authentication token. Please check your Slack token in Settings."
-                    ));
-                } else if error_msg.contains("channel_not_found") {
-                    return Err(anyhow!(
-                        "Channel not found. The channel may have been deleted or you may not have access."
-                    ));
-                } else if error_msg.contains("invalid_timestamp") {
+                if error_msg.contains("invalid_timestamp") {
                     return Err(anyhow!(
                         "Invalid timestamp. The message may not exist."
                     ));
                 }
 
-                return Err(anyhow!("Slack API error: {}", error_msg));
+                return Err(SlackApiError::from_response("conversations.mark", &error_msg, &response.body).into());
             }
         }
 
@@ -1739,6 +2551,17 @@ impl SlackClient {
 }
 
 // Helper functions for building search queries
+/// Whether a time-bearing date is exactly midnight (`2024-01-06T00:00:00Z`)
+fn is_midnight(date: &str) -> bool {
+    chrono::DateTime::parse_from_rfc3339(date)
+        .is_ok_and(|dt| dt.time() == chrono::NaiveTime::MIN)
+}
+
+/// Whether `value` is a Slack user ID (U followed by alphanumerics) rather than a name
+pub(crate) fn is_user_id(value: &str) -> bool {
+    value.starts_with('U') && value.len() > 8 && value.chars().skip(1).all(|c| c.is_alphanumeric())
+}
+
 pub fn build_search_query(params: &SearchRequest) -> String {
     let mut query_parts = Vec::new();
     let has_text_query = !params.query.trim().is_empty();
@@ -1824,14 +2647,15 @@ pub fn build_search_query(params: &SearchRequest) -> String {
                 trimmed.trim_start_matches('@')
             };
             if !clean_user.is_empty() {
-                // Check if it's a user ID (starts with U and followed by alphanumeric)
-                if clean_user.starts_with('U')
-                    && clean_user.len() > 8
-                    && clean_user.chars().skip(1).all(|c| c.is_alphanumeric())
-                {
+                if is_user_id(clean_user) {
                     // For user IDs, use plain format (no brackets)
                     info!("Using user ID format for search: {}", clean_user);
                     query_parts.push(format!("from:{}", clean_user));
+                } else if clean_user.contains(char::is_whitespace) {
+                    // A display name the caller couldn't resolve to an ID; unquoted,
+                    // only its first word would be taken as the user
+                    warn!("Unresolved multi-word user '{}' in search, quoting it", clean_user);
+                    query_parts.push(format!("from:\"{}\"", clean_user));
                 } else {
                     // For usernames, use the plain format
                     info!("Using username format for search: {}", clean_user);
@@ -1846,6 +2670,9 @@ pub fn build_search_query(params: &SearchRequest) -> String {
     // So to include a date, we need to use the day before as the "after" value
     // Similarly, "before:" is EXCLUSIVE (does not include the specified date)
     // So to include a date, we need to use the day after as the "before" value
+    // With dates_are_exact, the window is only widened as far as whole days need;
+    // the caller trims results to the exact timestamps.
+    let dates_are_exact = params.dates_are_exact.unwrap_or(false);
     if let Some(from) = &params.from_date {
         // Handle both ISO datetime (with T) and simple date (YYYY-MM-DD) formats
         let date_str = if from.contains('T') {
@@ -1858,7 +2685,7 @@ pub fn build_search_query(params: &SearchRequest) -> String {
 
         // Parse the date
         if let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-            if params.is_realtime.unwrap_or(false) {
+            if params.is_realtime.unwrap_or(false) && !dates_are_exact {
                 // For realtime/live mode: use yesterday as the "after" date to get today's messages
                 // This ensures we only get messages from today (midnight onwards)
                 let yesterday = date - chrono::Duration::days(1);
@@ -1894,7 +2721,7 @@ pub fn build_search_query(params: &SearchRequest) -> String {
     // Don't add to_date filter if already added by realtime mode
     if let Some(to) = &params.to_date {
         // Skip if realtime mode already added a before filter
-        if !params.is_realtime.unwrap_or(false) {
+        if !params.is_realtime.unwrap_or(false) || dates_are_exact {
             // Handle both ISO datetime (with T) and simple date (YYYY-MM-DD) formats
             let date_str = if to.contains('T') {
                 // ISO datetime format - extract date part
@@ -1906,8 +2733,13 @@ pub fn build_search_query(params: &SearchRequest) -> String {
 
             // Parse the date
             if let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                // Add one day to make it inclusive (since "before:" is exclusive)
-                let day_after = date + chrono::Duration::days(1);
+                // Add one day to make it inclusive (since "before:" is exclusive),
+                // unless an exact bound falls on midnight and excludes that day anyway
+                let day_after = if dates_are_exact && is_midnight(to) {
+                    date
+                } else {
+                    date + chrono::Duration::days(1)
+                };
                 let formatted_date = day_after.format("%Y-%m-%d");
                 info!(
                     "Using before:{} to include messages until {} (inclusive)",
@@ -1961,41 +2793,387 @@ pub fn build_search_query(params: &SearchRequest) -> String {
     final_query
 }
 
-// Pagination helper with parallel fetching
-pub async fn fetch_all_results(
-    client: &SlackClient,
-    query: String,
+/// How channel history treats thread replies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ThreadReplies {
+    /// Fetch every thread and merge its replies into the timeline
+    #[default]
+    Inline,
+    /// Return parents only; the UI shows "N replies" and opens the thread on click
+    Lazy,
+}
+
+/// Query params for one conversations.list page
+fn channel_list_params(include_archived: bool, cursor: Option<&str>) -> HashMap<&'static str, String> {
+    let mut params = HashMap::new();
+    params.insert("types", "public_channel,private_channel".to_string());
+    params.insert("limit", "1000".to_string());
+    if !include_archived {
+        params.insert("exclude_archived", "true".to_string());
+    }
+    if let Some(cursor_value) = cursor {
+        params.insert("cursor", cursor_value.to_string());
+    }
+    params
+}
+
+/// A response read to completion, so its body can be checked for rate limiting
+struct RawResponse {
+    status: reqwest::StatusCode,
+    /// Seconds from the Retry-After header, if Slack sent one
+    retry_after: Option<u64>,
+    body: String,
+}
+
+impl RawResponse {
+    fn is_rate_limited(&self) -> bool {
+        if self.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return true;
+        }
+        // Cheap substring check first so normal bodies are not parsed twice
+        self.body.contains("ratelimited")
+            && serde_json::from_str::<serde_json::Value>(&self.body)
+                .map(|v| {
+                    v.get("ok").and_then(|ok| ok.as_bool()) == Some(false)
+                        && v.get("error").and_then(|e| e.as_str()) == Some("ratelimited")
+                })
+                .unwrap_or(false)
+    }
+}
+
+/// Debug-log the non-fatal warnings Slack attaches to a response
+fn log_response_warnings(body: &str) {
+    // Most bodies carry no warnings; skip parsing those
+    if !body.contains("warning") {
+        return;
+    }
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+        let top_level = value.get("warning").and_then(|w| w.as_str());
+        let metadata = value
+            .pointer("/response_metadata/warnings")
+            .and_then(|w| w.as_array());
+        if top_level.is_some() || metadata.is_some() {
+            debug!("Slack response warnings: {:?} {:?}", top_level, metadata);
+        }
+    }
+}
+
+/// Page through conversations.replies until Slack runs out of cursors
+///
+/// Later pages repeat the thread parent, so messages whose ts (per `ts_of`)
+/// was already collected are skipped; order is otherwise kept as returned.
+async fn collect_reply_pages<T, K, F, Fut>(ts_of: K, mut fetch_page: F) -> Result<Vec<T>>
+where
+    K: Fn(&T) -> String,
+    F: FnMut(Option<String>) -> Fut,
+    Fut: std::future::Future<Output = Result<(Vec<T>, Option<String>)>>,
+{
+    let mut all = Vec::new();
+    let mut seen = HashSet::new();
+    let mut cursor = None;
+    let mut pages = 0;
+
+    loop {
+        let (page, next_cursor) = fetch_page(cursor).await?;
+        pages += 1;
+        all.extend(page.into_iter().filter(|msg| seen.insert(ts_of(msg))));
+
+        cursor = next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    if pages > 1 {
+        info!("Collected {} thread messages over {} pages", all.len(), pages);
+    }
+    Ok(all)
+}
+
+/// HTTP client carrying the Slack token, with a fresh connection pool
+fn http_client(token: &str, timeout_secs: u64) -> Result<Client> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        header::AUTHORIZATION,
+        header::HeaderValue::from_str(&format!("Bearer {}", token))?,
+    );
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+
+    Ok(Client::builder()
+        .default_headers(headers)
+        .timeout(Duration::from_secs(timeout_secs))
+        .build()?)
+}
+
+/// Whether `err` means no connection to Slack could be made, so the request
+/// was never sent
+///
+/// Errors after the request went out (a reset mid-response, a timeout) don't
+/// count: resending a chat.postMessage then could post it twice.
+fn is_connection_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(|e| e.is_connect())
+}
+
+/// Call `send` with `client`; if it cannot connect, build a new client with
+/// `rebuild` and call `send` once more with that
+async fn with_reconnect<R, F, Fut>(client: &Client, rebuild: R, mut send: F) -> Result<RawResponse>
+where
+    R: FnOnce() -> Result<Client>,
+    F: FnMut(Client) -> Fut,
+    Fut: std::future::Future<Output = Result<RawResponse>>,
+{
+    match send(client.clone()).await {
+        Err(e) if is_connection_error(&e) => {
+            warn!("Could not reach Slack ({}), retrying once with a new connection", e);
+            send(rebuild()?).await
+        }
+        result => result,
+    }
+}
+
+/// Call `send` until it is no longer rate limited, giving up after `max_retries` retries
+///
+/// Every outcome is recorded in `state` so later requests can pace themselves.
+async fn retry_rate_limited<F, Fut>(
+    state: &RateLimitState,
+    base_delay: Duration,
+    max_retries: u32,
+    mut send: F,
+) -> Result<RawResponse>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<RawResponse>>,
+{
+    let mut attempt = 0;
+    loop {
+        let response = send().await?;
+        if !response.is_rate_limited() {
+            state.record_success();
+            return Ok(response);
+        }
+        state.record_rate_limited();
+        if attempt >= max_retries {
+            error!("Still rate limited by Slack after {} retries, giving up", max_retries);
+            return Err(AppError::NetworkError(format!(
+                "Rate limited by Slack (gave up after {} retries). Please wait a moment and try again.",
+                max_retries
+            ))
+            .into());
+        }
+
+        let delay = response
+            .retry_after
+            .map(Duration::from_secs)
+            .unwrap_or(base_delay * 2u32.pow(attempt));
+        attempt += 1;
+        warn!(
+            "Rate limited by Slack (status {}), retry {}/{} in {:?}",
+            response.status, attempt, max_retries, delay
+        );
+        sleep(delay).await;
+    }
+}
+
+/// Keep only the requested message when history was asked for a single ts
+///
+/// With `oldest == latest` and `inclusive=true`, Slack sometimes returns the
+/// neighbouring message instead (float precision in the ts string), so only
+/// an exact string match is kept.
+fn retain_exact_ts(messages: &mut Vec<SlackMessage>, oldest: Option<&str>, latest: Option<&str>) {
+    if let (Some(oldest), Some(latest)) = (oldest, latest) {
+        if oldest == latest {
+            let before = messages.len();
+            messages.retain(|msg| msg.ts == oldest);
+            if messages.len() != before {
+                debug!("Dropped {} neighbouring messages for exact ts {}", before - messages.len(), oldest);
+            }
+        }
+    }
+}
+
+/// Append a history page, skipping messages whose ts an earlier page already
+/// returned (with `inclusive=true` the boundary message can reappear at a seam)
+fn extend_unique_ts(all: &mut Vec<SlackMessage>, seen: &mut HashSet<String>, page: Vec<SlackMessage>) {
+    let page_len = page.len();
+    let before = all.len();
+    all.extend(page.into_iter().filter(|msg| seen.insert(msg.ts.clone())));
+    let duplicates = page_len - (all.len() - before);
+    if duplicates > 0 {
+        debug!("Dropped {} boundary duplicate(s) between history pages", duplicates);
+    }
+}
+
+/// Messages still lacking reactions after history/replies were merged
+fn missing_reaction_count(messages: &[SlackMessage]) -> usize {
+    messages.iter().filter(|m| m.reactions.is_none()).count()
+}
+
+/// Options for fetching channel history
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HistoryOptions {
+    pub thread_replies: ThreadReplies,
+    /// Join public channels on `not_in_channel` and retry once (opt-in)
+    pub auto_join: bool,
+    /// Page until Slack runs out instead of stopping after the usual call cap (exports)
+    pub full_history: bool,
+}
+
+fn is_not_in_channel(err: &anyhow::Error) -> bool {
+    matches!(SlackApiError::of(err), Some(SlackApiError::NotInChannel))
+}
+
+/// Run `fetch`; if it fails with `not_in_channel` and `auto_join` is set,
+/// run `join` and retry `fetch` exactly once
+async fn with_auto_join<T, F, Fut, J, JFut>(auto_join: bool, fetch: F, join: J) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+    J: FnOnce() -> JFut,
+    JFut: std::future::Future<Output = Result<()>>,
+{
+    match fetch().await {
+        Err(e) if auto_join && is_not_in_channel(&e) => {
+            info!("Not in channel, joining and retrying: {}", e);
+            join().await?;
+            fetch().await
+        }
+        result => result,
+    }
+}
+
+/// Merge thread replies into history according to `mode`, newest first
+///
+/// `fetch_replies` is called once per parent with replies in inline mode and
+/// never in lazy mode.
+async fn with_thread_replies<F, Fut>(
+    messages: Vec<SlackMessage>,
+    mode: ThreadReplies,
+    fetch_replies: F,
+) -> Vec<SlackMessage>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<SlackMessage>>>,
+{
+    let mut messages_with_replies = Vec::with_capacity(messages.len());
+
+    for msg in messages {
+        let reply_count = msg.reply_count.unwrap_or(0);
+        let thread_ts = msg.ts.clone();
+        messages_with_replies.push(msg);
+
+        if mode == ThreadReplies::Lazy || reply_count == 0 {
+            continue;
+        }
+
+        info!("[DEBUG] Message {} has {} thread replies, fetching them...", thread_ts, reply_count);
+
+        match fetch_replies(thread_ts).await {
+            Ok(replies) => {
+                // Skip the first message as it's the parent message we already have
+                let thread_replies: Vec<SlackMessage> = replies.into_iter().skip(1).collect();
+                info!("[DEBUG] Retrieved {} thread replies", thread_replies.len());
+                messages_with_replies.extend(thread_replies);
+            }
+            Err(e) => {
+                warn!("[DEBUG] Failed to fetch thread replies: {}", e);
+            }
+        }
+    }
+
+    // Sort messages by timestamp (newest first)
+    messages_with_replies.sort_by(|a, b| {
+        // Parse timestamps as floats for accurate comparison
+        let ts_a = a.ts.parse::<f64>().unwrap_or(0.0);
+        let ts_b = b.ts.parse::<f64>().unwrap_or(0.0);
+        ts_b.partial_cmp(&ts_a).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    messages_with_replies
+}
+
+/// Slack refuses search.messages pages beyond this
+const MAX_SEARCH_PAGES: usize = 100;
+
+/// Messages gathered by `fetch_all_results`
+pub struct FetchedResults {
+    pub messages: Vec<SlackMessage>,
+    /// More results matched than Slack's page limit lets us fetch
+    pub truncated_at_api_limit: bool,
+    /// Slack flagged at least one page as truncated (`message_truncated`)
+    pub truncated_by_slack: bool,
+}
+
+/// Number of search pages to request for up to `max_results` of Slack's `total`
+///
+/// Returns the page count, clamped to Slack's page limit, and whether `total`
+/// is more than that limit could ever serve.
+fn plan_search_pages(total: usize, max_results: usize, per_page: usize) -> (usize, bool) {
+    let pages_needed = total.min(max_results).div_ceil(per_page).min(MAX_SEARCH_PAGES);
+    (pages_needed, total > MAX_SEARCH_PAGES * per_page)
+}
+
+/// One page of search results
+#[derive(Debug)]
+pub struct SearchPage {
+    pub page: usize,
+    pub matches: Vec<SlackMessage>,
+    /// Slack flagged this page as truncated (`message_truncated`)
+    pub truncated: bool,
+}
+
+/// Fetch search pages in parallel batches, in page order
+///
+/// Fetching stops once `max_results` matches were fetched. Returns the pages and
+/// whether Slack's page limit cut the results short.
+async fn fetch_search_pages(
+    client: &SlackClient,
+    query: String,
     max_results: usize,
-) -> Result<Vec<SlackMessage>> {
-    let start_time = Instant::now();
+    options: SearchOptions,
+) -> Result<(Vec<SearchPage>, bool)> {
     let per_page = 100;
 
     info!("Starting parallel search for query: {}", query);
 
     // First, get the initial page to determine total results
-    let initial_response = client.search_messages(&query, per_page, 1).await?;
+    let initial_response = client.search_messages(&query, per_page, 1, options).await?;
+    let initial_truncated = initial_response.is_truncated();
 
-    if initial_response.messages.is_none() {
-        return Ok(vec![]);
-    }
+    let Some(messages_data) = initial_response.messages else {
+        return Ok((vec![SearchPage { page: 1, matches: vec![], truncated: initial_truncated }], false));
+    };
 
-    let messages_data = initial_response.messages.unwrap();
     let total_available = messages_data.total.min(max_results);
-    let mut all_messages = messages_data.matches;
-
-    if all_messages.len() >= total_available {
-        info!("All results fetched in first page: {}", all_messages.len());
-        return Ok(all_messages);
+    // Calculate how many pages we need
+    let (pages_needed, truncated_at_api_limit) = plan_search_pages(messages_data.total, max_results, per_page);
+    if truncated_at_api_limit {
+        warn!(
+            "Search matched {} results but Slack only serves {} pages; at most {} can be fetched",
+            messages_data.total,
+            MAX_SEARCH_PAGES,
+            MAX_SEARCH_PAGES * per_page
+        );
     }
 
-    // Calculate how many pages we need
-    let pages_needed = ((total_available.min(max_results) - 1) / per_page) + 1;
+    let mut fetched = messages_data.matches.len();
+    let mut pages = vec![SearchPage { page: 1, matches: messages_data.matches, truncated: initial_truncated }];
+
+    if fetched >= total_available {
+        info!("All results fetched in first page: {}", fetched);
+        return Ok((pages, truncated_at_api_limit));
+    }
     let remaining_pages = pages_needed.saturating_sub(1); // We already fetched page 1
+    let batch_size = client.config.max_concurrent.max(1);
 
     if remaining_pages > 0 {
         info!(
             "Fetching {} additional pages in parallel",
-            remaining_pages.min(MAX_CONCURRENT_REQUESTS)
+            remaining_pages.min(batch_size)
         );
 
         // Create client Arc for parallel requests
@@ -2003,66 +3181,1088 @@ pub async fn fetch_all_results(
 
         // Process pages in batches to respect rate limits
         let mut current_page = 2;
-        while current_page <= pages_needed {
-            let batch_end = (current_page + MAX_CONCURRENT_REQUESTS - 1).min(pages_needed);
+        while current_page <= pages_needed && fetched < max_results {
+            let batch_end = (current_page + batch_size - 1).min(pages_needed);
             let batch_futures = (current_page..=batch_end).map(|page| {
                 let client = Arc::clone(&client_arc);
                 let query = query.clone();
 
                 async move {
                     debug!("Fetching page {}", page);
-                    match client.search_messages(&query, per_page, page).await {
+                    match client.search_messages(&query, per_page, page, options).await {
                         Ok(response) => {
-                            if let Some(messages) = response.messages {
-                                info!("Page {} returned {} results", page, messages.matches.len());
-                                Ok::<Vec<SlackMessage>, anyhow::Error>(messages.matches)
-                            } else {
-                                Ok::<Vec<SlackMessage>, anyhow::Error>(vec![])
-                            }
+                            let truncated = response.is_truncated();
+                            let matches = response.messages.map(|m| m.matches).unwrap_or_default();
+                            info!("Page {} returned {} results", page, matches.len());
+                            Some(SearchPage { page, matches, truncated })
                         }
                         Err(e) => {
                             error!("Failed to fetch page {}: {}", page, e);
-                            Ok::<Vec<SlackMessage>, anyhow::Error>(vec![]) // Continue with other pages
+                            None // Continue with other pages
                         }
                     }
                 }
             });
 
-            // Execute batch in parallel
+            // Execute batch in parallel; join_all keeps the pages in order
             let batch_results = futures::future::join_all(batch_futures).await;
 
-            // Collect results
-            for result in batch_results {
-                if let Ok(messages) = result {
-                    all_messages.extend(messages);
+            for page in batch_results.into_iter().flatten() {
+                fetched += page.matches.len();
+                pages.push(page);
 
-                    // Check if we've reached the limit
-                    if all_messages.len() >= max_results {
-                        break;
-                    }
+                // Check if we've reached the limit
+                if fetched >= max_results {
+                    break;
                 }
             }
 
             current_page = batch_end + 1;
 
             // Rate limit protection between batches
-            if current_page <= pages_needed {
-                sleep(Duration::from_millis(RATE_LIMIT_DELAY_MS)).await;
+            if current_page <= pages_needed && fetched < max_results {
+                sleep(client.pagination_delay()).await;
             }
         }
     }
 
+    Ok((pages, truncated_at_api_limit))
+}
+
+/// Merge search pages in order, keeping at most `max_results` matches
+///
+/// Returns the messages and whether any page was flagged as truncated by Slack.
+fn collect_search_pages(pages: Vec<SearchPage>, max_results: usize) -> (Vec<SlackMessage>, bool) {
+    let mut messages = Vec::new();
+    let mut truncated_by_slack = false;
+
+    for page in pages {
+        debug!("Merging search page {} with {} results", page.page, page.matches.len());
+        truncated_by_slack |= page.truncated;
+        messages.extend(page.matches);
+    }
+
     // Truncate to max_results if necessary
-    if all_messages.len() > max_results {
-        all_messages.truncate(max_results);
+    messages.truncate(max_results);
+    (messages, truncated_by_slack)
+}
+
+/// Identity of a search match across pages
+fn match_key(msg: &SlackMessage) -> MessageKey {
+    let channel = msg.channel.as_ref().map(|c| c.id.clone()).unwrap_or_default();
+    (channel, msg.ts.clone())
+}
+
+/// Fetch search pages one at a time, dropping matches already seen on earlier pages
+///
+/// Instead of trusting `total`, this stops at the first page that adds no new
+/// matches: on very active channels new messages shift results between pages,
+/// so later pages repeat earlier matches. A failed first page is an error; a
+/// later failure keeps what was gathered so far.
+async fn collect_deduped_pages<F, Fut>(
+    max_results: usize,
+    per_page: usize,
+    mut fetch_page: F,
+) -> Result<FetchedResults>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: std::future::Future<Output = Result<SearchPage>>,
+{
+    let mut seen = HashSet::new();
+    let mut messages = Vec::new();
+    let mut truncated_by_slack = false;
+    let mut truncated_at_api_limit = false;
+
+    for page in 1..=MAX_SEARCH_PAGES {
+        let result = match fetch_page(page).await {
+            Ok(result) => result,
+            Err(e) if page == 1 => return Err(e),
+            Err(e) => {
+                error!("Failed to fetch page {}, keeping {} results: {}", page, messages.len(), e);
+                break;
+            }
+        };
+        truncated_by_slack |= result.truncated;
+
+        let page_len = result.matches.len();
+        let before = messages.len();
+        messages.extend(result.matches.into_iter().filter(|m| seen.insert(match_key(m))));
+        let new_matches = messages.len() - before;
+        if new_matches < page_len {
+            debug!("Page {} repeated {} earlier matches", page, page_len - new_matches);
+        }
+
+        if new_matches == 0 || page_len < per_page || messages.len() >= max_results {
+            break;
+        }
+        truncated_at_api_limit = page == MAX_SEARCH_PAGES;
     }
 
+    messages.truncate(max_results);
+    Ok(FetchedResults {
+        messages,
+        truncated_at_api_limit,
+        truncated_by_slack,
+    })
+}
+
+/// Pagination helper with parallel fetching
+///
+/// With `dedupe`, pages are fetched sequentially and matches repeated across
+/// pages are dropped (see `collect_deduped_pages`).
+pub async fn fetch_all_results(
+    client: &SlackClient,
+    query: String,
+    max_results: usize,
+    options: SearchOptions,
+    dedupe: bool,
+) -> Result<FetchedResults> {
+    let start_time = Instant::now();
+
+    if dedupe {
+        let per_page = 100;
+        let fetched = collect_deduped_pages(max_results, per_page, |page| {
+            let query = query.clone();
+            async move {
+                let response = client.search_messages(&query, per_page, page, options).await?;
+                let truncated = response.is_truncated();
+                let matches = response.messages.map(|m| m.matches).unwrap_or_default();
+                Ok(SearchPage { page, matches, truncated })
+            }
+        })
+        .await?;
+        info!(
+            "Deduplicated search completed: {} results in {:.2}s",
+            fetched.messages.len(),
+            start_time.elapsed().as_secs_f64()
+        );
+        return Ok(fetched);
+    }
+
+    let (pages, truncated_at_api_limit) = fetch_search_pages(client, query, max_results, options).await?;
+    let (messages, truncated_by_slack) = collect_search_pages(pages, max_results);
+
     let elapsed = start_time.elapsed();
     info!(
         "Parallel search completed: {} results in {:.2}s (speedup from parallel fetching)",
-        all_messages.len(),
+        messages.len(),
         elapsed.as_secs_f64()
     );
 
-    Ok(all_messages)
+    Ok(FetchedResults {
+        messages,
+        truncated_at_api_limit,
+        truncated_by_slack,
+    })
+}
+
+/// The identifying fields of an auth.test response
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuthIdentity {
+    #[serde(default)]
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub team_id: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl AuthIdentity {
+    /// The workspace subdomain (`acme` for acme.slack.com)
+    pub fn team_domain(&self) -> Option<String> {
+        self.url.as_deref().and_then(super::parser::team_domain_from_url)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthTestResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(flatten)]
+    identity: AuthIdentity,
+}
+
+/// conversations.history parameters for a DM, in `team_id`'s context when given
+fn dm_history_params(dm_id: &str, limit: usize, team_id: Option<&str>) -> HashMap<&'static str, String> {
+    let mut params = HashMap::new();
+    params.insert("channel", dm_id.to_string());
+    params.insert("limit", limit.min(100).to_string()); // Cap at 100 for safety
+    if let Some(team_id) = team_id {
+        params.insert("team_id", team_id.to_string());
+    }
+    params
+}
+
+/// The DM's context team, if it differs from the token's home team
+fn cross_team_context(dm: &SlackConversation, home_team: Option<&str>) -> Option<String> {
+    dm.context_team_id
+        .as_deref()
+        .filter(|team| home_team.is_none_or(|home| home != *team))
+        .map(String::from)
+}
+
+/// `(channel, ts)` identifying a message
+pub type MessageKey = (String, String);
+
+/// Message keys and the next cursor from a stars.list body (files and channels are skipped)
+fn parse_starred_messages(body: &str) -> Result<(Vec<MessageKey>, Option<String>)> {
+    #[derive(Deserialize)]
+    struct StarsListResponse {
+        ok: bool,
+        #[serde(default)]
+        items: Vec<StarredItem>,
+        error: Option<String>,
+        response_metadata: Option<SlackResponseMetadata>,
+    }
+
+    #[derive(Deserialize)]
+    struct StarredItem {
+        channel: Option<String>,
+        message: Option<StarredMessage>,
+    }
+
+    #[derive(Deserialize)]
+    struct StarredMessage {
+        ts: String,
+    }
+
+    let result: StarsListResponse = serde_json::from_str(body)?;
+    if !result.ok {
+        let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(SlackApiError::from_response("stars.list", &error_msg, body).into());
+    }
+
+    let keys = result
+        .items
+        .into_iter()
+        .filter_map(|item| Some((item.channel?, item.message?.ts)))
+        .collect();
+    let next_cursor = result
+        .response_metadata
+        .and_then(|m| m.next_cursor)
+        .filter(|c| !c.is_empty());
+    Ok((keys, next_cursor))
+}
+
+/// The muted channel IDs from a users.prefs.get body
+///
+/// Fails when the `muted_channels` pref is absent or not a string: writing
+/// back a list read from such a response would wipe the user's mutes.
+fn parse_muted_channels(body: &str) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Prefs {
+        #[serde(default)]
+        muted_channels: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct PrefsResponse {
+        ok: bool,
+        #[serde(default)]
+        error: Option<String>,
+        #[serde(default)]
+        prefs: Option<Prefs>,
+    }
+
+    let result: PrefsResponse = serde_json::from_str(body)?;
+    if !result.ok {
+        let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(SlackApiError::from_response("users.prefs.get", &error_msg, body).into());
+    }
+
+    let muted = result
+        .prefs
+        .and_then(|p| p.muted_channels)
+        .ok_or_else(|| anyhow!("users.prefs.get returned no muted_channels pref"))?;
+    Ok(muted
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Bookmarks from a bookmarks.list body
+fn parse_bookmarks(body: &str) -> Result<Vec<SlackBookmark>> {
+    #[derive(Deserialize)]
+    struct BookmarksListResponse {
+        ok: bool,
+        #[serde(default, deserialize_with = "deserialize_lenient_vec")]
+        bookmarks: Vec<SlackBookmark>,
+        error: Option<String>,
+    }
+
+    let result: BookmarksListResponse = serde_json::from_str(body)?;
+    if !result.ok {
+        let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(SlackApiError::from_response("bookmarks.list", &error_msg, body).into());
+    }
+
+    Ok(result.bookmarks)
+}
+
+/// Reactions from a reactions.get body; `no_reaction` means none, other failures are errors
+pub(crate) fn parse_reactions(body: &str) -> Result<Vec<SlackReaction>> {
+    let result: serde_json::Value = serde_json::from_str(body)?;
+    if result.get("ok").and_then(|v| v.as_bool()) == Some(false) {
+        let error_msg = result
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error");
+
+        // Handle "no_reaction" as normal case - message has no reactions
+        if error_msg.contains("no_reaction") {
+            return Ok(vec![]);
+        }
+        return Err(SlackApiError::from_response("reactions.get", error_msg, body).into());
+    }
+
+    Ok(result
+        .get("message")
+        .and_then(|msg| msg.get("reactions"))
+        .and_then(|r| serde_json::from_value::<Vec<SlackReaction>>(r.clone()).ok())
+        .unwrap_or_default())
+}
+
+/// Pinned messages from a pins.list body, newest pin first as Slack sends them
+fn parse_pinned_messages(body: &str) -> Result<Vec<SlackMessage>> {
+    #[derive(Deserialize)]
+    struct PinsListResponse {
+        ok: bool,
+        #[serde(default)]
+        items: Vec<PinnedItem>,
+        error: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct PinnedItem {
+        channel: Option<String>,
+        message: Option<SlackMessage>,
+    }
+
+    let result: PinsListResponse = serde_json::from_str(body)?;
+    if !result.ok {
+        let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+        return Err(SlackApiError::from_response("pins.list", &error_msg, body).into());
+    }
+
+    Ok(result
+        .items
+        .into_iter()
+        .filter_map(|item| {
+            let mut message = item.message?;
+            // Messages inside pins.list items don't repeat their channel
+            if message.channel.is_none() {
+                message.channel = item.channel.map(|id| SlackChannelInfo {
+                    name: id.clone(), // Resolved from the channel cache by the caller
+                    id,
+                    is_shared: false,
+                });
+            }
+            Some(message)
+        })
+        .collect())
+}
+
+/// Slack errors meaning the token isn't allowed to browse another user's membership
+const USER_CONVERSATIONS_DENIED: &[&str] = &["missing_scope", "not_allowed_token_type", "restricted_action"];
+
+/// Channels and the next cursor from a users.conversations body
+fn parse_user_conversations(body: &str) -> Result<(Vec<SlackConversation>, Option<String>)> {
+    let result: SlackConversationsListResponse = serde_json::from_str(body)?;
+    if !result.ok {
+        let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+        if USER_CONVERSATIONS_DENIED.contains(&error_msg.as_str()) {
+            return Err(SlackApiError::from_response("users.conversations", "missing_scope", body).into());
+        }
+        return Err(SlackApiError::from_response("users.conversations", &error_msg, body).into());
+    }
+
+    let next_cursor = result
+        .response_metadata
+        .and_then(|m| m.next_cursor)
+        .filter(|c| !c.is_empty());
+    Ok((result.channels.unwrap_or_default(), next_cursor))
+}
+
+/// Extract the oldest message ts from a conversations.history response body
+fn parse_oldest_message_ts(body: &str) -> Result<Option<String>> {
+    #[derive(Deserialize)]
+    struct HistoryTsResponse {
+        ok: bool,
+        messages: Option<Vec<HistoryTs>>,
+        error: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct HistoryTs {
+        ts: String,
+    }
+
+    let result: HistoryTsResponse = serde_json::from_str(body)?;
+
+    if !result.ok {
+        return Err(anyhow!(
+            "Failed to get oldest message: {}",
+            result.error.unwrap_or_else(|| "Unknown error".to_string())
+        ));
+    }
+
+    // Messages come back newest-first; pick the smallest ts regardless of order
+    Ok(result
+        .messages
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| m.ts)
+        .min_by(|a, b| {
+            let a_val = a.parse::<f64>().unwrap_or(f64::MAX);
+            let b_val = b.parse::<f64>().unwrap_or(f64::MAX);
+            a_val.partial_cmp(&b_val).unwrap_or(std::cmp::Ordering::Equal)
+        }))
+}
+
+/// Pull `messages.total` out of a search.messages body without parsing the matches
+fn parse_search_total(body: &str) -> Result<usize> {
+    #[derive(Deserialize)]
+    struct SearchTotalResponse {
+        ok: bool,
+        messages: Option<SearchTotal>,
+        error: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct SearchTotal {
+        total: usize,
+    }
+
+    let result: SearchTotalResponse = serde_json::from_str(body)?;
+
+    if !result.ok {
+        return Err(anyhow!(
+            "Slack API error: {}",
+            result.error.unwrap_or_else(|| "Unknown error".to_string())
+        ));
+    }
+
+    Ok(result.messages.map(|m| m.total).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_mute_pref_is_an_error() {
+        let muted = parse_muted_channels(r#"{"ok":true,"prefs":{"muted_channels":"C1, C2,"}}"#).unwrap();
+        assert_eq!(muted, vec!["C1".to_string(), "C2".to_string()]);
+        assert!(parse_muted_channels(r#"{"ok":true,"prefs":{"muted_channels":""}}"#).unwrap().is_empty());
+
+        assert!(parse_muted_channels(r#"{"ok":true,"prefs":{}}"#).is_err());
+        assert!(parse_muted_channels(r#"{"ok":true}"#).is_err());
+        assert!(parse_muted_channels(r#"{"ok":true,"prefs":{"muted_channels":["C1"]}}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_bookmarks() {
+        let body = r#"{"ok":true,"bookmarks":[
+            {"id":"Bk1","channel_id":"C1","title":"Runbook","link":"https://wiki/runbook","emoji":":memo:","type":"link"},
+            {"id":"Bk2","channel_id":"C1","title":"Dashboard","link":"https://grafana/d/1","emoji":null,"type":"link"}
+        ]}"#;
+
+        let bookmarks = parse_bookmarks(body).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].emoji.as_deref(), Some(":memo:"));
+        assert_eq!(bookmarks[1].link, "https://grafana/d/1");
+        assert_eq!(bookmarks[1].emoji, None);
+
+        let denied = r#"{"ok":false,"error":"missing_scope"}"#;
+        let err = parse_bookmarks(denied).unwrap_err();
+        assert!(matches!(SlackApiError::of(&err), Some(SlackApiError::MissingScope { .. })));
+    }
+
+    #[test]
+    fn test_parse_pinned_messages_skips_files() {
+        let body = r#"{"ok":true,"items":[
+            {"type":"message","channel":"C1","created":1700000500,"created_by":"U2",
+             "message":{"ts":"1700000100.000100","user":"U1","text":"deploy steps","permalink":"https://x.slack.com/archives/C1/p1700000100000100","pinned_to":["C1"]}},
+            {"type":"file","created":1700000400,"file":{"id":"F1","name":"notes.txt"}}
+        ]}"#;
+
+        let messages = parse_pinned_messages(body).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].ts, "1700000100.000100");
+        assert_eq!(messages[0].channel.as_ref().map(|c| c.id.as_str()), Some("C1"));
+        assert!(messages[0].is_pinned());
+
+        let denied = r#"{"ok":false,"error":"channel_not_found"}"#;
+        assert!(parse_pinned_messages(denied).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reply_pages_are_joined_in_order() {
+        let pages = vec![
+            (history_messages(serde_json::json!([
+                { "ts": "1.0", "text": "parent", "reply_count": 3 },
+                { "ts": "1.1", "text": "first" },
+                { "ts": "1.2", "text": "second" }
+            ])), Some("page2".to_string())),
+            (history_messages(serde_json::json!([
+                { "ts": "1.0", "text": "parent", "reply_count": 3 },
+                { "ts": "1.3", "text": "third" }
+            ])), None),
+        ];
+        let mut pages = pages.into_iter();
+        let mut cursors = Vec::new();
+
+        let messages = collect_reply_pages(
+            |msg: &SlackMessage| msg.ts.clone(),
+            |cursor| {
+                cursors.push(cursor);
+                let page = pages.next().unwrap();
+                async move { Ok(page) }
+            },
+        )
+        .await
+        .unwrap();
+
+        let ts: Vec<&str> = messages.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(ts, vec!["1.0", "1.1", "1.2", "1.3"]);
+        assert_eq!(cursors, vec![None, Some("page2".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_connection_error_rebuilds_client_and_retries() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // A port that was just free refuses connections
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let stale = http_client("xoxp-test", 5).unwrap();
+        let rebuilds = AtomicUsize::new(0);
+        let attempts = AtomicUsize::new(0);
+
+        let response = with_reconnect(
+            &stale,
+            || {
+                rebuilds.fetch_add(1, Ordering::SeqCst);
+                http_client("xoxp-test", 5)
+            },
+            |client| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt == 0 {
+                        client.get(format!("http://{}/api/auth.test", addr)).send().await?;
+                    }
+                    Ok::<_, anyhow::Error>(RawResponse {
+                        status: reqwest::StatusCode::OK,
+                        retry_after: None,
+                        body: r#"{"ok":true}"#.to_string(),
+                    })
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.body, r#"{"ok":true}"#);
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 1);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_parse_oldest_message_ts() {
+        let body = r#"{"ok":true,"messages":[{"type":"message","ts":"1512085950.000216","text":"first"}],"has_more":true}"#;
+        assert_eq!(
+            parse_oldest_message_ts(body).unwrap(),
+            Some("1512085950.000216".to_string())
+        );
+
+        let empty = r#"{"ok":true,"messages":[],"has_more":false}"#;
+        assert_eq!(parse_oldest_message_ts(empty).unwrap(), None);
+
+        let error = r#"{"ok":false,"error":"channel_not_found"}"#;
+        assert!(parse_oldest_message_ts(error).is_err());
+    }
+
+    fn history_messages(json: serde_json::Value) -> Vec<SlackMessage> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_lazy_thread_replies_skip_fetch() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let messages = history_messages(serde_json::json!([
+            { "ts": "2.0", "text": "parent", "reply_count": 3 },
+            { "ts": "1.0", "text": "plain" }
+        ]));
+        let calls = AtomicUsize::new(0);
+
+        let result = with_thread_replies(messages.clone(), ThreadReplies::Lazy, |_| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        })
+        .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].reply_count, Some(3));
+
+        let inline = with_thread_replies(messages, ThreadReplies::Inline, |thread_ts| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                Ok(history_messages(serde_json::json!([
+                    { "ts": thread_ts, "text": "parent" },
+                    { "ts": "2.5", "thread_ts": "2.0", "text": "reply" }
+                ])))
+            }
+        })
+        .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let order: Vec<&str> = inline.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(order, vec!["2.5", "2.0", "1.0"]);
+    }
+
+    #[test]
+    fn test_single_ts_fetch_drops_neighbour() {
+        let mut messages = history_messages(serde_json::json!([
+            { "ts": "1700000000.000201", "text": "neighbour" },
+            { "ts": "1700000000.000200", "text": "wanted" }
+        ]));
+        retain_exact_ts(&mut messages, Some("1700000000.000200"), Some("1700000000.000200"));
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].text, "wanted");
+
+        // Ranges are left alone
+        let mut range = history_messages(serde_json::json!([
+            { "ts": "2.0", "text": "b" },
+            { "ts": "1.0", "text": "a" }
+        ]));
+        retain_exact_ts(&mut range, Some("1.0"), Some("2.0"));
+        assert_eq!(range.len(), 2);
+    }
+
+    #[test]
+    fn test_history_pages_sharing_boundary_keep_one_copy() {
+        let first = history_messages(serde_json::json!([
+            { "ts": "3.0", "text": "c" },
+            { "ts": "2.0", "text": "boundary" }
+        ]));
+        let second = history_messages(serde_json::json!([
+            { "ts": "2.0", "text": "boundary" },
+            { "ts": "1.0", "text": "a" }
+        ]));
+
+        let mut all = Vec::new();
+        let mut seen = HashSet::new();
+        extend_unique_ts(&mut all, &mut seen, first);
+        extend_unique_ts(&mut all, &mut seen, second);
+
+        let order: Vec<&str> = all.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(order, vec!["3.0", "2.0", "1.0"]);
+    }
+
+    #[test]
+    fn test_message_channel_as_string_or_object() {
+        let messages = history_messages(serde_json::json!([
+            { "ts": "1.0", "text": "from history", "channel": "D0123456789" },
+            { "ts": "2.0", "text": "from search", "channel": { "id": "C1", "name": "general", "is_ext_shared": true } },
+            { "ts": "3.0", "text": "odd payload", "channel": { "name": "no id" } },
+            { "ts": "4.0", "text": "org shared", "channel": { "id": "C3", "name": "grid", "is_shared": true } }
+        ]));
+
+        let history = messages[0].channel.as_ref().unwrap();
+        assert_eq!((history.id.as_str(), history.name.as_str()), ("D0123456789", "D0123456789"));
+        let search = messages[1].channel.as_ref().unwrap();
+        assert_eq!((search.id.as_str(), search.name.as_str()), ("C1", "general"));
+        assert!(search.is_shared);
+        assert!(messages[2].channel.is_none());
+        // Shared within an Enterprise Grid org isn't Slack Connect
+        assert!(!messages[3].in_shared_channel());
+
+        // The standalone type accepts both shapes too
+        let info: SlackChannelInfo = serde_json::from_value(serde_json::json!("C2")).unwrap();
+        assert_eq!(info.id, "C2");
+        assert!(serde_json::from_value::<SlackChannelInfo>(serde_json::json!(42)).is_err());
+    }
+
+    #[test]
+    fn test_message_pin_and_star_flags() {
+        let messages = history_messages(serde_json::json!([
+            { "ts": "1.0", "text": "pinned and saved", "pinned_to": ["C123"], "is_starred": true },
+            { "ts": "2.0", "text": "plain" }
+        ]));
+
+        assert_eq!(messages[0].pinned_to.as_deref(), Some(&["C123".to_string()][..]));
+        assert!(messages[0].is_pinned());
+        assert!(messages[0].is_saved());
+        assert!(!messages[1].is_pinned());
+        assert!(!messages[1].is_saved());
+    }
+
+    #[tokio::test]
+    async fn test_reply_reactions_survive_thread_merge() {
+        let messages = history_messages(serde_json::json!([
+            { "ts": "2.0", "text": "parent", "reply_count": 2,
+              "reactions": [{ "name": "eyes", "count": 1, "users": ["U1"] }] }
+        ]));
+
+        let merged = with_thread_replies(messages, ThreadReplies::Inline, |thread_ts| async move {
+            Ok(history_messages(serde_json::json!([
+                { "ts": thread_ts, "text": "parent" },
+                { "ts": "2.5", "thread_ts": "2.0", "text": "reacted reply",
+                  "reactions": [{ "name": "tada", "count": 2, "users": ["U1", "U2"] }] },
+                { "ts": "2.6", "thread_ts": "2.0", "text": "bare reply" }
+            ])))
+        })
+        .await;
+
+        let reacted = merged.iter().find(|m| m.ts == "2.5").unwrap();
+        let reactions = reacted.reactions.as_ref().unwrap();
+        assert_eq!(reactions[0].name, "tada");
+        assert_eq!(reactions[0].count, 2);
+        // Only the reply that came back without reactions is left for the backfill
+        assert_eq!(missing_reaction_count(&merged), 1);
+        assert!(merged.iter().find(|m| m.ts == "2.6").unwrap().reactions.is_none());
+    }
+
+    async fn run_auto_join(auto_join: bool, first_error: &str) -> (Result<u32>, usize, usize) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let fetches = AtomicUsize::new(0);
+        let joins = AtomicUsize::new(0);
+        let result = with_auto_join(
+            auto_join,
+            || async {
+                if fetches.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(SlackApiError::from_code("conversations.history", first_error).into())
+                } else {
+                    Ok(7)
+                }
+            },
+            || async {
+                joins.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .await;
+        (result, fetches.load(Ordering::SeqCst), joins.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn test_auto_join_retries_only_on_not_in_channel() {
+        let (result, fetches, joins) = run_auto_join(true, "not_in_channel").await;
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!((fetches, joins), (2, 1));
+
+        let (result, fetches, joins) = run_auto_join(false, "not_in_channel").await;
+        assert!(result.is_err());
+        assert_eq!((fetches, joins), (1, 0));
+
+        let (result, fetches, joins) = run_auto_join(true, "channel_not_found").await;
+        assert!(result.is_err());
+        assert_eq!((fetches, joins), (1, 0));
+    }
+
+    fn raw(status: u16, body: &str) -> RawResponse {
+        RawResponse {
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            retry_after: None,
+            body: body.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ok_false_ratelimited_body_is_retried() {
+        let mut calls = 0;
+        let state = RateLimitState::new(1);
+        let response = retry_rate_limited(&state, Duration::from_millis(1), 3, || {
+            calls += 1;
+            let body = if calls == 1 {
+                r#"{"ok":false,"error":"ratelimited"}"#
+            } else {
+                r#"{"ok":true,"messages":[]}"#
+            };
+            async move { Ok(raw(200, body)) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(calls, 2);
+        assert!(response.body.contains("\"ok\":true"));
+        assert_eq!(state.status().recent_429_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_retries_are_bounded() {
+        let mut calls = 0;
+        let state = RateLimitState::new(1);
+        let result = retry_rate_limited(&state, Duration::from_millis(1), 2, || {
+            calls += 1;
+            async { Ok(raw(429, "")) }
+        })
+        .await;
+
+        assert_eq!(calls, 3);
+        let err = result.err().expect("exhausted retries should fail");
+        assert!(err.to_string().contains("gave up after 2 retries"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_header_sets_the_delay() {
+        let mut calls = 0;
+        let state = RateLimitState::new(1);
+        let started = Instant::now();
+        let response = retry_rate_limited(&state, Duration::from_secs(60), 3, || {
+            calls += 1;
+            let response = if calls == 1 {
+                RawResponse { retry_after: Some(0), ..raw(429, "") }
+            } else {
+                raw(200, r#"{"ok":true}"#)
+            };
+            async move { Ok(response) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(calls, 2);
+        assert_eq!(response.status, reqwest::StatusCode::OK);
+        assert!(started.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_client_config_sizes_the_request_pool() {
+        let config = SlackClientConfig { max_concurrent: 4, delay_ms: 100, timeout_secs: 60 };
+        let client = SlackClient::with_config("xoxp-test".to_string(), config).unwrap();
+        assert_eq!(client.rate_limiter.available_permits(), 4);
+        assert_eq!(client.pagination_delay(), Duration::from_millis(100));
+
+        let client = SlackClient::new("xoxp-test".to_string()).unwrap();
+        assert_eq!(client.config, SlackClientConfig::default());
+        assert_eq!(client.rate_limiter.available_permits(), MAX_CONCURRENT_REQUESTS);
+    }
+
+    #[test]
+    fn test_rate_limited_detection() {
+        assert!(raw(429, "").is_rate_limited());
+        assert!(raw(200, r#"{"ok":false,"error":"ratelimited"}"#).is_rate_limited());
+        assert!(!raw(200, r#"{"ok":false,"error":"channel_not_found"}"#).is_rate_limited());
+        assert!(!raw(200, r#"{"ok":true,"text":"we got ratelimited"}"#).is_rate_limited());
+    }
+
+    #[test]
+    fn test_channel_list_params_exclude_archived() {
+        let params = channel_list_params(false, None);
+        assert_eq!(params.get("exclude_archived").map(String::as_str), Some("true"));
+        assert!(!params.contains_key("cursor"));
+
+        let params = channel_list_params(true, Some("dXNlcjpVMDYxTkZUVDI="));
+        assert!(!params.contains_key("exclude_archived"));
+        assert_eq!(params.get("cursor").map(String::as_str), Some("dXNlcjpVMDYxTkZUVDI="));
+    }
+
+    #[test]
+    fn test_parse_search_total() {
+        let body = r#"{"ok":true,"query":"deploy","messages":{"total":1234,"matches":[]}}"#;
+        assert_eq!(parse_search_total(body).unwrap(), 1234);
+
+        assert_eq!(parse_search_total(r#"{"ok":true}"#).unwrap(), 0);
+        assert!(parse_search_total(r#"{"ok":false,"error":"invalid_auth"}"#).is_err());
+    }
+
+    #[test]
+    fn test_malformed_search_match_is_skipped() {
+        let mut matches: Vec<serde_json::Value> = (0..10)
+            .map(|i| serde_json::json!({ "ts": format!("{}.0", i), "text": format!("message {}", i) }))
+            .collect();
+        // `text` must be a string; this one shouldn't take the rest of the page down with it
+        matches[4] = serde_json::json!({ "ts": "4.0", "text": { "unexpected": true } });
+
+        let body = serde_json::json!({
+            "ok": true,
+            "messages": {
+                "total": 10,
+                "pagination": { "total_count": 10, "page": 1, "per_page": 100, "page_count": 1, "first": 1, "last": 10 },
+                "paging": { "count": 100, "total": 10, "page": 1, "pages": 1 },
+                "matches": matches
+            }
+        });
+
+        let result: SlackSearchResponse = serde_json::from_value(body).unwrap();
+        let parsed = result.messages.unwrap().matches;
+        assert_eq!(parsed.len(), 9);
+        assert!(parsed.iter().all(|m| m.ts != "4.0"));
+    }
+
+    #[test]
+    fn test_malformed_history_message_is_skipped() {
+        #[derive(Deserialize)]
+        struct History {
+            #[serde(default, deserialize_with = "deserialize_lenient_opt_vec")]
+            messages: Option<Vec<SlackMessage>>,
+        }
+
+        let body = r#"{"ok":true,"messages":[{"ts":"2.0","text":"ok"},{"text":"missing ts"}]}"#;
+        let history: History = serde_json::from_str(body).unwrap();
+        assert_eq!(history.messages.unwrap().len(), 1);
+
+        let history: History = serde_json::from_str(r#"{"ok":false}"#).unwrap();
+        assert!(history.messages.is_none());
+    }
+
+    #[test]
+    fn test_message_truncated_warning_flags_response() {
+        let body = r#"{"ok":true,"query":"deploy","warning":"superfluous_charset",
+            "response_metadata":{"warnings":["superfluous_charset","message_truncated"]}}"#;
+        let result: SlackSearchResponse = serde_json::from_str(body).unwrap();
+        assert!(result.is_truncated());
+        assert_eq!(result.warnings().len(), 3);
+
+        let body = r#"{"ok":true,"query":"deploy","warning":"missing_charset"}"#;
+        let result: SlackSearchResponse = serde_json::from_str(body).unwrap();
+        assert!(!result.is_truncated());
+        assert_eq!(result.warnings(), vec!["missing_charset".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_starred_messages() {
+        let body = r#"{"ok":true,"items":[
+            {"type":"message","channel":"C1","message":{"ts":"1700000000.000100","text":"saved"}},
+            {"type":"file","file":{"id":"F1"}}
+        ],"response_metadata":{"next_cursor":"abc"}}"#;
+        let (keys, cursor) = parse_starred_messages(body).unwrap();
+        assert_eq!(keys, vec![("C1".to_string(), "1700000000.000100".to_string())]);
+        assert_eq!(cursor.as_deref(), Some("abc"));
+
+        let last = r#"{"ok":true,"items":[],"response_metadata":{"next_cursor":""}}"#;
+        assert_eq!(parse_starred_messages(last).unwrap().1, None);
+    }
+
+    #[test]
+    fn test_parse_user_conversations() {
+        let body = r#"{"ok":true,"channels":[
+            {"id":"C1","name":"general","is_channel":true,"is_private":false},
+            {"id":"G2","name":"secret-project","is_group":true,"is_private":true}
+        ],"response_metadata":{"next_cursor":"dXNlcjpVMDYx"}}"#;
+        let (channels, cursor) = parse_user_conversations(body).unwrap();
+        let pairs: Vec<(&str, Option<&str>)> = channels
+            .iter()
+            .map(|c| (c.id.as_str(), c.name.as_deref()))
+            .collect();
+        assert_eq!(pairs, vec![("C1", Some("general")), ("G2", Some("secret-project"))]);
+        assert_eq!(cursor.as_deref(), Some("dXNlcjpVMDYx"));
+
+        let denied = r#"{"ok":false,"error":"missing_scope","needed":"channels:read"}"#;
+        let err = parse_user_conversations(denied).unwrap_err();
+        assert!(matches!(
+            SlackApiError::of(&err),
+            Some(SlackApiError::MissingScope { required_scope, .. }) if required_scope == "channels:read"
+        ));
+    }
+
+    #[test]
+    fn test_cross_team_dm_history_includes_team() {
+        let dm: SlackConversation = serde_json::from_value(serde_json::json!({
+            "id": "D0CROSS",
+            "is_im": true,
+            "context_team_id": "T0OTHER"
+        }))
+        .unwrap();
+
+        let team = cross_team_context(&dm, Some("T0HOME"));
+        assert_eq!(team.as_deref(), Some("T0OTHER"));
+        let params = dm_history_params("D0CROSS", 500, team.as_deref());
+        assert_eq!(params.get("team_id").map(String::as_str), Some("T0OTHER"));
+        assert_eq!(params.get("limit").map(String::as_str), Some("100"));
+
+        // Home-team DMs keep the plain request
+        assert_eq!(cross_team_context(&dm, Some("T0OTHER")), None);
+        assert!(!dm_history_params("D0HOME", 50, None).contains_key("team_id"));
+    }
+
+    #[test]
+    fn test_auth_test_response_carries_the_whole_identity() {
+        let result: AuthTestResponse = serde_json::from_value(serde_json::json!({
+            "ok": true,
+            "url": "https://acme.slack.com/",
+            "team": "Acme",
+            "user": "alice",
+            "team_id": "T0HOME",
+            "user_id": "U0ALICE"
+        }))
+        .unwrap();
+
+        assert!(result.ok);
+        assert_eq!(result.identity.user_id.as_deref(), Some("U0ALICE"));
+        assert_eq!(result.identity.team_id.as_deref(), Some("T0HOME"));
+        assert_eq!(result.identity.team_domain().as_deref(), Some("acme"));
+    }
+
+    #[test]
+    fn test_plan_search_pages_clamps_to_api_limit() {
+        assert_eq!(plan_search_pages(20000, 20000, 100), (100, true));
+        assert_eq!(plan_search_pages(10000, 20000, 100), (100, false));
+        assert_eq!(plan_search_pages(250, 5000, 100), (3, false));
+        assert_eq!(plan_search_pages(0, 5000, 100), (0, false));
+        // Slack's raw total decides, even when max_results asks for fewer
+        assert_eq!(plan_search_pages(20000, 5000, 100), (50, true));
+        assert_eq!(plan_search_pages(8000, 5000, 100), (50, false));
+    }
+
+    #[test]
+    fn test_collect_search_pages_keeps_page_order() {
+        let pages = (1..=5)
+            .map(|page| {
+                let matches: Vec<SlackMessage> = serde_json::from_value(serde_json::json!([
+                    { "ts": format!("{}.000100", page), "text": "a" },
+                    { "ts": format!("{}.000200", page), "text": "b" }
+                ]))
+                .unwrap();
+                SearchPage { page, matches, truncated: page == 3 }
+            })
+            .collect();
+
+        let (messages, truncated) = collect_search_pages(pages, 100);
+
+        let ts: Vec<&str> = messages.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(ts.len(), 10);
+        assert_eq!(ts[0], "1.000100");
+        assert_eq!(ts[3], "2.000200");
+        assert_eq!(ts[9], "5.000200");
+        assert!(truncated);
+
+        let (messages, truncated) = collect_search_pages(vec![], 100);
+        assert!(messages.is_empty() && !truncated);
+    }
+
+    #[tokio::test]
+    async fn test_deduped_pages_drop_repeated_matches() {
+        // Page 2 overlaps page 1 (new messages shifted results); page 3 adds nothing new
+        let pages = [
+            vec!["5.0", "4.0", "3.0"],
+            vec!["3.0", "2.0", "1.0"],
+            vec!["2.0", "1.0", "1.0"],
+            vec!["0.5", "0.4", "0.3"],
+        ];
+        let mut requested = Vec::new();
+        let fetched = collect_deduped_pages(100, 3, |page| {
+            requested.push(page);
+            let matches: Vec<SlackMessage> = pages[page - 1]
+                .iter()
+                .map(|ts| serde_json::from_value(serde_json::json!({
+                    "ts": ts, "text": "deploy", "channel": { "id": "C1", "name": "ops" }
+                })).unwrap())
+                .collect();
+            async move { Ok(SearchPage { page, matches, truncated: false }) }
+        })
+        .await
+        .unwrap();
+
+        let ts: Vec<&str> = fetched.messages.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(ts, vec!["5.0", "4.0", "3.0", "2.0", "1.0"]);
+        let unique: HashSet<&str> = ts.iter().copied().collect();
+        assert_eq!(unique.len(), ts.len());
+        assert_eq!(requested, vec![1, 2, 3]);
+        assert!(!fetched.truncated_at_api_limit);
+    }
 }
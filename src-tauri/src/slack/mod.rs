@@ -1,8 +1,9 @@
 pub mod client;
 pub mod models;
 pub mod parser;
+pub mod rate_limit;
 pub mod upload;
 
-pub use client::{build_search_query, fetch_all_results, SlackClient};
+pub use client::{build_search_query, fetch_all_results, HistoryOptions, SlackClient, SlackClientConfig, ThreadReplies};
 pub use models::*;
 pub use parser::parse_slack_url;
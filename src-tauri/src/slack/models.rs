@@ -1,5 +1,9 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use tracing::warn;
+
+use super::parser::extract_block_urls;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchRequest {
@@ -12,6 +16,70 @@ pub struct SearchRequest {
     pub is_realtime: Option<bool>, // Flag for realtime/live mode searches
     pub has_files: Option<bool>,   // Deprecated: Filter messages with attachments
     pub file_extensions: Option<Vec<String>>, // Filter by file extensions (e.g., ["pdf", "jpg", "png"])
+    #[serde(default)]
+    pub dates_are_exact: Option<bool>, // Use from/to timestamps as given instead of widening them to whole days
+}
+
+/// A search the user ran, remembered for suggestions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentSearch {
+    pub query: String,
+    pub channel: Option<String>,
+    pub user: Option<String>,
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub has_files: Option<bool>,
+    pub file_extensions: Option<Vec<String>>,
+    pub searched_at: u64, // Unix timestamp
+}
+
+impl RecentSearch {
+    /// Whether both describe the same search, ignoring when it ran and query case/whitespace
+    pub fn same_search(&self, other: &RecentSearch) -> bool {
+        self.query.trim().to_lowercase() == other.query.trim().to_lowercase()
+            && self.channel == other.channel
+            && self.user == other.user
+            && self.from_date == other.from_date
+            && self.to_date == other.to_date
+            && self.has_files == other.has_files
+            && self.file_extensions == other.file_extensions
+    }
+}
+
+/// Result ordering for search.messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSort {
+    /// Newest first (the app's long-standing default)
+    #[default]
+    Timestamp,
+    /// Slack's relevance ranking
+    Score,
+}
+
+impl SearchSort {
+    /// Parse the frontend's sort option, falling back to timestamp order
+    pub fn from_param(sort: Option<&str>) -> Self {
+        match sort {
+            Some("score") | Some("relevance") => SearchSort::Score,
+            _ => SearchSort::Timestamp,
+        }
+    }
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            SearchSort::Timestamp => "timestamp",
+            SearchSort::Score => "score",
+        }
+    }
+}
+
+/// Per-request options for search.messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Ask Slack to wrap matches in highlight markers
+    pub highlight: bool,
+    pub sort: SearchSort,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,18 +97,88 @@ pub struct Message {
     pub permalink: String,
     #[serde(rename = "isThreadParent")]
     pub is_thread_parent: bool,
+    /// A reply inside a thread (history inlines these next to top-level messages)
+    #[serde(rename = "isThreadReply", default)]
+    pub is_thread_reply: bool,
     #[serde(rename = "replyCount")]
     pub reply_count: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reactions: Option<Vec<SlackReaction>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub files: Option<Vec<SlackFile>>,
+    /// Slack-side match ranges in `text`, present only for highlighted searches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<Vec<HighlightRange>>,
+    /// App-provided message metadata, passed through untouched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    #[serde(rename = "isPinned", default)]
+    pub is_pinned: bool,
+    /// In the user's saved items (stars.list)
+    #[serde(rename = "isSaved", default)]
+    pub is_saved: bool,
+    /// Posted in a Slack Connect channel
+    #[serde(rename = "isSharedChannel", default)]
+    pub is_shared_channel: bool,
+    /// Link-button and other block URLs, for "open links" alongside those in `text`
+    #[serde(rename = "blockUrls", default, skip_serializing_if = "Vec::is_empty")]
+    pub block_urls: Vec<String>,
+}
+
+/// A highlighted span of message text, as char offsets (end exclusive)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HighlightRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Lightweight view of a search result for the initial render
+///
+/// Full message details (text, files, reactions) are fetched on demand.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MessageSummary {
+    pub ts: String,
+    pub channel: String,
+    #[serde(rename = "userName")]
+    pub user_name: String,
+    #[serde(rename = "channelName")]
+    pub channel_name: String,
+    #[serde(rename = "textPreview")]
+    pub text_preview: String,
+    #[serde(rename = "reactionCount")]
+    pub reaction_count: u32,
+    #[serde(rename = "hasFiles")]
+    pub has_files: bool,
+}
+
+impl MessageSummary {
+    /// Summarize a message, keeping at most `max_chars` characters of its text
+    pub fn from_message(msg: &Message, max_chars: usize) -> Self {
+        let text_preview = super::parser::preview(&msg.text, max_chars);
+
+        MessageSummary {
+            ts: msg.ts.clone(),
+            channel: msg.channel.clone(),
+            user_name: msg.user_name.clone(),
+            channel_name: msg.channel_name.clone(),
+            text_preview,
+            reaction_count: msg
+                .reactions
+                .as_ref()
+                .map(|r| r.iter().map(|reaction| reaction.count).sum())
+                .unwrap_or(0),
+            has_files: msg.files.as_ref().map_or(false, |f| !f.is_empty()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThreadMessages {
     pub parent: Message,
     pub replies: Vec<Message>,
+    /// Explains an empty reply list that contradicts the parent's reply count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +188,31 @@ pub struct SearchResult {
     pub query: String,
     #[serde(rename = "executionTimeMs")]
     pub execution_time_ms: u64,
+    /// Why a channel search came back empty (only set when there are no messages)
+    #[serde(rename = "resultReason", default, skip_serializing_if = "Option::is_none")]
+    pub result_reason: Option<ResultReason>,
+    /// Slack matched more results than its 100-page search limit can serve
+    #[serde(rename = "truncatedAtApiLimit", default)]
+    pub truncated_at_api_limit: bool,
+    /// Slack warned (`message_truncated`) that some results were left out
+    #[serde(rename = "truncatedBySlack", default)]
+    pub truncated_by_slack: bool,
+    /// The payload budget was hit, so later messages come without reactions/files
+    #[serde(rename = "partialEnrichment", default)]
+    pub partial_enrichment: bool,
+    /// Channels of a multi-channel search that still failed after a retry
+    #[serde(rename = "failedChannels", default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_channels: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ResultReason {
+    /// The channel has messages but none matched the query/filters
+    NoMatches,
+    /// The channel doesn't exist or the token can't read it
+    NoAccess,
+    /// The channel has no messages at all
+    Empty,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +223,9 @@ pub struct ParsedUrl {
     pub message_ts: String,
     #[serde(rename = "threadTs")]
     pub thread_ts: Option<String>,
+    /// Team/enterprise ID, when the link carries one (client and deep links)
+    #[serde(rename = "teamId", default, skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +256,36 @@ pub struct SlackSearchResponse {
     pub messages: Option<SlackSearchMessages>,
     pub error: Option<String>,
     pub needed: Option<String>,
+    /// Older endpoints report comma-separated warnings here
+    pub warning: Option<String>,
+    pub response_metadata: Option<SlackResponseMetadata>,
+}
+
+/// Slack warnings that mean the response is missing data
+pub const TRUNCATION_WARNINGS: &[&str] = &["message_truncated"];
+
+impl SlackSearchResponse {
+    /// Non-fatal warnings from both `warning` and `response_metadata.warnings`
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = self
+            .warning
+            .iter()
+            .flat_map(|w| w.split(','))
+            .map(|w| w.trim().to_string())
+            .filter(|w| !w.is_empty())
+            .collect();
+        if let Some(ref metadata) = self.response_metadata {
+            warnings.extend(metadata.warnings.iter().cloned());
+        }
+        warnings
+    }
+
+    /// Whether Slack warned that it cut this response short
+    pub fn is_truncated(&self) -> bool {
+        self.warnings()
+            .iter()
+            .any(|w| TRUNCATION_WARNINGS.contains(&w.as_str()))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -98,6 +294,7 @@ pub struct SlackSearchMessages {
     pub total: usize,
     pub pagination: SlackPagination,
     pub paging: SlackPaging,
+    #[serde(default, deserialize_with = "deserialize_lenient_vec")]
     pub matches: Vec<SlackMessage>,
 }
 
@@ -137,52 +334,193 @@ pub struct SlackMessage {
     pub permalink: Option<String>,  // Make permalink optional for conversations.history
     #[serde(default)]
     pub reactions: Option<Vec<SlackReaction>>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_message_files")]
     pub files: Option<Vec<SlackFile>>,
     #[serde(default)]
     pub reply_count: Option<usize>,  // Number of thread replies
+    #[serde(default)]
+    pub latest_reply: Option<String>,  // ts of the newest thread reply (thread parents only)
+    #[serde(default)]
+    pub metadata: Option<Value>,  // App metadata (event_type + event_payload)
+    #[serde(default)]
+    pub topic: Option<String>,  // New topic for channel_topic messages
+    #[serde(default)]
+    pub purpose: Option<String>,  // New purpose for channel_purpose messages
+    #[serde(default)]
+    pub name: Option<String>,  // New name for channel_name messages
+    #[serde(default)]
+    pub old_name: Option<String>,  // Previous name for channel_name messages
+    #[serde(default)]
+    pub pinned_to: Option<Vec<String>>,  // Channels the message is pinned in
+    #[serde(default)]
+    pub is_starred: Option<bool>,  // Saved by the token's user
+    #[serde(default)]
+    pub user_profile: Option<SlackUserProfile>,  // Inline author profile, sent for Slack Connect users
+    #[serde(default)]
+    pub blocks: Option<Value>,  // Block Kit layout (buttons carry their own URLs)
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl SlackMessage {
+    pub fn is_pinned(&self) -> bool {
+        self.pinned_to.as_ref().is_some_and(|channels| !channels.is_empty())
+    }
+
+    pub fn is_saved(&self) -> bool {
+        self.is_starred.unwrap_or(false)
+    }
+
+    /// Whether this is a reply in someone else's thread rather than a top-level message
+    pub fn is_thread_reply(&self) -> bool {
+        self.thread_ts.as_ref().is_some_and(|t| t != &self.ts)
+    }
+
+    /// Author name from the inline `user_profile`; users.info can't see external users
+    pub fn inline_user_name(&self) -> Option<String> {
+        let profile = self.user_profile.as_ref()?;
+        profile
+            .display_name
+            .clone()
+            .filter(|n| !n.is_empty())
+            .or_else(|| profile.real_name.clone().filter(|n| !n.is_empty()))
+    }
+
+    /// URLs behind link buttons and other block elements, which the text doesn't contain
+    pub fn block_urls(&self) -> Vec<String> {
+        self.blocks.as_ref().map(extract_block_urls).unwrap_or_default()
+    }
+
+    /// In a Slack Connect channel (as far as the channel info on the message knows)
+    pub fn in_shared_channel(&self) -> bool {
+        self.channel.as_ref().is_some_and(|c| c.is_shared)
+    }
+}
+
+/// The `channel` of a message: search.messages sends an object, while
+/// conversations.history and some older payloads send just the channel ID
+#[derive(Debug, Clone, Serialize)]
 pub struct SlackChannelInfo {
     pub id: String,
     pub name: String,
+    /// Slack Connect (shared with another workspace)
+    #[serde(default)]
+    pub is_shared: bool,
 }
 
-// Custom deserializer for channel field that can handle both string and object formats
+impl SlackChannelInfo {
+    /// Read either shape; `None` for anything without a channel ID
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            // When channel is just a string ID (like in DM conversations.history)
+            Value::String(id) if !id.is_empty() => Some(SlackChannelInfo {
+                name: id.clone(), // Use ID as name for now
+                id,
+                is_shared: false,
+            }),
+            // When channel is an object (like in search.messages)
+            Value::Object(map) => {
+                let id = map.get("id").and_then(|v| v.as_str()).filter(|id| !id.is_empty())?;
+                let name = map.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                let is_shared = map.get("is_ext_shared").and_then(|v| v.as_bool()).unwrap_or(false);
+
+                Some(SlackChannelInfo {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    is_shared,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SlackChannelInfo {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        SlackChannelInfo::from_value(value)
+            .ok_or_else(|| serde::de::Error::custom("expected a channel ID or an object with an id"))
+    }
+}
+
+// A message's channel in either shape; anything unreadable is treated as missing
+// rather than failing the whole message
 fn deserialize_channel_info<'de, D>(deserializer: D) -> Result<Option<SlackChannelInfo>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let value = Option::<Value>::deserialize(deserializer)?;
+    Ok(value.and_then(SlackChannelInfo::from_value))
+}
 
-    match value {
-        None => Ok(None),
-        Some(Value::String(id)) => {
-            // When channel is just a string ID (like in DM conversations.history)
-            Ok(Some(SlackChannelInfo {
-                id: id.clone(),
-                name: id,  // Use ID as name for now
-            }))
-        }
-        Some(Value::Object(map)) => {
-            // When channel is an object (like in search.messages)
-            let id = map.get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let name = map.get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-
-            Ok(Some(SlackChannelInfo {
-                id: id.to_string(),
-                name: name.to_string(),
-            }))
-        }
-        _ => Ok(None),
+impl SlackFile {
+    /// Slack keeps a `mode: "tombstone"` stub, with no URLs, for deleted files
+    pub fn is_tombstoned(&self) -> bool {
+        self.mode.as_deref() == Some("tombstone")
+            || (self.url_private.is_none() && self.url_private_download.is_none())
     }
 }
 
+// Message files, with tombstoned ones flagged `unavailable` so the UI can gray them out
+fn deserialize_message_files<'de, D>(deserializer: D) -> Result<Option<Vec<SlackFile>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let files = Option::<Vec<SlackFile>>::deserialize(deserializer)?;
+    Ok(files.map(|files| {
+        files
+            .into_iter()
+            .map(|mut file| {
+                file.unavailable = file.unavailable || file.is_tombstoned();
+                file
+            })
+            .collect()
+    }))
+}
+
+/// Deserialize each element on its own, dropping (and logging) the ones that don't fit `T`
+///
+/// One message with an unexpected field shape shouldn't cost the whole page.
+pub fn parse_lenient<T: DeserializeOwned>(values: Vec<Value>) -> Vec<T> {
+    let total = values.len();
+    let parsed: Vec<T> = values
+        .into_iter()
+        .filter_map(|value| match serde_json::from_value::<T>(value) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                warn!("Skipping malformed item in Slack response: {}", e);
+                None
+            }
+        })
+        .collect();
+
+    if parsed.len() < total {
+        warn!("Skipped {} of {} malformed items", total - parsed.len(), total);
+    }
+    parsed
+}
+
+pub(crate) fn deserialize_lenient_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = Option::<Vec<Value>>::deserialize(deserializer)?;
+    Ok(parse_lenient(values.unwrap_or_default()))
+}
+
+pub(crate) fn deserialize_lenient_opt_vec<'de, D, T>(
+    deserializer: D,
+) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = Option::<Vec<Value>>::deserialize(deserializer)?;
+    Ok(values.map(parse_lenient))
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 pub struct SlackConversationsRepliesResponse {
@@ -190,6 +528,21 @@ pub struct SlackConversationsRepliesResponse {
     pub messages: Option<Vec<SlackReplyMessage>>,
     pub error: Option<String>,
     pub has_more: Option<bool>,
+    #[serde(default)]
+    pub response_metadata: Option<SlackResponseMetadata>,
+}
+
+impl SlackConversationsRepliesResponse {
+    /// Cursor for the next page, if Slack says there is one
+    pub fn next_cursor(&self) -> Option<String> {
+        if self.has_more == Some(false) {
+            return None;
+        }
+        self.response_metadata
+            .as_ref()
+            .and_then(|m| m.next_cursor.clone())
+            .filter(|c| !c.is_empty())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -208,8 +561,35 @@ pub struct SlackReplyMessage {
     pub latest_reply: Option<String>,
     #[serde(default)]
     pub reactions: Option<Vec<SlackReaction>>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_message_files")]
     pub files: Option<Vec<SlackFile>>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+    #[serde(default)]
+    pub pinned_to: Option<Vec<String>>,
+    #[serde(default)]
+    pub is_starred: Option<bool>,
+    #[serde(default)]
+    pub blocks: Option<Value>,
+}
+
+impl SlackReplyMessage {
+    pub fn is_pinned(&self) -> bool {
+        self.pinned_to.as_ref().is_some_and(|channels| !channels.is_empty())
+    }
+
+    pub fn is_saved(&self) -> bool {
+        self.is_starred.unwrap_or(false)
+    }
+
+    /// Whether this is a reply in someone else's thread rather than the thread parent
+    pub fn is_thread_reply(&self) -> bool {
+        self.thread_ts.as_ref().is_some_and(|t| t != &self.ts)
+    }
+
+    pub fn block_urls(&self) -> Vec<String> {
+        self.blocks.as_ref().map(extract_block_urls).unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -229,13 +609,67 @@ pub struct SlackUserInfo {
     pub deleted: Option<bool>,
 }
 
+/// A user group (subteam) from usergroups.list
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlackUsergroup {
+    pub id: String,
+    pub handle: String,
+}
+
+/// A link saved to a channel's bookmark bar, from bookmarks.list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlackBookmark {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub link: String,
+    /// e.g. ":memo:", when the bookmark has one
+    #[serde(default)]
+    pub emoji: Option<String>,
+}
+
+/// A followed thread from subscriptions.thread.getView (the "Threads" view)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlackFollowedThread {
+    pub root_msg: SlackFollowedRoot,
+    /// Replies the user hasn't read yet
+    #[serde(default, deserialize_with = "deserialize_lenient_vec")]
+    pub unread_replies: Vec<SlackReplyMessage>,
+}
+
+/// Parent message of a followed thread
 #[derive(Debug, Clone, Deserialize)]
+pub struct SlackFollowedRoot {
+    pub ts: String,
+    pub channel: String,
+    #[serde(default)]
+    pub thread_ts: Option<String>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub reply_count: Option<usize>,
+    #[serde(default)]
+    pub latest_reply: Option<String>,
+    #[serde(default)]
+    pub last_read: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[allow(dead_code)]
 pub struct SlackUserProfile {
     pub display_name: Option<String>,
     pub real_name: Option<String>,
     pub image_48: Option<String>,
     pub image_72: Option<String>,
+    pub image_192: Option<String>,
+    pub image_512: Option<String>,
+    pub image_1024: Option<String>,
+    /// Only set for users who uploaded a photo
+    pub image_original: Option<String>,
+    /// `false` when the user still has Slack's default gravatar
+    pub is_custom_image: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -261,11 +695,40 @@ pub struct SlackConversation {
     pub is_member: Option<bool>,  // User is a member of this channel
     pub is_muted: Option<bool>,  // Channel is muted by the user
     pub is_archived: Option<bool>,  // Channel is archived
+    #[serde(default)]
+    pub last_read: Option<String>,  // Slack's read cursor (conversations.info only)
+    #[serde(default)]
+    pub is_shared: Option<bool>,  // Shared with another workspace or organization
+    #[serde(default)]
+    pub is_ext_shared: Option<bool>,  // Shared with an external organization (Slack Connect)
+    #[serde(default)]
+    pub context_team_id: Option<String>,  // Team the conversation lives in (Enterprise Grid)
+    #[serde(default)]
+    pub updated: Option<i64>,  // Last activity in milliseconds (conversations.list)
+    #[serde(default)]
+    pub unread_count_display: Option<u64>,  // Unread messages as Slack badges them (conversations.info only)
+    #[serde(default)]
+    pub created: Option<i64>,  // Creation time in seconds
+    #[serde(default)]
+    pub creator: Option<String>,  // ID of the user who created the channel
+}
+
+impl SlackConversation {
+    /// A Slack Connect channel, whose external members need inline profiles
+    ///
+    /// `is_shared` alone also covers channels shared inside one Enterprise Grid
+    /// org, whose members are ordinary users.
+    pub fn is_slack_connect(&self) -> bool {
+        self.is_ext_shared.unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SlackResponseMetadata {
     pub next_cursor: Option<String>,
+    /// Non-fatal warnings such as `missing_charset` or `message_truncated`
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -297,6 +760,32 @@ pub struct SlackReaction {
     pub name: String,
     pub count: u32,
     pub users: Vec<String>,
+    /// `users` lists fewer people than `count`, either capped by us or cut short by Slack
+    #[serde(default)]
+    pub users_truncated: bool,
+}
+
+impl SlackReaction {
+    /// Keep at most `cap` reactors, flagging the reaction when anyone is left out
+    ///
+    /// reactions.get has no paging; with `full=true` Slack already sends every
+    /// reactor it is going to, so `count` stays the true total either way.
+    pub fn cap_users(&mut self, cap: usize) {
+        if self.users.len() > cap {
+            self.users.truncate(cap);
+        }
+        self.users_truncated = (self.count as usize) > self.users.len();
+    }
+}
+
+/// Apply `cap_users` to reactions that came inline with history or search results
+pub fn capped_reactions(reactions: Option<Vec<SlackReaction>>, cap: usize) -> Option<Vec<SlackReaction>> {
+    reactions.map(|mut reactions| {
+        for reaction in &mut reactions {
+            reaction.cap_users(cap);
+        }
+        reactions
+    })
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -341,14 +830,18 @@ pub struct SlackFile {
     pub id: String,
     pub created: Option<i64>,
     pub timestamp: Option<i64>,
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub title: String,
+    #[serde(default)]
     pub mimetype: String,
     pub filetype: Option<String>,
     pub pretty_type: Option<String>,
     pub user: Option<String>,
     pub username: Option<String>,
     pub editable: Option<bool>,
+    #[serde(default)]
     pub size: i64,
     pub mode: Option<String>,
     pub is_external: Option<bool>,
@@ -413,6 +906,10 @@ pub struct SlackFile {
     pub inline_attachment_count: Option<i32>,
     /// Plain text version of email body
     pub plain_text: Option<String>,
+
+    /// Deleted (tombstoned) or otherwise undownloadable; set while parsing messages
+    #[serde(default)]
+    pub unavailable: bool,
 }
 
 // Post message models
@@ -422,12 +919,24 @@ pub struct PostMessageRequest {
     pub text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thread_ts: Option<String>,
+    /// Display name override (bot tokens only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Emoji avatar override, e.g. ":robot_face:" (bot tokens only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_emoji: Option<String>,
+    /// Image avatar override (bot tokens only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PostMessageResponse {
     pub ok: bool,
+    // Error responses carry neither channel nor ts
+    #[serde(default)]
     pub channel: String,
+    #[serde(default)]
     pub ts: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<PostedMessage>,
@@ -438,6 +947,8 @@ pub struct PostMessageResponse {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PostedMessage {
     pub text: String,
+    // Bot posts with a persona may omit the user field
+    #[serde(default)]
     pub user: String,
     #[serde(rename = "userName", skip_serializing_if = "Option::is_none")]
     pub user_name: Option<String>,
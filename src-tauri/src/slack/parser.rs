@@ -1,8 +1,38 @@
-use super::models::ParsedUrl;
+use super::models::{HighlightRange, ParsedUrl};
 use anyhow::{anyhow, Result};
 use regex::Regex;
+use serde_json::Value;
+use tracing::warn;
 use url::Url;
 
+/// Collect the web URLs inside a message's `blocks`, e.g. link buttons in
+/// `elements[]` or section `accessory` buttons, in order and without duplicates
+pub fn extract_block_urls(blocks: &Value) -> Vec<String> {
+    fn walk(value: &Value, urls: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    match (key.as_str(), child) {
+                        ("url", Value::String(url)) => {
+                            let is_web = url.starts_with("https://") || url.starts_with("http://");
+                            if is_web && !urls.contains(url) {
+                                urls.push(url.clone());
+                            }
+                        }
+                        _ => walk(child, urls),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| walk(item, urls)),
+            _ => {}
+        }
+    }
+
+    let mut urls = Vec::new();
+    walk(blocks, &mut urls);
+    urls
+}
+
 /// Parse a Slack URL to extract channel ID, message timestamp, and thread timestamp
 ///
 /// Supported URL formats:
@@ -52,13 +82,14 @@ pub fn parse_slack_url(url_str: &str) -> Result<ParsedUrl> {
         channel_id,
         message_ts,
         thread_ts,
+        team_id: None,
     })
 }
 
 /// Convert Slack's p-format message ID to timestamp format
 ///
 /// Example: p1234567890123456 -> 1234567890.123456
-fn convert_message_id_to_ts(message_id: &str) -> Result<String> {
+pub(crate) fn convert_message_id_to_ts(message_id: &str) -> Result<String> {
     if !message_id.starts_with('p') {
         return Err(anyhow!("Message ID must start with 'p'"));
     }
@@ -84,7 +115,6 @@ fn convert_message_id_to_ts(message_id: &str) -> Result<String> {
 /// Convert timestamp to p-format message ID
 ///
 /// Example: 1234567890.123456 -> p1234567890123456
-#[allow(dead_code)]
 pub fn convert_ts_to_message_id(ts: &str) -> Result<String> {
     let parts: Vec<&str> = ts.split('.').collect();
     if parts.len() != 2 {
@@ -129,9 +159,13 @@ pub fn extract_user_name(text: &str) -> Option<String> {
 }
 
 /// Build a Slack archive URL from components
-#[allow(dead_code)]
+///
+/// `team_domain` is the workspace subdomain (`acme` for acme.slack.com); without
+/// it the link points at slack.com, which Slack redirects for signed-in users.
+/// Replies (a `thread_ts` different from `message_ts`) get the `thread_ts`/`cid`
+/// query params so the link opens the reply inside its thread.
 pub fn build_slack_url(
-    workspace: &str,
+    team_domain: Option<&str>,
     channel_id: &str,
     message_ts: &str,
     thread_ts: Option<&str>,
@@ -139,56 +173,380 @@ pub fn build_slack_url(
     let message_id = convert_ts_to_message_id(message_ts)
         .unwrap_or_else(|_| format!("p{}", message_ts.replace('.', "")));
 
-    let base_url = format!(
-        "https://{}.slack.com/archives/{}/{}",
-        workspace, channel_id, message_id
-    );
+    let host = match team_domain.filter(|d| !d.is_empty()) {
+        Some(domain) => format!("{}.slack.com", domain),
+        None => "slack.com".to_string(),
+    };
+    let base_url = format!("https://{}/archives/{}/{}", host, channel_id, message_id);
+
+    match thread_ts {
+        Some(thread) if thread != message_ts => {
+            format!("{}?thread_ts={}&cid={}", base_url, thread, channel_id)
+        }
+        _ => base_url,
+    }
+}
 
-    if let Some(thread) = thread_ts {
-        format!("{}?thread_ts={}", base_url, thread)
-    } else {
-        base_url
+/// Workspace subdomain from the `url` auth.test returns (`https://acme.slack.com/` -> `acme`)
+///
+/// Enterprise Grid hosts keep every label (`acme.enterprise`), since that is
+/// the host links must point at.
+pub fn team_domain_from_url(url_str: &str) -> Option<String> {
+    let url = Url::parse(url_str).ok()?;
+    let host = url.host_str()?;
+    let domain = host.strip_suffix(".slack.com")?;
+    Some(domain.to_string()).filter(|d| !d.is_empty())
+}
+
+/// The ID of the one cached user whose name or real name is `name`, ignoring case
+///
+/// Two users can share a real name (two "John Smith"s); picking either would be
+/// a guess, so an ambiguous name resolves to nothing.
+pub fn find_user_by_name<'a>(
+    name: &str,
+    users: &'a std::collections::HashMap<String, crate::state::CachedUser>,
+) -> Option<&'a String> {
+    let mut matches = users.iter().filter(|(_, user)| {
+        user.name.eq_ignore_ascii_case(name)
+            || user.real_name.as_deref().is_some_and(|real| real.eq_ignore_ascii_case(name))
+    });
+    let (id, _) = matches.next()?;
+    if matches.next().is_some() {
+        warn!("Several cached users are named '{}', leaving it unresolved", name);
+        return None;
     }
+    Some(id)
 }
 
 /// Replace Slack user mentions with display names
 /// Handles both <@USERID> and <@USERID|username> formats
+///
+/// Mentions inside inline code or code blocks are left literal.
 pub fn replace_user_mentions(
     text: &str,
     user_cache: &std::collections::HashMap<String, crate::state::CachedUser>,
 ) -> String {
-    let mut result = text.to_string();
-
     // Updated regex to handle both <@USERID> and <@USERID|username> formats
     // The Slack API can return mentions in either format depending on the endpoint
     let re = regex::Regex::new(r"<@(U[A-Z0-9]+)(?:\|([^>]+))?>").unwrap();
 
-    for cap in re.captures_iter(text) {
-        let user_id = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-        let display_name = cap.get(2).map(|m| m.as_str());
-
-        let replacement = if let Some(name) = display_name {
-            // If we have the display name in the mention (e.g., <@U123|john.doe>), use it directly
-            format!("@{}", name)
-        } else if let Some(cached_user) = user_cache.get(user_id) {
-            // Otherwise, look up the user in our cache (for <@U123> format)
-            format!("@{}", cached_user.name)
-        } else {
-            // Fallback: keep the original format if we can't resolve it
-            continue;
+    map_outside_code(text, |segment| {
+        re.replace_all(segment, |cap: &regex::Captures| {
+            let user_id = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+
+            if let Some(name) = cap.get(2) {
+                // If we have the display name in the mention (e.g., <@U123|john.doe>), use it directly
+                format!("@{}", name.as_str())
+            } else if let Some(cached_user) = user_cache.get(user_id) {
+                // Otherwise, look up the user in our cache (for <@U123> format)
+                format!("@{}", cached_user.name)
+            } else {
+                // Fallback: keep the original format if we can't resolve it
+                cap[0].to_string()
+            }
+        })
+        .into_owned()
+    })
+}
+
+/// Replace user group mentions (`<!subteam^S123|@eng>`) with `@handle`
+///
+/// `usergroups` maps group ID to handle. Unknown groups fall back to the
+/// label Slack embedded in the token, then to the raw group ID.
+pub fn replace_subteam_mentions(
+    text: &str,
+    usergroups: &std::collections::HashMap<String, String>,
+) -> String {
+    let re = regex::Regex::new(r"<!subteam\^([A-Z0-9]+)(?:\|([^>]+))?>").unwrap();
+
+    map_outside_code(text, |segment| {
+        re.replace_all(segment, |cap: &regex::Captures| {
+            let group_id = &cap[1];
+            match (usergroups.get(group_id), cap.get(2)) {
+                (Some(handle), _) => format!("@{}", handle),
+                (None, Some(label)) => format!("@{}", label.as_str().trim_start_matches('@')),
+                (None, None) => format!("@{}", group_id),
+            }
+        })
+        .into_owned()
+    })
+}
+
+/// Resolve custom emoji aliases (`:thumbsup_alt:` -> `:+1:`) using the emoji.list map
+///
+/// In that map an alias's value is `alias:<target>`; everything else is left as written.
+pub fn resolve_emoji_aliases(
+    text: &str,
+    emoji: &std::collections::HashMap<String, String>,
+) -> String {
+    let re = regex::Regex::new(r":([a-z0-9_+'\-]+):").unwrap();
+
+    map_outside_code(text, |segment| {
+        re.replace_all(segment, |cap: &regex::Captures| {
+            match emoji.get(&cap[1]).and_then(|value| value.strip_prefix("alias:")) {
+                Some(target) => format!(":{}:", target),
+                None => cap[0].to_string(),
+            }
+        })
+        .into_owned()
+    })
+}
+
+/// Apply `f` to the parts of `text` outside Slack code formatting
+///
+/// Triple-backtick blocks and single-backtick inline spans are copied through
+/// untouched so token replacement can't corrupt code. Inline spans end at a
+/// newline, and an unterminated fence leaves the rest of the text as plain.
+pub fn map_outside_code<F: FnMut(&str) -> String>(text: &str, mut f: F) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some((start, end)) = next_code_span(rest) {
+        out.push_str(&f(&rest[..start]));
+        out.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    out.push_str(&f(rest));
+
+    out
+}
+
+/// Byte range of the first code span in `text`, including its backticks
+fn next_code_span(text: &str) -> Option<(usize, usize)> {
+    let open = text.find('`')?;
+
+    if text[open..].starts_with("```") {
+        let close = text[open + 3..].find("```")?;
+        return Some((open, open + 3 + close + 3));
+    }
+
+    let body_start = open + 1;
+    let stop = text[body_start..].find(['`', '\n'])? + body_start;
+    if text[stop..].starts_with('`') {
+        Some((open, stop + 1))
+    } else {
+        // A newline before the closing backtick means this one isn't code
+        next_code_span(&text[stop..]).map(|(s, e)| (s + stop, e + stop))
+    }
+}
+
+/// Search operators typed into the query box, pulled out of the free text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InlineOperators {
+    /// `in:` channels, comma-separated like the structured channel field
+    pub channel: Option<String>,
+    /// `from:` users, comma-separated like the structured user field
+    pub user: Option<String>,
+    /// `after:` as an inclusive from date (Slack's `after:` is exclusive)
+    pub from_date: Option<String>,
+    /// `before:` as an inclusive to date (Slack's `before:` is exclusive)
+    pub to_date: Option<String>,
+    /// `has:` / `is:` operators, which have no structured field and stay in the query
+    pub filters: Vec<String>,
+}
+
+fn push_csv(field: &mut Option<String>, value: &str) {
+    match field {
+        Some(existing) => {
+            existing.push(',');
+            existing.push_str(value);
+        }
+        None => *field = Some(value.to_string()),
+    }
+}
+
+/// `value` shifted by `days` if it is a YYYY-MM-DD date
+fn shift_date(value: &str, days: i64) -> Option<String> {
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    Some((date + chrono::Duration::days(days)).format("%Y-%m-%d").to_string())
+}
+
+/// Split `in:`, `from:`, `before:`, `after:`, `has:` and `is:` out of a typed query
+///
+/// Returns the remaining text and the operators found. Unknown operators,
+/// operators with no value, dates that aren't YYYY-MM-DD and anything inside
+/// double quotes are left in the text untouched.
+pub fn parse_inline_operators(raw: &str) -> (String, InlineOperators) {
+    let mut operators = InlineOperators::default();
+    let mut words = Vec::new();
+    let mut in_quotes = false;
+
+    for word in raw.split_whitespace() {
+        let quoted = in_quotes || word.starts_with('"');
+        in_quotes ^= word.matches('"').count() % 2 == 1;
+        let parsed = match word.split_once(':') {
+            Some((name, value)) if !quoted && !value.is_empty() => {
+                match name.to_lowercase().as_str() {
+                    "in" => {
+                        push_csv(&mut operators.channel, value.trim_start_matches('#'));
+                        true
+                    }
+                    "from" => {
+                        push_csv(&mut operators.user, value);
+                        true
+                    }
+                    "after" => shift_date(value, 1)
+                        .map(|date| operators.from_date = Some(date))
+                        .is_some(),
+                    "before" => shift_date(value, -1)
+                        .map(|date| operators.to_date = Some(date))
+                        .is_some(),
+                    "has" | "is" => {
+                        operators.filters.push(word.to_string());
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
         };
+        if !parsed {
+            words.push(word);
+        }
+    }
+
+    (words.join(" "), operators)
+}
+
+/// How a local keyword filter compares the query with message text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Case-insensitive substring match on the whole query
+    #[default]
+    Exact,
+    /// Every query term must appear in the text allowing small typos
+    Fuzzy,
+}
+
+/// Whether `text` matches `query` under `mode`
+pub fn text_matches(text: &str, query: &str, mode: MatchMode) -> bool {
+    let text_lower = text.to_lowercase();
+    let query_lower = query.to_lowercase();
+
+    match mode {
+        MatchMode::Exact => text_lower.contains(&query_lower),
+        MatchMode::Fuzzy => {
+            let words: Vec<&str> = text_lower
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .collect();
+            query_lower
+                .split_whitespace()
+                .all(|term| text_lower.contains(term) || words.iter().any(|w| fuzzy_word_match(term, w)))
+        }
+    }
+}
+
+/// Typos tolerated per term: none for short terms, more for long ones
+fn max_typos(term_chars: usize) -> usize {
+    match term_chars {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// A term matches a word within the typo budget, either whole or as a prefix
+/// (so "deplpy" still finds "deployment")
+fn fuzzy_word_match(term: &str, word: &str) -> bool {
+    let term_chars: Vec<char> = term.chars().collect();
+    let word_chars: Vec<char> = word.chars().collect();
+    let budget = max_typos(term_chars.len());
+    if budget == 0 {
+        return false;
+    }
+
+    if levenshtein(&term_chars, &word_chars) <= budget {
+        return true;
+    }
+    word_chars.len() > term_chars.len()
+        && levenshtein(&term_chars, &word_chars[..term_chars.len()]) <= budget
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Truncate `text` to at most `max_chars` characters, appending an ellipsis if anything was cut
+///
+/// Counts chars rather than bytes so multibyte text never splits mid-character.
+pub fn preview(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((byte_idx, _)) => format!("{}…", &text[..byte_idx]),
+        None => text.to_string(),
+    }
+}
+
+/// Marker Slack inserts before a highlighted match when `highlight=true`
+pub const HIGHLIGHT_START: char = '\u{e000}';
+/// Marker Slack inserts after a highlighted match when `highlight=true`
+pub const HIGHLIGHT_END: char = '\u{e001}';
 
-        let original = cap.get(0).map(|m| m.as_str()).unwrap_or("");
-        result = result.replace(original, &replacement);
+/// Strip Slack's highlight markers from `text`, returning the clean text and
+/// the highlighted ranges as char offsets into it
+///
+/// Unbalanced markers are dropped without producing a range.
+pub fn parse_highlight_ranges(text: &str) -> (String, Vec<HighlightRange>) {
+    let mut clean = String::with_capacity(text.len());
+    let mut ranges = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut pos = 0;
+
+    for ch in text.chars() {
+        match ch {
+            HIGHLIGHT_START => start = Some(pos),
+            HIGHLIGHT_END => {
+                if let Some(s) = start.take() {
+                    if pos > s {
+                        ranges.push(HighlightRange { start: s, end: pos });
+                    }
+                }
+            }
+            _ => {
+                clean.push(ch);
+                pos += 1;
+            }
+        }
     }
 
-    result
+    (clean, ranges)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_inline_operators_split_from_free_text() {
+        let (text, ops) = parse_inline_operators(
+            "deploy has:link in:#ops from:@alice after:2024-03-01 rollback before:2024-03-10 is:thread",
+        );
+        assert_eq!(text, "deploy rollback");
+        assert_eq!(ops.channel.as_deref(), Some("ops"));
+        assert_eq!(ops.user.as_deref(), Some("@alice"));
+        // Slack's after:/before: are exclusive; the structured dates are inclusive
+        assert_eq!(ops.from_date.as_deref(), Some("2024-03-02"));
+        assert_eq!(ops.to_date.as_deref(), Some("2024-03-09"));
+        assert_eq!(ops.filters, vec!["has:link".to_string(), "is:thread".to_string()]);
+
+        let (text, ops) = parse_inline_operators("in:ops in:dev during:today \"in:quoted text\" https://x.io after:yesterday");
+        assert_eq!(text, "during:today \"in:quoted text\" https://x.io after:yesterday");
+        assert_eq!(ops.channel.as_deref(), Some("ops,dev"));
+        assert_eq!(ops.from_date, None);
+    }
+
     #[test]
     fn test_parse_slack_url() {
         let url = "https://workspace.slack.com/archives/C1234567890/p1234567890123456";
@@ -221,6 +579,47 @@ mod tests {
         assert_eq!(message_id, "p1234567890123456");
     }
 
+    #[test]
+    fn test_build_slack_url_round_trips() {
+        let url = build_slack_url(Some("acme"), "C1234567890", "1234567890.123456", None);
+        assert_eq!(url, "https://acme.slack.com/archives/C1234567890/p1234567890123456");
+        let parsed = parse_slack_url(&url).unwrap();
+        assert_eq!(parsed.channel_id, "C1234567890");
+        assert_eq!(parsed.message_ts, "1234567890.123456");
+        assert_eq!(parsed.thread_ts, None);
+
+        // A reply links into its thread
+        let url = build_slack_url(
+            Some("acme"),
+            "C1234567890",
+            "1234567999.000100",
+            Some("1234567890.123456"),
+        );
+        assert!(url.ends_with("?thread_ts=1234567890.123456&cid=C1234567890"));
+        let parsed = parse_slack_url(&url).unwrap();
+        assert_eq!(parsed.message_ts, "1234567999.000100");
+        assert_eq!(parsed.thread_ts.as_deref(), Some("1234567890.123456"));
+
+        // A parent's own thread_ts adds no query, and a missing domain still parses
+        let url = build_slack_url(None, "C1234567890", "1234567890.123456", Some("1234567890.123456"));
+        assert_eq!(url, "https://slack.com/archives/C1234567890/p1234567890123456");
+        assert!(parse_slack_url(&url).is_ok());
+    }
+
+    #[test]
+    fn test_team_domain_from_url() {
+        assert_eq!(team_domain_from_url("https://acme.slack.com/"), Some("acme".to_string()));
+        assert_eq!(
+            team_domain_from_url("https://big-co.enterprise.slack.com/"),
+            Some("big-co.enterprise".to_string())
+        );
+        assert_eq!(
+            build_slack_url(Some("big-co.enterprise"), "C1234567890", "1234567890.123456", None),
+            "https://big-co.enterprise.slack.com/archives/C1234567890/p1234567890123456"
+        );
+        assert_eq!(team_domain_from_url("https://example.com/"), None);
+    }
+
     #[test]
     fn test_extract_channel_name() {
         assert_eq!(
@@ -241,4 +640,152 @@ mod tests {
             Some("U1234567890".to_string())
         );
     }
+
+    #[test]
+    fn test_parse_highlight_ranges() {
+        let text = "deploy \u{e000}failed\u{e001} on \u{e000}日本\u{e001}";
+        let (clean, ranges) = parse_highlight_ranges(text);
+        assert_eq!(clean, "deploy failed on 日本");
+        assert_eq!(
+            ranges,
+            vec![
+                HighlightRange { start: 7, end: 13 },
+                HighlightRange { start: 17, end: 19 },
+            ]
+        );
+
+        let (clean, ranges) = parse_highlight_ranges("no markers\u{e001} here");
+        assert_eq!(clean, "no markers here");
+        assert!(ranges.is_empty());
+    }
+
+    fn user_cache() -> std::collections::HashMap<String, crate::state::CachedUser> {
+        let user = crate::state::CachedUser {
+            name: "alice".to_string(),
+            real_name: None,
+            cached_at: 0,
+        };
+        std::collections::HashMap::from([("U123".to_string(), user)])
+    }
+
+    #[test]
+    fn test_mentions_inside_code_are_left_literal() {
+        let cache = user_cache();
+        let text = "ping <@U123> see `<@U123>` and\n```\nif a <@U123> b\n```\nthanks <@U999|bob>";
+        assert_eq!(
+            replace_user_mentions(text, &cache),
+            "ping @alice see `<@U123>` and\n```\nif a <@U123> b\n```\nthanks @bob"
+        );
+    }
+
+    #[test]
+    fn test_extract_block_urls_from_buttons() {
+        let blocks = serde_json::json!([
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": "Build *#512* failed" },
+                "accessory": {
+                    "type": "button",
+                    "text": { "type": "plain_text", "text": "Logs" },
+                    "url": "https://ci.example.com/builds/512/logs"
+                }
+            },
+            {
+                "type": "actions",
+                "elements": [
+                    { "type": "button", "text": { "type": "plain_text", "text": "Open PR" },
+                      "url": "https://github.com/acme/app/pull/77" },
+                    { "type": "button", "text": { "type": "plain_text", "text": "Retry" },
+                      "action_id": "retry", "value": "512" },
+                    { "type": "button", "text": { "type": "plain_text", "text": "Logs again" },
+                      "url": "https://ci.example.com/builds/512/logs" }
+                ]
+            },
+            { "type": "image", "image_url": "https://ci.example.com/badge.png", "alt_text": "badge" }
+        ]);
+
+        assert_eq!(
+            extract_block_urls(&blocks),
+            vec![
+                "https://ci.example.com/builds/512/logs".to_string(),
+                "https://github.com/acme/app/pull/77".to_string(),
+            ]
+        );
+        assert!(extract_block_urls(&serde_json::json!([])).is_empty());
+    }
+
+    #[test]
+    fn test_unterminated_code_is_plain_text() {
+        let cache = user_cache();
+        assert_eq!(replace_user_mentions("a ` <@U123>\nb", &cache), "a ` @alice\nb");
+        assert_eq!(replace_user_mentions("```<@U123>", &cache), "```@alice");
+        assert_eq!(replace_user_mentions("<@U404>", &cache), "<@U404>");
+    }
+
+    #[test]
+    fn test_resolve_emoji_aliases() {
+        let emoji = std::collections::HashMap::from([
+            ("shipit".to_string(), "https://emoji.slack-edge.com/T1/shipit/abc.png".to_string()),
+            ("ship".to_string(), "alias:shipit".to_string()),
+        ]);
+        assert_eq!(
+            resolve_emoji_aliases("ready :ship: :shipit: :tada: `:ship:`", &emoji),
+            "ready :shipit: :shipit: :tada: `:ship:`"
+        );
+    }
+
+    #[test]
+    fn test_replace_subteam_mentions() {
+        let groups = std::collections::HashMap::from([("S123".to_string(), "eng".to_string())]);
+        assert_eq!(
+            replace_subteam_mentions("hey <!subteam^S123|@old-eng> and <!subteam^S123>", &groups),
+            "hey @eng and @eng"
+        );
+        assert_eq!(
+            replace_subteam_mentions("<!subteam^S999|@design> <!subteam^S404>", &groups),
+            "@design @S404"
+        );
+        assert_eq!(
+            replace_subteam_mentions("`<!subteam^S123>`", &groups),
+            "`<!subteam^S123>`"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_match_tolerates_one_typo() {
+        let text = "The release was deployed to staging";
+        assert!(!text_matches(text, "relase", MatchMode::Exact));
+        assert!(text_matches(text, "relase", MatchMode::Fuzzy));
+        assert!(text_matches(text, "Deploy staging", MatchMode::Fuzzy));
+        assert!(text_matches("deployment done", "deplpy", MatchMode::Fuzzy));
+    }
+
+    #[test]
+    fn test_fuzzy_match_still_rejects_unrelated_terms() {
+        let text = "The release was deployed to staging";
+        assert!(!text_matches(text, "rollback", MatchMode::Fuzzy));
+        // Short terms need an exact hit
+        assert!(!text_matches(text, "thx", MatchMode::Fuzzy));
+        assert!(!text_matches(text, "release deployed", MatchMode::Exact));
+        assert!(text_matches(text, "release was", MatchMode::Exact));
+    }
+
+    #[test]
+    fn test_preview_ascii() {
+        assert_eq!(preview("hello world", 5), "hello…");
+        assert_eq!(preview("hi", 5), "hi");
+        assert_eq!(preview("", 5), "");
+    }
+
+    #[test]
+    fn test_preview_multibyte() {
+        assert_eq!(preview("こんにちは世界", 5), "こんにちは…");
+        assert_eq!(preview("👍👍👍", 2), "👍👍…");
+    }
+
+    #[test]
+    fn test_preview_exactly_at_limit() {
+        assert_eq!(preview("hello", 5), "hello");
+        assert_eq!(preview("日本語", 3), "日本語");
+    }
 }
@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// 429s older than this no longer count as "recent"
+const RECENT_WINDOW: Duration = Duration::from_secs(60);
+/// First adaptive delay applied after a rate limit
+const MIN_ADAPTIVE_DELAY_MS: u64 = 250;
+/// The adaptive delay never grows past this
+const MAX_ADAPTIVE_DELAY_MS: u64 = 10_000;
+
+/// Rate-limit pacing shared by every `SlackClient` built from the same `AppState`
+///
+/// Clients are cheap and recreated per command, so the concurrency pool and
+/// the backoff state live here instead of on the client.
+pub struct RateLimitState {
+    semaphore: Arc<Semaphore>,
+    capacity: usize,
+    recent_rate_limits: Mutex<VecDeque<Instant>>,
+    adaptive_delay_ms: AtomicU64,
+}
+
+/// Snapshot of the pacing state for the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitStatus {
+    pub tiers: Vec<TierStatus>,
+    pub recent_429_count: usize,
+    pub adaptive_delay_ms: u64,
+    /// True while requests are being delayed because of recent rate limits
+    pub slowed_down: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TierStatus {
+    pub tier: String,
+    pub available: usize,
+    pub capacity: usize,
+}
+
+impl RateLimitState {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            capacity: max_concurrent,
+            recent_rate_limits: Mutex::new(VecDeque::new()),
+            adaptive_delay_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub fn semaphore(&self) -> Arc<Semaphore> {
+        self.semaphore.clone()
+    }
+
+    /// Delay to wait before the next request
+    pub fn adaptive_delay(&self) -> Duration {
+        Duration::from_millis(self.adaptive_delay_ms.load(Ordering::Relaxed))
+    }
+
+    /// Record a 429 (or `ok:false ratelimited`) and double the adaptive delay
+    pub fn record_rate_limited(&self) {
+        self.record_rate_limited_at(Instant::now());
+    }
+
+    fn record_rate_limited_at(&self, now: Instant) {
+        if let Ok(mut recent) = self.recent_rate_limits.lock() {
+            prune(&mut recent, now);
+            recent.push_back(now);
+        }
+
+        let _ = self
+            .adaptive_delay_ms
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |delay| {
+                Some((delay * 2).clamp(MIN_ADAPTIVE_DELAY_MS, MAX_ADAPTIVE_DELAY_MS))
+            });
+    }
+
+    /// Record a request that was not rate limited, halving the adaptive delay
+    pub fn record_success(&self) {
+        let _ = self
+            .adaptive_delay_ms
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |delay| {
+                let halved = delay / 2;
+                (delay > 0).then_some(if halved < MIN_ADAPTIVE_DELAY_MS { 0 } else { halved })
+            });
+    }
+
+    pub fn status(&self) -> RateLimitStatus {
+        self.status_at(Instant::now())
+    }
+
+    fn status_at(&self, now: Instant) -> RateLimitStatus {
+        let recent_429_count = self
+            .recent_rate_limits
+            .lock()
+            .map(|mut recent| {
+                prune(&mut recent, now);
+                recent.len()
+            })
+            .unwrap_or(0);
+        let adaptive_delay_ms = self.adaptive_delay_ms.load(Ordering::Relaxed);
+
+        RateLimitStatus {
+            // All Slack methods currently share one request pool
+            tiers: vec![TierStatus {
+                tier: "shared".to_string(),
+                available: self.semaphore.available_permits(),
+                capacity: self.capacity,
+            }],
+            recent_429_count,
+            adaptive_delay_ms,
+            slowed_down: adaptive_delay_ms > 0,
+        }
+    }
+}
+
+fn prune(recent: &mut VecDeque<Instant>, now: Instant) {
+    while let Some(&oldest) = recent.front() {
+        if now.duration_since(oldest) > RECENT_WINDOW {
+            recent.pop_front();
+        } else {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_reflects_rate_limits() {
+        let state = RateLimitState::new(4);
+        let initial = state.status();
+        assert_eq!(initial.recent_429_count, 0);
+        assert_eq!(initial.adaptive_delay_ms, 0);
+        assert!(!initial.slowed_down);
+        assert_eq!(initial.tiers[0].available, 4);
+
+        state.record_rate_limited();
+        let after_one = state.status();
+        assert_eq!(after_one.recent_429_count, 1);
+        assert_eq!(after_one.adaptive_delay_ms, MIN_ADAPTIVE_DELAY_MS);
+        assert!(after_one.slowed_down);
+
+        state.record_rate_limited();
+        let after_two = state.status();
+        assert_eq!(after_two.recent_429_count, 2);
+        assert!(after_two.adaptive_delay_ms > after_one.adaptive_delay_ms);
+
+        state.record_success();
+        state.record_success();
+        assert_eq!(state.status().adaptive_delay_ms, 0);
+        assert_eq!(state.status().recent_429_count, 2);
+    }
+
+    #[test]
+    fn test_old_rate_limits_drop_out_of_window() {
+        let state = RateLimitState::new(4);
+        let start = Instant::now();
+        state.record_rate_limited_at(start);
+
+        let later = start + RECENT_WINDOW + Duration::from_secs(1);
+        assert_eq!(state.status_at(later).recent_429_count, 0);
+    }
+}
@@ -1,13 +1,23 @@
+use crate::commands::cache::{decode_cache, encode_cache};
 use crate::error::{AppError, AppResult};
-use crate::slack::{SearchResult, SlackClient, SlackReaction};
+use crate::slack::client::{AuthIdentity, SlackClientConfig, DEFAULT_MAX_RATE_LIMIT_RETRIES, DEFAULT_REACTOR_CAP};
+use crate::slack::parser::replace_subteam_mentions;
+use crate::slack::rate_limit::{RateLimitState, RateLimitStatus};
+use crate::slack::{
+    Message, RecentSearch, SearchResult, SearchSort, SlackClient, SlackConversation, SlackMessage,
+    SlackReaction, SlackUserInfo, ThreadReplies,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CachedUser {
@@ -24,6 +34,62 @@ pub struct CachedChannel {
     pub cached_at: u64,  // Unix timestamp
 }
 
+/// Every search parameter that can change the results, used as the search cache key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SearchCacheKey {
+    pub query: String,
+    pub channel: Option<String>,
+    pub user: Option<String>,
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub limit: Option<usize>,
+    pub has_files: Option<bool>,
+    pub file_extensions: Option<Vec<String>>,
+    pub highlight: bool,
+    pub sort: SearchSort,
+    pub thread_replies: ThreadReplies,
+    /// Incremental (live mode) searches only return messages after this ts
+    pub last_timestamp: Option<String>,
+    pub dates_are_exact: bool,
+    /// Results carry reply counts from per-message lookups
+    pub reply_counts: bool,
+    /// Matches repeated across result pages were dropped
+    pub dedupe: bool,
+    /// Results carry reactions fetched from Slack rather than none or only cached ones
+    pub reactions: bool,
+    /// Reactions were fetched in low-reaction channels too
+    pub fetch_low_reaction: bool,
+}
+
+/// Everything that changes the result of a DM search
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DmSearchCacheKey {
+    pub dm_id: String,
+    pub query: String,
+    pub limit: usize,
+    pub fuzzy: bool,
+}
+
+/// Custom emoji map with when it was fetched, persisted to the store between runs
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CachedEmoji {
+    pub emoji: HashMap<String, String>,
+    pub cached_at: u64, // Unix timestamp
+}
+
+/// Recent searches kept for suggestions
+pub const MAX_RECENT_SEARCHES: usize = 50;
+
+/// Put `search` at the front of `recent`, dropping an earlier copy and anything past `cap`
+fn push_recent_search(recent: &mut Vec<RecentSearch>, search: RecentSearch, cap: usize) {
+    recent.retain(|existing| !existing.same_search(&search));
+    recent.insert(0, search);
+    recent.truncate(cap);
+}
+
+/// emoji.list is heavy on big workspaces, and custom emoji rarely change
+const EMOJI_CACHE_TTL_SECS: u64 = 3600; // 1 hour
+
 #[derive(Clone)]
 pub struct CachedSearchResult {
     pub result: SearchResult,
@@ -36,6 +102,42 @@ pub struct CachedReactions {
     pub cached_at: u64, // Unix timestamp
 }
 
+/// Thread shape of a message, as read by a single-message lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplyInfo {
+    pub thread_ts: Option<String>,
+    pub reply_count: usize,
+}
+
+/// File under the app data dir the user cache is saved to between runs
+pub const USER_CACHE_FILE: &str = "user_cache.json";
+/// Save the user cache to disk after this many `cache_user` calls
+const USER_CACHE_SAVE_EVERY: usize = 100;
+/// Display names rarely change, so users saved to disk are loaded back while younger than this
+const USER_CACHE_MAX_AGE_SECS: u64 = 7 * 86400; // 7 days
+
+/// Users from a saved cache that are still young enough to trust
+fn fresh_users(users: HashMap<String, CachedUser>, now: u64) -> HashMap<String, CachedUser> {
+    users
+        .into_iter()
+        .filter(|(_, user)| now.saturating_sub(user.cached_at) < USER_CACHE_MAX_AGE_SECS)
+        .collect()
+}
+
+/// How long a standard (non-live) search result is served from cache
+const SEARCH_CACHE_SECS: u64 = 30;
+/// Searches kept in each search cache
+const MAX_CACHED_SEARCHES: usize = 50;
+
+/// Reactions change constantly, so cached ones are only trusted briefly unless configured otherwise
+pub(crate) const DEFAULT_REACTION_CACHE_TTL_SECS: u64 = 60;
+
+/// Reply counts change as threads grow, so lookups are only reused briefly
+const REPLY_COUNT_CACHE_TTL_SECS: u64 = 300; // 5 minutes
+
+/// stars.list is paged in full, so the saved set is reused across searches for a while
+const SAVED_ITEMS_CACHE_TTL_SECS: u64 = 300; // 5 minutes
+
 #[derive(Clone)]
 pub struct AppState {
     token: Arc<RwLock<Option<String>>>,
@@ -43,7 +145,28 @@ pub struct AppState {
     user_cache: Arc<RwLock<HashMap<String, CachedUser>>>,
     channel_cache: Arc<RwLock<HashMap<String, CachedChannel>>>,
     search_cache: Arc<RwLock<HashMap<u64, CachedSearchResult>>>, // Hash of search params -> result
+    dm_search_cache: Arc<RwLock<HashMap<DmSearchCacheKey, (Vec<SlackMessage>, u64)>>>, // value: (messages, cached_at)
     reaction_cache: Arc<RwLock<HashMap<String, CachedReactions>>>, // Key: "channel:timestamp"
+    advanced_mode: Arc<RwLock<bool>>, // Enables raw Slack method calls
+    local_read_cursors: Arc<RwLock<Option<HashMap<String, String>>>>, // channel -> last read ts; None until loaded from the store
+    message_details: Arc<RwLock<HashMap<String, Message>>>, // Key: "channel:timestamp", backs summary searches
+    rate_limit: Arc<RwLock<Arc<RateLimitState>>>, // Shared by every client so backoff survives across commands; replaced when resized
+    client_config: Arc<RwLock<SlackClientConfig>>, // Throughput settings for every client
+    usergroup_handles: Arc<RwLock<Option<HashMap<String, String>>>>, // subteam ID -> handle; None until fetched
+    auth_identity: Arc<RwLock<Option<Option<AuthIdentity>>>>, // auth.test result for links and per-workspace stores; None until looked up, Some(None) if that failed
+    emoji_cache: Arc<RwLock<Option<CachedEmoji>>>,
+    recent_searches: Arc<RwLock<Option<Vec<RecentSearch>>>>, // Newest first; None until loaded from the store
+    downloaded_files: Arc<RwLock<Option<HashMap<String, String>>>>, // file ID -> local path; None until loaded from the store
+    permalinks: Arc<RwLock<HashMap<String, String>>>, // Key: "channel:timestamp"
+    reply_counts: Arc<RwLock<HashMap<String, (ReplyInfo, u64)>>>, // Key: "channel:timestamp", value: (info, cached_at)
+    saved_items: Arc<RwLock<Option<(HashSet<(String, String)>, u64)>>>, // Saved (channel, ts) keys and cached_at; None until fetched
+    reactor_cap: Arc<RwLock<usize>>, // Reactor IDs kept per reaction
+    reaction_cache_ttl_secs: Arc<RwLock<u64>>, // How long a cached reactions entry counts as a hit
+    max_rate_limit_retries: Arc<RwLock<u32>>, // Retries per request while Slack rate limits it
+    user_cache_file: Arc<RwLock<Option<PathBuf>>>, // Where the user cache is saved; None until setup
+    unsaved_users: Arc<AtomicUsize>, // cache_user calls since the last save
+    compress_user_cache: Arc<AtomicBool>, // Follows the persisted-cache compression setting
+    channel_mute_flags: Arc<RwLock<HashMap<String, bool>>>, // Mutes changed this session; wins over Slack's is_muted
 }
 
 impl AppState {
@@ -54,11 +177,34 @@ impl AppState {
             user_cache: Arc::new(RwLock::new(HashMap::new())),
             channel_cache: Arc::new(RwLock::new(HashMap::new())),
             search_cache: Arc::new(RwLock::new(HashMap::new())),
+            dm_search_cache: Arc::new(RwLock::new(HashMap::new())),
             reaction_cache: Arc::new(RwLock::new(HashMap::new())),
+            advanced_mode: Arc::new(RwLock::new(false)),
+            local_read_cursors: Arc::new(RwLock::new(None)),
+            downloaded_files: Arc::new(RwLock::new(None)),
+            message_details: Arc::new(RwLock::new(HashMap::new())),
+            rate_limit: Arc::new(RwLock::new(Arc::new(RateLimitState::new(
+                SlackClientConfig::default().max_concurrent,
+            )))),
+            client_config: Arc::new(RwLock::new(SlackClientConfig::default())),
+            usergroup_handles: Arc::new(RwLock::new(None)),
+            auth_identity: Arc::new(RwLock::new(None)),
+            emoji_cache: Arc::new(RwLock::new(None)),
+            recent_searches: Arc::new(RwLock::new(None)),
+            permalinks: Arc::new(RwLock::new(HashMap::new())),
+            reply_counts: Arc::new(RwLock::new(HashMap::new())),
+            saved_items: Arc::new(RwLock::new(None)),
+            reactor_cap: Arc::new(RwLock::new(DEFAULT_REACTOR_CAP)),
+            reaction_cache_ttl_secs: Arc::new(RwLock::new(DEFAULT_REACTION_CACHE_TTL_SECS)),
+            max_rate_limit_retries: Arc::new(RwLock::new(DEFAULT_MAX_RATE_LIMIT_RETRIES)),
+            user_cache_file: Arc::new(RwLock::new(None)),
+            unsaved_users: Arc::new(AtomicUsize::new(0)),
+            compress_user_cache: Arc::new(AtomicBool::new(false)),
+            channel_mute_flags: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    fn current_timestamp() -> u64 {
+    pub(crate) fn current_timestamp() -> u64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or(Duration::from_secs(0))
@@ -71,8 +217,93 @@ impl AppState {
         now - cached_at < CACHE_DURATION_SECS
     }
 
+    /// Save the user cache to `path` from now on, seeding it with what was saved last run
+    ///
+    /// Entries past the max age are ignored; users already cached in memory win.
+    /// Returns the number of users loaded.
+    pub async fn attach_user_cache_file(&self, path: PathBuf) -> usize {
+        let saved = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(AppError::from)
+                .and_then(decode_cache::<HashMap<String, CachedUser>>)
+                .map(|users| fresh_users(users, Self::current_timestamp()))
+                .unwrap_or_else(|e| {
+                    warn!("Ignoring unreadable user cache at {}: {}", path.display(), e);
+                    HashMap::new()
+                }),
+            Err(_) => HashMap::new(),
+        };
+        *self.user_cache_file.write().await = Some(path);
+
+        let mut cache = self.user_cache.write().await;
+        let loaded = saved.len();
+        for (id, user) in saved {
+            cache.entry(id).or_insert(user);
+        }
+        loaded
+    }
+
+    /// Write the user cache to disk, if a file has been attached
+    pub async fn save_user_cache(&self) {
+        let Some(path) = self.user_cache_file.read().await.clone() else {
+            return;
+        };
+        self.unsaved_users.store(0, Ordering::Relaxed);
+
+        let compress = self.compress_user_cache.load(Ordering::Relaxed);
+        let encoded = encode_cache(&*self.user_cache.read().await, compress);
+        let json = match encoded.and_then(|value| Ok(serde_json::to_vec(&value)?)) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize user cache: {}", e);
+                return;
+            }
+        };
+        if let Some(dir) = path.parent() {
+            let _ = tokio::fs::create_dir_all(dir).await;
+        }
+        match tokio::fs::write(&path, json).await {
+            Ok(()) => debug!("Saved user cache to {}", path.display()),
+            Err(e) => warn!("Failed to save user cache to {}: {}", path.display(), e),
+        }
+    }
+
+    /// Whether the next saves of the user cache are gzip-compressed
+    pub fn set_user_cache_compression(&self, enabled: bool) {
+        self.compress_user_cache.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Delete the saved user cache; the in-memory cache is kept
+    pub async fn clear_user_cache_disk(&self) -> AppResult<()> {
+        let Some(path) = self.user_cache_file.read().await.clone() else {
+            return Ok(());
+        };
+        self.unsaved_users.store(0, Ordering::Relaxed);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {
+                info!("Removed saved user cache at {}", path.display());
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::StorageError(format!(
+                "Failed to remove user cache: {}",
+                e
+            ))),
+        }
+    }
+
     pub async fn set_token(&self, token: String) -> AppResult<()> {
         let mut token_lock = self.token.write().await;
+
+        // A different token may belong to a different user
+        if token_lock.as_deref() != Some(token.as_str()) {
+            *self.user_id.write().await = None;
+            *self.usergroup_handles.write().await = None;
+            *self.auth_identity.write().await = None;
+            *self.saved_items.write().await = None;
+            *self.emoji_cache.write().await = None;
+            self.permalinks.write().await.clear();
+        }
         *token_lock = Some(token);
 
         // Also save to secure storage
@@ -112,6 +343,284 @@ impl AppState {
         user_id_lock.clone()
     }
 
+    /// Get the cached user ID, running `fetch` only if it hasn't been stored yet
+    pub async fn get_or_fetch_user_id<F, Fut>(&self, fetch: F) -> AppResult<Option<String>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = AppResult<Option<String>>>,
+    {
+        if let Some(user_id) = self.get_user_id().await {
+            return Ok(Some(user_id));
+        }
+
+        let user_id = fetch().await?;
+        if let Some(ref uid) = user_id {
+            self.set_user_id(uid.clone()).await;
+        }
+        Ok(user_id)
+    }
+
+    /// Get the current user ID, falling back to auth.test only on first use
+    pub async fn current_user_id(&self) -> AppResult<Option<String>> {
+        self.get_or_fetch_user_id(|| async {
+            let client = self.get_client().await?;
+            match client.test_auth().await {
+                Ok((true, user_id)) => Ok(user_id),
+                Ok((false, _)) => Err(AppError::AuthError("Authentication failed".to_string())),
+                Err(e) => Err(AppError::NetworkError(e.to_string())),
+            }
+        })
+        .await
+    }
+
+    /// Whether the in-memory emoji cache has been populated (from the store or Slack)
+    pub async fn has_emoji_cache(&self) -> bool {
+        self.emoji_cache.read().await.is_some()
+    }
+
+    /// Seed the emoji cache from the store on cold start
+    pub async fn load_emoji_cache(&self, cached: CachedEmoji) {
+        let mut cache = self.emoji_cache.write().await;
+        if cache.is_none() {
+            *cache = Some(cached);
+        }
+    }
+
+    /// Serve the emoji map from cache while it is younger than the TTL, otherwise
+    /// call `fetch` and cache the result
+    ///
+    /// Returns the map and, when it was refetched, the new cache entry to persist.
+    pub async fn get_or_fetch_emoji<F, Fut>(
+        &self,
+        force_refresh: bool,
+        fetch: F,
+    ) -> AppResult<(HashMap<String, String>, Option<CachedEmoji>)>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = AppResult<HashMap<String, String>>>,
+    {
+        self.get_or_fetch_emoji_at(Self::current_timestamp(), force_refresh, fetch)
+            .await
+    }
+
+    async fn get_or_fetch_emoji_at<F, Fut>(
+        &self,
+        now: u64,
+        force_refresh: bool,
+        fetch: F,
+    ) -> AppResult<(HashMap<String, String>, Option<CachedEmoji>)>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = AppResult<HashMap<String, String>>>,
+    {
+        if !force_refresh {
+            if let Some(ref cached) = *self.emoji_cache.read().await {
+                if now.saturating_sub(cached.cached_at) < EMOJI_CACHE_TTL_SECS {
+                    debug!("Emoji cache hit ({} emojis)", cached.emoji.len());
+                    return Ok((cached.emoji.clone(), None));
+                }
+            }
+        }
+
+        let emoji = fetch().await?;
+        let cached = CachedEmoji {
+            emoji: emoji.clone(),
+            cached_at: now,
+        };
+        *self.emoji_cache.write().await = Some(cached.clone());
+        Ok((emoji, Some(cached)))
+    }
+
+    /// Workspace subdomain used for archive links, looked up via auth.test on first use
+    pub async fn team_domain(&self) -> Option<String> {
+        self.auth_identity().await?.team_domain()
+    }
+
+    /// Workspace ID of the current token, looked up via auth.test on first use
+    pub async fn team_id(&self) -> Option<String> {
+        self.auth_identity().await?.team_id
+    }
+
+    /// The token's auth.test identity, shared by `team_domain` and `team_id`
+    ///
+    /// A failed lookup is remembered as `None` until the token changes, so
+    /// building links doesn't retry auth.test for every message.
+    async fn auth_identity(&self) -> Option<AuthIdentity> {
+        if let Some(identity) = self.auth_identity.read().await.clone() {
+            return identity;
+        }
+
+        let identity = match self.get_client().await {
+            Ok(client) => match client.auth_identity().await {
+                Ok(identity) => Some(identity),
+                Err(e) => {
+                    warn!("Failed to look up workspace identity: {}", e);
+                    None
+                }
+            },
+            Err(e) => {
+                warn!("Failed to look up workspace identity: {}", e);
+                None
+            }
+        };
+        *self.auth_identity.write().await = Some(identity.clone());
+        identity
+    }
+
+    /// Resolve `<!subteam^...>` mentions in `text`, fetching user groups on first use
+    ///
+    /// A failed fetch (e.g. no `usergroups:read` scope) is remembered as an empty
+    /// map so it isn't retried for every message; labels embedded in the
+    /// mentions are used instead.
+    pub async fn resolve_subteam_mentions(&self, text: String) -> String {
+        if !text.contains("<!subteam^") {
+            return text;
+        }
+
+        if let Some(ref handles) = *self.usergroup_handles.read().await {
+            return replace_subteam_mentions(&text, handles);
+        }
+
+        let handles = match self.get_client().await {
+            Ok(client) => match client.get_usergroups().await {
+                Ok(groups) => groups.into_iter().map(|g| (g.id, g.handle)).collect(),
+                Err(e) => {
+                    warn!("Failed to fetch user groups: {}", e);
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+
+        let resolved = replace_subteam_mentions(&text, &handles);
+        *self.usergroup_handles.write().await = Some(handles);
+        resolved
+    }
+
+    pub async fn set_advanced_mode(&self, enabled: bool) {
+        *self.advanced_mode.write().await = enabled;
+        info!("Advanced mode {}", if enabled { "enabled" } else { "disabled" });
+    }
+
+    pub async fn is_advanced_mode(&self) -> bool {
+        *self.advanced_mode.read().await
+    }
+
+    /// Change the reactor cap; cached reactions were capped at the old size, so drop them
+    pub async fn set_reactor_cap(&self, cap: usize) {
+        *self.reactor_cap.write().await = cap;
+        self.reaction_cache.write().await.clear();
+        info!("Reactor cap set to {}", cap);
+    }
+
+    pub async fn reactor_cap(&self) -> usize {
+        *self.reactor_cap.read().await
+    }
+
+    /// Change how long cached reactions are served before being fetched again
+    pub async fn set_reaction_cache_ttl(&self, ttl_secs: u64) {
+        *self.reaction_cache_ttl_secs.write().await = ttl_secs;
+        info!("Reaction cache TTL set to {}s", ttl_secs);
+    }
+
+    /// Remember a mute change so channel listings reflect it before Slack's copy catches up
+    pub async fn set_channel_muted_flag(&self, channel_id: &str, muted: bool) {
+        self.channel_mute_flags
+            .write()
+            .await
+            .insert(channel_id.to_string(), muted);
+    }
+
+    pub async fn channel_mute_flags(&self) -> HashMap<String, bool> {
+        self.channel_mute_flags.read().await.clone()
+    }
+
+    /// Change the throughput settings used by every client from now on
+    ///
+    /// A new `max_concurrent` replaces the shared request pool, so its size
+    /// always matches the batch sizes clients derive from the config. Requests
+    /// already holding a permit finish on the old pool.
+    pub async fn set_client_config(&self, config: SlackClientConfig) {
+        let config = SlackClientConfig {
+            max_concurrent: config.max_concurrent.max(1),
+            ..config
+        };
+        let mut current = self.client_config.write().await;
+        if current.max_concurrent != config.max_concurrent {
+            *self.rate_limit.write().await = Arc::new(RateLimitState::new(config.max_concurrent));
+        }
+        *current = config;
+        info!("Client config set to {:?}", config);
+    }
+
+    pub async fn client_config(&self) -> SlackClientConfig {
+        *self.client_config.read().await
+    }
+
+    /// Change how often a rate-limited request is retried before it fails
+    pub async fn set_max_rate_limit_retries(&self, max_retries: u32) {
+        *self.max_rate_limit_retries.write().await = max_retries;
+        info!("Max rate-limit retries set to {}", max_retries);
+    }
+
+    /// Local read cursors, or None if they haven't been loaded from the store yet
+    pub async fn get_local_read_cursors(&self) -> Option<HashMap<String, String>> {
+        self.local_read_cursors.read().await.clone()
+    }
+
+    pub async fn load_local_read_cursors(&self, cursors: HashMap<String, String>) {
+        *self.local_read_cursors.write().await = Some(cursors);
+    }
+
+    /// Record the last locally-read message for a channel, returning all cursors
+    pub async fn set_local_read(&self, channel_id: String, ts: String) -> HashMap<String, String> {
+        let mut lock = self.local_read_cursors.write().await;
+        let cursors = lock.get_or_insert_with(HashMap::new);
+        cursors.insert(channel_id, ts);
+        cursors.clone()
+    }
+
+    /// Downloaded file paths by Slack file ID, or None if not loaded from the store yet
+    pub async fn get_downloaded_files(&self) -> Option<HashMap<String, String>> {
+        self.downloaded_files.read().await.clone()
+    }
+
+    pub async fn load_downloaded_files(&self, files: HashMap<String, String>) {
+        *self.downloaded_files.write().await = Some(files);
+    }
+
+    /// Remember where a file was downloaded to, returning the whole registry
+    pub async fn record_download(&self, file_id: String, path: String) -> HashMap<String, String> {
+        let mut lock = self.downloaded_files.write().await;
+        let files = lock.get_or_insert_with(HashMap::new);
+        files.insert(file_id, path);
+        files.clone()
+    }
+
+    pub async fn get_recent_searches(&self) -> Option<Vec<RecentSearch>> {
+        self.recent_searches.read().await.clone()
+    }
+
+    pub async fn load_recent_searches(&self, searches: Vec<RecentSearch>) {
+        *self.recent_searches.write().await = Some(searches);
+    }
+
+    /// Remember a search (moving repeats to the front), returning the full list
+    pub async fn add_recent_search(&self, search: RecentSearch) -> Vec<RecentSearch> {
+        let mut lock = self.recent_searches.write().await;
+        let recent = lock.get_or_insert_with(Vec::new);
+        push_recent_search(recent, search, MAX_RECENT_SEARCHES);
+        recent.clone()
+    }
+
+    pub async fn clear_recent_searches(&self) {
+        *self.recent_searches.write().await = Some(Vec::new());
+    }
+
+    pub async fn rate_limit_status(&self) -> RateLimitStatus {
+        self.rate_limit.read().await.status()
+    }
+
     pub async fn get_client(&self) -> AppResult<SlackClient> {
         let token = match self.get_token().await {
             Ok(t) => {
@@ -133,9 +642,13 @@ impl AppState {
             ));
         }
 
-        match SlackClient::new(token) {
+        let config = self.client_config().await;
+        let rate_limit = self.rate_limit.read().await.clone();
+        match SlackClient::with_rate_limit_state(token, config, rate_limit) {
             Ok(client) => {
-                Ok(client)
+                Ok(client
+                    .with_reactor_cap(*self.reactor_cap.read().await)
+                    .with_max_retries(*self.max_rate_limit_retries.read().await))
             }
             Err(e) => {
                 error!("Failed to create Slack client: {}", e);
@@ -147,9 +660,78 @@ impl AppState {
         }
     }
 
+    /// Fill the user and channel caches from users.list and conversations.list,
+    /// fetched concurrently
+    ///
+    /// Whichever listing succeeds is cached even if the other fails. Returns
+    /// the number of users and channels cached.
+    pub async fn warm_caches<U, UF, C, CF>(
+        &self,
+        fetch_users: U,
+        fetch_channels: C,
+    ) -> AppResult<(usize, usize)>
+    where
+        U: FnOnce() -> UF,
+        UF: Future<Output = AppResult<Vec<SlackUserInfo>>>,
+        C: FnOnce() -> CF,
+        CF: Future<Output = AppResult<Vec<SlackConversation>>>,
+    {
+        let (users, channels) = tokio::join!(fetch_users(), fetch_channels());
+
+        let user_count = users.as_ref().map(Vec::len).unwrap_or(0);
+        if let Ok(users) = &users {
+            let mut cache = self.user_cache.write().await;
+            let now = Self::current_timestamp();
+            for user in users {
+                // Same priority as search results: display_name > real_name > name
+                let name = user
+                    .profile
+                    .as_ref()
+                    .and_then(|p| p.display_name.clone().filter(|s| !s.is_empty()))
+                    .or_else(|| user.real_name.clone().filter(|s| !s.is_empty()))
+                    .unwrap_or_else(|| user.name.clone());
+                cache.insert(
+                    user.id.clone(),
+                    CachedUser {
+                        name,
+                        real_name: user.real_name.clone(),
+                        cached_at: now,
+                    },
+                );
+            }
+        }
+
+        if users.is_ok() {
+            self.save_user_cache().await;
+        }
+
+        let mut channel_count = 0;
+        if let Ok(channels) = &channels {
+            let mut cache = self.channel_cache.write().await;
+            let now = Self::current_timestamp();
+            for channel in channels {
+                if let Some(ref name) = channel.name {
+                    cache.insert(
+                        channel.id.clone(),
+                        CachedChannel {
+                            name: name.clone(),
+                            is_im: channel.is_im.unwrap_or(false),
+                            is_mpim: channel.is_mpim.unwrap_or(false),
+                            cached_at: now,
+                        },
+                    );
+                    channel_count += 1;
+                }
+            }
+        }
+
+        users?;
+        channels?;
+        Ok((user_count, channel_count))
+    }
+
     pub async fn cache_user(&self, user_id: String, user_name: String, real_name: Option<String>) {
-        let mut cache = self.user_cache.write().await;
-        cache.insert(
+        self.user_cache.write().await.insert(
             user_id,
             CachedUser {
                 name: user_name,
@@ -157,6 +739,11 @@ impl AppState {
                 cached_at: Self::current_timestamp(),
             },
         );
+
+        // Debounced: a save per call would rewrite thousands of users during users.list
+        if self.unsaved_users.fetch_add(1, Ordering::Relaxed) + 1 >= USER_CACHE_SAVE_EVERY {
+            self.save_user_cache().await;
+        }
     }
 
     pub async fn cache_channel(&self, channel_id: String, channel_name: String, is_im: bool, is_mpim: bool) {
@@ -216,53 +803,27 @@ impl AppState {
         result
     }
 
-    fn hash_search_params(
-        query: &str,
-        channel: &Option<String>,
-        user: &Option<String>,
-        from_date: &Option<String>,
-        to_date: &Option<String>,
-        limit: &Option<usize>,
-        has_files: &Option<bool>,
-        file_extensions: &Option<Vec<String>>,
-    ) -> u64 {
+    fn hash_search_params(key: &SearchCacheKey) -> u64 {
         let mut hasher = DefaultHasher::new();
-        query.hash(&mut hasher);
-        channel.hash(&mut hasher);
-        user.hash(&mut hasher);
-        from_date.hash(&mut hasher);
-        to_date.hash(&mut hasher);
-        limit.hash(&mut hasher);
-        has_files.hash(&mut hasher);
-        file_extensions.hash(&mut hasher);
+        key.hash(&mut hasher);
         hasher.finish()
     }
 
-    pub async fn get_cached_search(
-        &self,
-        query: &str,
-        channel: &Option<String>,
-        user: &Option<String>,
-        from_date: &Option<String>,
-        to_date: &Option<String>,
-        limit: &Option<usize>,
-        has_files: &Option<bool>,
-        file_extensions: &Option<Vec<String>>,
-    ) -> Option<SearchResult> {
-        let cache_key = Self::hash_search_params(query, channel, user, from_date, to_date, limit, has_files, file_extensions);
+    pub async fn get_cached_search(&self, key: &SearchCacheKey) -> Option<SearchResult> {
+        let cache_key = Self::hash_search_params(key);
         let cache = self.search_cache.read().await;
+        let query = key.query.as_str();
 
         if let Some(cached) = cache.get(&cache_key) {
             // Much shorter cache duration for live mode to ensure fresh data
             // Standard search can use longer cache
             const LIVE_MODE_CACHE_SECS: u64 = 2; // 2 seconds for live mode
-            const STANDARD_CACHE_SECS: u64 = 30; // 30 seconds for standard search
 
             // Use shorter cache if query is empty (live mode)
             let cache_duration = if query.is_empty() || query == "*" {
                 LIVE_MODE_CACHE_SECS
             } else {
-                STANDARD_CACHE_SECS
+                SEARCH_CACHE_SECS
             };
 
             let now = Self::current_timestamp();
@@ -274,23 +835,12 @@ impl AppState {
         None
     }
 
-    pub async fn cache_search_result(
-        &self,
-        query: &str,
-        channel: &Option<String>,
-        user: &Option<String>,
-        from_date: &Option<String>,
-        to_date: &Option<String>,
-        limit: &Option<usize>,
-        has_files: &Option<bool>,
-        file_extensions: &Option<Vec<String>>,
-        result: SearchResult,
-    ) {
-        let cache_key = Self::hash_search_params(query, channel, user, from_date, to_date, limit, has_files, file_extensions);
+    pub async fn cache_search_result(&self, key: &SearchCacheKey, result: SearchResult) {
+        let cache_key = Self::hash_search_params(key);
         let mut cache = self.search_cache.write().await;
 
         // Keep cache size reasonable (max 50 searches)
-        if cache.len() >= 50 {
+        if cache.len() >= MAX_CACHED_SEARCHES {
             // Remove oldest entry
             if let Some(oldest_key) = cache
                 .iter()
@@ -308,7 +858,98 @@ impl AppState {
                 cached_at: Self::current_timestamp(),
             },
         );
-        debug!("Cached search result for query: {}", query);
+        debug!("Cached search result for query: {}", key.query);
+    }
+
+    /// Serve a DM search from the search cache window, otherwise run `search` and cache it
+    pub async fn get_or_search_dm<F, Fut>(
+        &self,
+        key: &DmSearchCacheKey,
+        force_refresh: bool,
+        search: F,
+    ) -> AppResult<Vec<SlackMessage>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = AppResult<Vec<SlackMessage>>>,
+    {
+        let now = Self::current_timestamp();
+        if !force_refresh {
+            if let Some((messages, cached_at)) = self.dm_search_cache.read().await.get(key) {
+                if now.saturating_sub(*cached_at) < SEARCH_CACHE_SECS {
+                    info!("DM search cache hit for {} (cache age: {}s)", key.dm_id, now - cached_at);
+                    return Ok(messages.clone());
+                }
+            }
+        }
+
+        let messages = search().await?;
+        let mut cache = self.dm_search_cache.write().await;
+        if cache.len() >= MAX_CACHED_SEARCHES {
+            if let Some(oldest_key) = cache
+                .iter()
+                .min_by_key(|(_, (_, cached_at))| *cached_at)
+                .map(|(k, _)| k.clone())
+            {
+                cache.remove(&oldest_key);
+            }
+        }
+        cache.insert(key.clone(), (messages.clone(), now));
+        Ok(messages)
+    }
+
+    /// Keep full messages from the latest summary search for on-demand detail lookups
+    pub async fn cache_message_details(&self, messages: &[Message]) {
+        let mut cache = self.message_details.write().await;
+        cache.clear();
+        for msg in messages {
+            cache.insert(format!("{}:{}", msg.channel, msg.ts), msg.clone());
+        }
+    }
+
+    pub async fn get_message_details(&self, channel: &str, timestamp: &str) -> Option<Message> {
+        let cache = self.message_details.read().await;
+        cache.get(&format!("{}:{}", channel, timestamp)).cloned()
+    }
+
+    // Permalinks never change, so they are cached for the session
+    pub async fn get_cached_permalink(&self, channel: &str, timestamp: &str) -> Option<String> {
+        let cache = self.permalinks.read().await;
+        cache.get(&format!("{}:{}", channel, timestamp)).cloned()
+    }
+
+    pub async fn cache_permalink(&self, channel: &str, timestamp: &str, permalink: String) {
+        let mut cache = self.permalinks.write().await;
+        cache.insert(format!("{}:{}", channel, timestamp), permalink);
+    }
+
+    pub async fn get_cached_reply_info(&self, channel: &str, timestamp: &str) -> Option<ReplyInfo> {
+        let cache = self.reply_counts.read().await;
+        let (info, cached_at) = cache.get(&format!("{}:{}", channel, timestamp))?;
+        (Self::current_timestamp().saturating_sub(*cached_at) < REPLY_COUNT_CACHE_TTL_SECS)
+            .then(|| info.clone())
+    }
+
+    pub async fn cache_reply_info(&self, channel: &str, timestamp: &str, info: ReplyInfo) {
+        let mut cache = self.reply_counts.write().await;
+        cache.insert(
+            format!("{}:{}", channel, timestamp),
+            (info, Self::current_timestamp()),
+        );
+    }
+
+    /// The user's saved `(channel, ts)` keys, while younger than the TTL
+    pub async fn get_cached_saved_items(&self) -> Option<HashSet<(String, String)>> {
+        self.get_cached_saved_items_at(Self::current_timestamp()).await
+    }
+
+    async fn get_cached_saved_items_at(&self, now: u64) -> Option<HashSet<(String, String)>> {
+        let cache = self.saved_items.read().await;
+        let (saved, cached_at) = cache.as_ref()?;
+        (now.saturating_sub(*cached_at) < SAVED_ITEMS_CACHE_TTL_SECS).then(|| saved.clone())
+    }
+
+    pub async fn cache_saved_items(&self, saved: HashSet<(String, String)>) {
+        *self.saved_items.write().await = Some((saved, Self::current_timestamp()));
     }
 
     // Reaction cache methods
@@ -317,14 +958,22 @@ impl AppState {
         channel: &str,
         timestamp: &str,
     ) -> Option<Vec<SlackReaction>> {
+        self.get_cached_reactions_at(Self::current_timestamp(), channel, timestamp)
+            .await
+    }
+
+    async fn get_cached_reactions_at(
+        &self,
+        now: u64,
+        channel: &str,
+        timestamp: &str,
+    ) -> Option<Vec<SlackReaction>> {
+        let ttl_secs = *self.reaction_cache_ttl_secs.read().await;
         let cache_key = format!("{}:{}", channel, timestamp);
         let cache = self.reaction_cache.read().await;
 
         if let Some(cached) = cache.get(&cache_key) {
-            // Much shorter cache duration for reactions to see updates quickly
-            const REACTION_CACHE_DURATION_SECS: u64 = 60; // 1 minute (was 30 minutes!)
-            let now = Self::current_timestamp();
-            if now - cached.cached_at < REACTION_CACHE_DURATION_SECS {
+            if now.saturating_sub(cached.cached_at) < ttl_secs {
                 debug!("Reaction cache hit for {}:{}", channel, timestamp);
                 return Some(cached.reactions.clone());
             }
@@ -362,6 +1011,14 @@ impl AppState {
         );
     }
     
+    /// Drop the cached reactions of one message, e.g. after the user reacted to it
+    pub async fn invalidate_reactions(&self, channel: &str, timestamp: &str) {
+        let cache_key = format!("{}:{}", channel, timestamp);
+        if self.reaction_cache.write().await.remove(&cache_key).is_some() {
+            debug!("Dropped cached reactions for {}", cache_key);
+        }
+    }
+
     pub async fn clear_reaction_cache(&self) {
         let mut cache = self.reaction_cache.write().await;
         cache.clear();
@@ -373,6 +1030,7 @@ impl AppState {
         // Clear search cache for this channel
         let mut search_cache = self.search_cache.write().await;
         search_cache.clear(); // For now, clear all search cache
+        self.dm_search_cache.write().await.retain(|key, _| key.dm_id != channel);
 
         // Clear reaction cache for this channel
         if let Some(ts) = after_timestamp {
@@ -398,3 +1056,314 @@ impl Default for AppState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_user_id_fetched_only_once() {
+        let state = AppState::new();
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            let user_id = state
+                .get_or_fetch_user_id(|| async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(Some("U123".to_string()))
+                })
+                .await
+                .unwrap();
+            assert_eq!(user_id.as_deref(), Some("U123"));
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    fn empty_result(query: &str) -> SearchResult {
+        SearchResult {
+            messages: Vec::new(),
+            total: 0,
+            query: query.to_string(),
+            execution_time_ms: 0,
+            result_reason: None,
+            truncated_at_api_limit: false,
+            truncated_by_slack: false,
+            partial_enrichment: false,
+            failed_channels: Vec::new(),
+        }
+    }
+
+    async fn fetch_emoji_counting(
+        state: &AppState,
+        now: u64,
+        fetches: &std::sync::atomic::AtomicUsize,
+    ) -> HashMap<String, String> {
+        let (emoji, _) = state
+            .get_or_fetch_emoji_at(now, false, || async {
+                fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(HashMap::from([("party".to_string(), "https://emoji/party.gif".to_string())]))
+            })
+            .await
+            .unwrap();
+        emoji
+    }
+
+    #[tokio::test]
+    async fn test_emoji_cache_hit_within_ttl() {
+        let state = AppState::new();
+        let fetches = std::sync::atomic::AtomicUsize::new(0);
+
+        fetch_emoji_counting(&state, 1_000, &fetches).await;
+        let emoji = fetch_emoji_counting(&state, 1_000 + EMOJI_CACHE_TTL_SECS - 1, &fetches).await;
+
+        assert_eq!(fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(emoji.contains_key("party"));
+    }
+
+    #[tokio::test]
+    async fn test_emoji_cache_refetches_after_expiry() {
+        let state = AppState::new();
+        let fetches = std::sync::atomic::AtomicUsize::new(0);
+
+        // A persisted entry from a previous run seeds the cache
+        state
+            .load_emoji_cache(CachedEmoji {
+                emoji: HashMap::new(),
+                cached_at: 1_000,
+            })
+            .await;
+        fetch_emoji_counting(&state, 1_000 + EMOJI_CACHE_TTL_SECS, &fetches).await;
+
+        assert_eq!(fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_saved_items_cache_expires() {
+        let state = AppState::new();
+        assert!(state.get_cached_saved_items().await.is_none());
+
+        state
+            .cache_saved_items(HashSet::from([("C1".to_string(), "1.0".to_string())]))
+            .await;
+        let cached_at = AppState::current_timestamp();
+
+        let saved = state.get_cached_saved_items_at(cached_at).await.unwrap();
+        assert!(saved.contains(&("C1".to_string(), "1.0".to_string())));
+        let expired = cached_at + SAVED_ITEMS_CACHE_TTL_SECS;
+        assert!(state.get_cached_saved_items_at(expired).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reaction_cache_expires_and_invalidates_per_entry() {
+        let state = AppState::new();
+        let reactions = vec![SlackReaction {
+            name: "eyes".to_string(),
+            users: vec!["U1".to_string()],
+            count: 1,
+            users_truncated: false,
+        }];
+        state.cache_reactions("C1", "1.0", reactions.clone()).await;
+        state.cache_reactions("C1", "2.0", reactions).await;
+        let cached_at = AppState::current_timestamp();
+
+        assert!(state.get_cached_reactions_at(cached_at, "C1", "1.0").await.is_some());
+        let expired = cached_at + DEFAULT_REACTION_CACHE_TTL_SECS;
+        assert!(state.get_cached_reactions_at(expired, "C1", "1.0").await.is_none());
+
+        state.set_reaction_cache_ttl(DEFAULT_REACTION_CACHE_TTL_SECS * 2).await;
+        assert!(state.get_cached_reactions_at(expired, "C1", "1.0").await.is_some());
+
+        state.invalidate_reactions("C1", "1.0").await;
+        assert!(state.get_cached_reactions("C1", "1.0").await.is_none());
+        assert!(state.get_cached_reactions("C1", "2.0").await.is_some());
+    }
+
+    fn recent(query: &str, channel: Option<&str>, searched_at: u64) -> RecentSearch {
+        RecentSearch {
+            query: query.to_string(),
+            channel: channel.map(str::to_string),
+            user: None,
+            from_date: None,
+            to_date: None,
+            has_files: None,
+            file_extensions: None,
+            searched_at,
+        }
+    }
+
+    #[test]
+    fn test_recent_searches_dedupe_on_repeat() {
+        let mut list = Vec::new();
+        push_recent_search(&mut list, recent("deploy", None, 1), 10);
+        push_recent_search(&mut list, recent("deploy", Some("C1"), 2), 10);
+        push_recent_search(&mut list, recent(" Deploy ", None, 3), 10);
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].searched_at, 3);
+        assert_eq!(list[1].channel.as_deref(), Some("C1"));
+    }
+
+    #[test]
+    fn test_recent_searches_are_capped() {
+        let mut list = Vec::new();
+        for i in 0..5 {
+            push_recent_search(&mut list, recent(&format!("q{}", i), None, i), 3);
+        }
+
+        let queries: Vec<&str> = list.iter().map(|s| s.query.as_str()).collect();
+        assert_eq!(queries, vec!["q4", "q3", "q2"]);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_dm_search_hits_cache() {
+        let state = AppState::new();
+        let searches = AtomicUsize::new(0);
+        let key = DmSearchCacheKey {
+            dm_id: "D0123456789".to_string(),
+            query: "invoice".to_string(),
+            limit: 50,
+            fuzzy: false,
+        };
+        let search = || async {
+            searches.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        };
+
+        state.get_or_search_dm(&key, false, search).await.unwrap();
+        state.get_or_search_dm(&key, false, search).await.unwrap();
+        assert_eq!(searches.load(Ordering::SeqCst), 1);
+
+        // force_refresh bypasses the cache, and a different query is its own entry
+        state.get_or_search_dm(&key, true, search).await.unwrap();
+        let other = DmSearchCacheKey { query: "receipt".to_string(), ..key.clone() };
+        state.get_or_search_dm(&other, false, search).await.unwrap();
+        assert_eq!(searches.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_search_cache_key_includes_sort() {
+        let state = AppState::new();
+        let by_time = SearchCacheKey {
+            query: "deploy".to_string(),
+            sort: SearchSort::Timestamp,
+            ..Default::default()
+        };
+        let by_score = SearchCacheKey {
+            sort: SearchSort::Score,
+            ..by_time.clone()
+        };
+
+        assert_ne!(
+            AppState::hash_search_params(&by_time),
+            AppState::hash_search_params(&by_score)
+        );
+
+        state.cache_search_result(&by_time, empty_result("by time")).await;
+        assert_eq!(state.get_cached_search(&by_time).await.unwrap().query, "by time");
+        assert!(state.get_cached_search(&by_score).await.is_none());
+
+        state.cache_search_result(&by_score, empty_result("by score")).await;
+        assert_eq!(state.get_cached_search(&by_score).await.unwrap().query, "by score");
+        assert_eq!(state.get_cached_search(&by_time).await.unwrap().query, "by time");
+
+        let deduped = SearchCacheKey { dedupe: true, ..by_time.clone() };
+        assert!(state.get_cached_search(&deduped).await.is_none());
+
+        let with_reactions = SearchCacheKey { reactions: true, ..by_time.clone() };
+        let all_reactions = SearchCacheKey { fetch_low_reaction: true, ..with_reactions.clone() };
+        state.cache_search_result(&with_reactions, empty_result("reactions")).await;
+        assert_eq!(state.get_cached_search(&by_time).await.unwrap().query, "by time");
+        assert!(state.get_cached_search(&all_reactions).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_user_id_cleared_on_token_change() {
+        let state = AppState::new();
+        state.set_token("xoxp-first".to_string()).await.unwrap();
+        state.set_user_id("U123".to_string()).await;
+
+        state.set_token("xoxp-first".to_string()).await.unwrap();
+        assert_eq!(state.get_user_id().await.as_deref(), Some("U123"));
+
+        state.set_token("xoxp-second".to_string()).await.unwrap();
+        assert_eq!(state.get_user_id().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_client_config_resizes_the_shared_pool() {
+        let state = AppState::new();
+        let config = SlackClientConfig { max_concurrent: 4, delay_ms: 100, timeout_secs: 60 };
+
+        state.set_client_config(config).await;
+        assert_eq!(state.client_config().await, config);
+        let status = state.rate_limit_status().await;
+        assert_eq!((status.tiers[0].capacity, status.tiers[0].available), (4, 4));
+
+        state.set_client_config(SlackClientConfig { max_concurrent: 0, ..config }).await;
+        assert_eq!(state.client_config().await.max_concurrent, 1);
+        assert_eq!(state.rate_limit_status().await.tiers[0].capacity, 1);
+    }
+
+    #[tokio::test]
+    async fn test_warm_caches_populates_users_and_channels() {
+        let state = AppState::new();
+        let users: Vec<SlackUserInfo> = serde_json::from_value(serde_json::json!([
+            { "id": "U1", "name": "alice", "real_name": "Alice A", "profile": { "display_name": "ally" } },
+            { "id": "U2", "name": "bob", "profile": { "display_name": "" } }
+        ]))
+        .unwrap();
+        let channels: Vec<SlackConversation> = serde_json::from_value(serde_json::json!([
+            { "id": "C1", "name": "general", "is_channel": true },
+            { "id": "G1", "name": "mpdm-alice--bob-1", "is_mpim": true }
+        ]))
+        .unwrap();
+
+        let counts = state
+            .warm_caches(|| async { Ok(users) }, || async { Ok(channels) })
+            .await
+            .unwrap();
+        assert_eq!(counts, (2, 2));
+
+        let user_cache = state.get_user_cache().await;
+        assert_eq!(user_cache.get("U1").map(String::as_str), Some("ally"));
+        assert_eq!(user_cache.get("U2").map(String::as_str), Some("bob"));
+        let channel_cache = state.get_channel_cache_full().await;
+        assert_eq!(channel_cache.get("C1").map(|c| c.name.as_str()), Some("general"));
+        assert!(channel_cache.get("G1").unwrap().is_mpim);
+    }
+
+    #[tokio::test]
+    async fn test_user_cache_survives_restart_and_drops_stale_entries() {
+        let path = std::env::temp_dir().join(format!("psc-user-cache-{}.json", std::process::id()));
+        let now = AppState::current_timestamp();
+        let saved = HashMap::from([
+            (
+                "U1".to_string(),
+                CachedUser { name: "alice".to_string(), real_name: None, cached_at: now - 3600 },
+            ),
+            (
+                "U2".to_string(),
+                CachedUser { name: "bob".to_string(), real_name: None, cached_at: now - 8 * 86400 },
+            ),
+        ]);
+        std::fs::write(&path, serde_json::to_vec(&saved).unwrap()).unwrap();
+
+        let state = AppState::new();
+        assert_eq!(state.attach_user_cache_file(path.clone()).await, 1);
+        let names = state.get_user_cache().await;
+        assert_eq!(names.get("U1").map(String::as_str), Some("alice"));
+        assert!(!names.contains_key("U2"));
+
+        // A save round-trips into the next run, compressed like the other caches
+        state.cache_user("U3".to_string(), "carol".to_string(), None).await;
+        state.set_user_cache_compression(true);
+        state.save_user_cache().await;
+        assert!(std::fs::read_to_string(&path).unwrap().contains("\"gzip\""));
+        let next_run = AppState::new();
+        assert_eq!(next_run.attach_user_cache_file(path.clone()).await, 2);
+
+        next_run.clear_user_cache_disk().await.unwrap();
+        assert!(!path.exists());
+    }
+}